@@ -0,0 +1,257 @@
+use crate::error::{RecentEnablerError, Result};
+use rusqlite::{types::ValueRef, Connection, OpenFlags, Row};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// `AppId` platforms in order of preference for display
+const APP_PLATFORMS: &[&str] = &[
+    "x_exe_path",
+    "windows_win32",
+    "windows_universal",
+    "packageId",
+];
+
+const ACTIVITY_COLUMNS: &str = "AppId, ActivityType, Payload, StartTime, EndTime, LastModifiedTime";
+
+/// `ActivityType` of "app in focus" records
+pub const ACTIVITY_TYPE_IN_FOCUS: i64 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivitySource {
+    /// Synced activity history
+    Activity,
+    /// Pending operations not yet merged into `Activity`
+    ActivityOperation,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    pub source: ActivitySource,
+    pub app: String,
+    pub display_text: Option<String>,
+    pub activity_type: i64,
+    pub start_time: Option<SystemTime>,
+    pub end_time: Option<SystemTime>,
+    pub focus_duration: Option<Duration>,
+    pub last_modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActivityPolicies {
+    pub enable_activity_feed: Option<u32>,
+    pub publish_user_activities: Option<u32>,
+    pub upload_user_activities: Option<u32>,
+}
+
+impl ActivityPolicies {
+    /// Local history is recorded unless the feed or publishing is turned off
+    #[must_use]
+    pub const fn is_disabled(&self) -> bool {
+        matches!(self.enable_activity_feed, Some(0))
+            || matches!(self.publish_user_activities, Some(0))
+    }
+
+    #[must_use]
+    pub const fn is_upload_enabled(&self) -> bool {
+        matches!(self.upload_user_activities, Some(v) if v != 0)
+    }
+}
+
+/// Pick the most descriptive application name from an `AppId` JSON array
+#[must_use]
+pub fn parse_app_id(app_id: &str) -> Option<String> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(app_id).ok()?;
+    let application = |platform: Option<&str>| {
+        entries
+            .iter()
+            .filter(|e| platform.is_none() || e["platform"].as_str() == platform)
+            .find_map(|e| e["application"].as_str().filter(|s| !s.is_empty()))
+            .map(str::to_string)
+    };
+
+    APP_PLATFORMS
+        .iter()
+        .find_map(|platform| application(Some(platform)))
+        .or_else(|| application(None))
+}
+
+fn unix_time(secs: Option<i64>) -> Option<SystemTime> {
+    let secs = u64::try_from(secs?).ok().filter(|&s| s > 0)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+/// Read a column stored as either TEXT or BLOB
+fn text_column(row: &Row<'_>, index: usize) -> rusqlite::Result<Option<String>> {
+    Ok(match row.get_ref(index)? {
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => {
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+        _ => None,
+    })
+}
+
+fn parse_row(row: &Row<'_>, source: ActivitySource) -> rusqlite::Result<ActivityEntry> {
+    let app_id = text_column(row, 0)?.unwrap_or_default();
+    let activity_type: i64 = row.get::<_, Option<i64>>(1)?.unwrap_or_default();
+    let payload: Option<serde_json::Value> = text_column(row, 2)?
+        .as_deref()
+        .and_then(|p| serde_json::from_str(p).ok());
+    let start_time = unix_time(row.get(3)?);
+    let end_time = unix_time(row.get(4)?);
+
+    let payload_text = |key: &str| {
+        payload
+            .as_ref()
+            .and_then(|p| p[key].as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let active_seconds = payload
+        .as_ref()
+        .and_then(|p| p["activeDurationSeconds"].as_u64());
+    let focus_duration = active_seconds.map(Duration::from_secs).or_else(|| {
+        (activity_type == ACTIVITY_TYPE_IN_FOCUS)
+            .then(|| end_time?.duration_since(start_time?).ok())
+            .flatten()
+    });
+
+    Ok(ActivityEntry {
+        source,
+        app: parse_app_id(&app_id).unwrap_or(app_id),
+        display_text: payload_text("displayText").or_else(|| payload_text("appDisplayName")),
+        activity_type,
+        start_time,
+        end_time,
+        focus_duration,
+        last_modified: unix_time(row.get(5)?),
+    })
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+}
+
+/// Parse the `Activity` and `ActivityOperation` tables of an open database
+///
+/// Entries are returned sorted by start time, newest first.
+///
+/// # Errors
+///
+/// Returns error if the tables cannot be queried
+pub fn parse_activities(conn: &Connection) -> Result<Vec<ActivityEntry>> {
+    let read_failed = |e: rusqlite::Error| RecentEnablerError::ActivitiesReadFailed(e.to_string());
+    let mut entries = Vec::new();
+
+    for (table, source) in [
+        ("Activity", ActivitySource::Activity),
+        ("ActivityOperation", ActivitySource::ActivityOperation),
+    ] {
+        if !table_exists(conn, table).map_err(read_failed)? {
+            continue;
+        }
+        let mut statement = conn
+            .prepare(&format!("SELECT {ACTIVITY_COLUMNS} FROM {table}"))
+            .map_err(read_failed)?;
+        let rows = statement
+            .query_map([], |row| parse_row(row, source))
+            .map_err(read_failed)?;
+        for entry in rows {
+            entries.push(entry.map_err(read_failed)?);
+        }
+    }
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.start_time));
+    Ok(entries)
+}
+
+/// Open an `ActivitiesCache.db` read-only and parse its activities
+///
+/// # Errors
+///
+/// Returns error if the database cannot be opened or parsed
+pub fn read_activities(path: &Path) -> Result<Vec<ActivityEntry>> {
+    let conn =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| {
+            RecentEnablerError::ActivitiesReadFailed(format!("{}: {e}", path.display()))
+        })?;
+    parse_activities(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "
+        CREATE TABLE Activity (Id BLOB, AppId TEXT, ActivityType INT, Payload BLOB,
+            StartTime INT, EndTime INT, LastModifiedTime INT);
+        CREATE TABLE ActivityOperation (Id BLOB, AppId TEXT, ActivityType INT, Payload BLOB,
+            StartTime INT, EndTime INT, LastModifiedTime INT, OperationType INT);
+    ";
+
+    #[test]
+    fn test_parse_app_id() {
+        let app_id = r#"[{"application":"Microsoft.Windows.Explorer","platform":"windows_win32"},
+            {"application":"C:\\Tools\\nc.exe","platform":"x_exe_path"}]"#;
+        assert_eq!(parse_app_id(app_id).as_deref(), Some(r"C:\Tools\nc.exe"));
+        assert_eq!(
+            parse_app_id(r#"[{"application":"app","platform":"other"}]"#).as_deref(),
+            Some("app")
+        );
+        assert!(parse_app_id("not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_activities() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO Activity VALUES (x'01', ?1, 5, ?2, 1609459200, 0, 1609459300)",
+            [
+                r#"[{"application":"notepad.exe","platform":"windows_win32"}]"#,
+                r#"{"displayText":"notes.txt","activeDurationSeconds":42}"#,
+            ],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO ActivityOperation VALUES (x'02', ?1, 6, NULL, 1609462800, 1609462890, 0, 1)",
+            [r#"[{"application":"cmd.exe","platform":"windows_win32"}]"#],
+        )
+        .unwrap();
+
+        let entries = parse_activities(&conn).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let focus = &entries[0];
+        assert_eq!(focus.source, ActivitySource::ActivityOperation);
+        assert_eq!(focus.app, "cmd.exe");
+        assert_eq!(focus.focus_duration, Some(Duration::from_secs(90)));
+        assert!(focus.display_text.is_none());
+        assert!(focus.last_modified.is_none());
+
+        let opened = &entries[1];
+        assert_eq!(opened.app, "notepad.exe");
+        assert_eq!(opened.display_text.as_deref(), Some("notes.txt"));
+        assert_eq!(opened.focus_duration, Some(Duration::from_secs(42)));
+        assert!(opened.end_time.is_none());
+    }
+
+    #[test]
+    fn test_policies() {
+        let mut policies = ActivityPolicies::default();
+        assert!(!policies.is_disabled());
+        policies.publish_user_activities = Some(0);
+        assert!(policies.is_disabled());
+        policies.upload_user_activities = Some(1);
+        assert!(policies.is_upload_enabled());
+    }
+}
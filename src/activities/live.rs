@@ -0,0 +1,86 @@
+use super::*;
+
+use crate::utils;
+use std::path::PathBuf;
+use winreg::enums::HKEY_LOCAL_MACHINE;
+
+const POLICY_PATH: &str = r"SOFTWARE\Policies\Microsoft\Windows\System";
+
+const ENABLE_ACTIVITY_FEED: &str = "EnableActivityFeed";
+
+const PUBLISH_USER_ACTIVITIES: &str = "PublishUserActivities";
+
+const UPLOAD_USER_ACTIVITIES: &str = "UploadUserActivities";
+
+const DATABASE_NAME: &str = "ActivitiesCache.db";
+
+/// Read the activity history policies
+#[must_use]
+pub fn get_activity_policies() -> ActivityPolicies {
+    let read = |name| utils::read_reg_dword(HKEY_LOCAL_MACHINE, POLICY_PATH, name);
+    ActivityPolicies {
+        enable_activity_feed: read(ENABLE_ACTIVITY_FEED),
+        publish_user_activities: read(PUBLISH_USER_ACTIVITIES),
+        upload_user_activities: read(UPLOAD_USER_ACTIVITIES),
+    }
+}
+
+/// Check if local activity history is disabled by policy
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_activities_disabled() -> Result<bool> {
+    Ok(get_activity_policies().is_disabled())
+}
+
+/// Enable local activity history, keeping cloud upload off
+///
+/// # Errors
+///
+/// Returns error if the policy key cannot be written
+pub fn enable_activities() -> Result {
+    for (name, value) in [
+        (ENABLE_ACTIVITY_FEED, 1),
+        (PUBLISH_USER_ACTIVITIES, 1),
+        (UPLOAD_USER_ACTIVITIES, 0),
+    ] {
+        utils::write_reg_dword(HKEY_LOCAL_MACHINE, POLICY_PATH, name, value)
+            .map_err(|e| RecentEnablerError::ActivitiesEnableFailed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Get the `ConnectedDevicesPlatform` folder of the current user
+///
+/// # Errors
+///
+/// Returns error if LOCALAPPDATA environment variable is not set
+pub fn get_platform_folder() -> Result<PathBuf> {
+    let local_app_data = std::env::var("LOCALAPPDATA").map_err(|e| {
+        RecentEnablerError::ActivitiesReadFailed(format!("LOCALAPPDATA not found: {e}"))
+    })?;
+    Ok(PathBuf::from(local_app_data).join("ConnectedDevicesPlatform"))
+}
+
+/// Find `ActivitiesCache.db` files, one per signed-in account
+///
+/// # Errors
+///
+/// Returns error if the platform folder exists but cannot be read
+pub fn get_activities_databases() -> Result<Vec<PathBuf>> {
+    let folder = get_platform_folder()?;
+    let entries = match std::fs::read_dir(&folder) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(RecentEnablerError::ActivitiesReadFailed(e.to_string())),
+    };
+
+    let mut databases: Vec<PathBuf> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path().join(DATABASE_NAME))
+        .filter(|path| path.is_file())
+        .collect();
+    databases.sort();
+    Ok(databases)
+}
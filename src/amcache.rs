@@ -0,0 +1,280 @@
+use crate::{
+    error::{RecentEnablerError, Result},
+    hive::{Hive, HiveKey},
+};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const FILES_PATH: &str = "Root\\InventoryApplicationFile";
+const APPLICATIONS_PATH: &str = "Root\\InventoryApplication";
+
+#[derive(Debug, Clone)]
+pub struct AmcacheFileEntry {
+    pub key_name: String,
+    /// `LowerCaseLongPath` of the executable
+    pub path: String,
+    /// SHA-1 of the file, taken from `FileId` without its `0000` prefix
+    pub sha1: Option<String>,
+    /// PE header link (compile) time
+    pub link_date: Option<SystemTime>,
+    pub publisher: Option<String>,
+    pub product_name: Option<String>,
+    pub version: Option<String>,
+    pub size: Option<u64>,
+    pub program_id: Option<String>,
+    pub last_write: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AmcacheApplicationEntry {
+    pub program_id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub publisher: Option<String>,
+    pub install_date: Option<SystemTime>,
+    pub root_dir_path: Option<String>,
+    pub source: Option<String>,
+    pub last_write: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AmcacheInventory {
+    pub files: Vec<AmcacheFileEntry>,
+    pub applications: Vec<AmcacheApplicationEntry>,
+    /// Entries left out because their values couldn't be read
+    pub skipped: usize,
+}
+
+impl AmcacheInventory {
+    /// Sort both lists by key last-write time, newest first
+    pub fn sort_by_last_write(&mut self) {
+        self.files.sort_by_key(|e| std::cmp::Reverse(e.last_write));
+        self.applications
+            .sort_by_key(|e| std::cmp::Reverse(e.last_write));
+    }
+}
+
+/// Get the path to `Amcache.hve`
+///
+/// # Errors
+///
+/// Returns error if SystemRoot/windir environment variables are not set
+pub fn get_amcache_path() -> Result<PathBuf> {
+    let windows_dir = std::env::var("SystemRoot")
+        .or_else(|_| std::env::var("windir"))
+        .map_err(|e| {
+            RecentEnablerError::WindowsPathNotFound(format!("SystemRoot/windir not found: {e}"))
+        })?;
+    Ok(PathBuf::from(windows_dir)
+        .join("AppCompat")
+        .join("Programs")
+        .join("Amcache.hve"))
+}
+
+/// Parse Amcache date strings (`MM/dd/yyyy HH:mm:ss`, UTC)
+#[must_use]
+pub fn parse_amcache_date(value: &str) -> Option<SystemTime> {
+    let datetime = chrono::NaiveDateTime::parse_from_str(value.trim(), "%m/%d/%Y %H:%M:%S")
+        .or_else(|_| {
+            chrono::NaiveDate::parse_from_str(value.trim(), "%m/%d/%Y")
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .ok()?;
+    let secs = u64::try_from(datetime.and_utc().timestamp()).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+fn string_value(key: &HiveKey<'_>, name: &str) -> Result<Option<String>> {
+    Ok(key
+        .value(name)?
+        .and_then(|v| v.as_string())
+        .filter(|s| !s.is_empty()))
+}
+
+fn parse_file_entry(key: &HiveKey<'_>) -> Result<AmcacheFileEntry> {
+    let sha1 = string_value(key, "FileId")?
+        .map(|id| id.strip_prefix("0000").unwrap_or(&id).to_ascii_lowercase());
+
+    Ok(AmcacheFileEntry {
+        key_name: key.name().to_string(),
+        path: string_value(key, "LowerCaseLongPath")?.unwrap_or_default(),
+        sha1,
+        link_date: string_value(key, "LinkDate")?
+            .as_deref()
+            .and_then(parse_amcache_date),
+        publisher: string_value(key, "Publisher")?,
+        product_name: string_value(key, "ProductName")?,
+        version: string_value(key, "Version")?,
+        size: key.value("Size")?.and_then(|v| v.as_u64()),
+        program_id: string_value(key, "ProgramId")?,
+        last_write: key.last_written(),
+    })
+}
+
+fn parse_application_entry(key: &HiveKey<'_>) -> Result<AmcacheApplicationEntry> {
+    Ok(AmcacheApplicationEntry {
+        program_id: key.name().to_string(),
+        name: string_value(key, "Name")?,
+        version: string_value(key, "Version")?,
+        publisher: string_value(key, "Publisher")?,
+        install_date: string_value(key, "InstallDate")?
+            .as_deref()
+            .and_then(parse_amcache_date),
+        root_dir_path: string_value(key, "RootDirPath")?,
+        source: string_value(key, "Source")?,
+        last_write: key.last_written(),
+    })
+}
+
+/// Parse the file and application inventories of an Amcache hive
+///
+/// Entries are returned sorted by key last-write time, newest first. An entry
+/// with corrupted values is counted in `skipped` instead of failing the rest.
+///
+/// # Errors
+///
+/// Returns error if the hive structure is corrupted
+pub fn parse_amcache(hive: &Hive) -> Result<AmcacheInventory> {
+    let root = hive.root()?;
+    let mut inventory = AmcacheInventory::default();
+
+    if let Some(files) = root.open(FILES_PATH)? {
+        for key in files.subkeys()? {
+            match parse_file_entry(&key) {
+                Ok(entry) => inventory.files.push(entry),
+                Err(_) => inventory.skipped += 1,
+            }
+        }
+    }
+    if let Some(applications) = root.open(APPLICATIONS_PATH)? {
+        for key in applications.subkeys()? {
+            match parse_application_entry(&key) {
+                Ok(entry) => inventory.applications.push(entry),
+                Err(_) => inventory.skipped += 1,
+            }
+        }
+    }
+
+    inventory.sort_by_last_write();
+    Ok(inventory)
+}
+
+/// Read and parse an `Amcache.hve` file
+///
+/// The live hive is locked by the system, so this usually needs a copy
+/// (for example from a triage collection); on a live system
+/// [`crate::vss::read_locked_file`] plus [`decode_amcache`] handles that.
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or parsed
+pub fn read_amcache(path: &Path) -> Result<AmcacheInventory> {
+    let hive =
+        Hive::open(path).map_err(|e| RecentEnablerError::AmcacheReadFailed(e.to_string()))?;
+    parse_amcache(&hive).map_err(|e| RecentEnablerError::AmcacheReadFailed(e.to_string()))
+}
+
+/// Decode the raw bytes of an `Amcache.hve` file
+///
+/// # Errors
+///
+/// Returns error if the data is not a readable hive
+pub fn decode_amcache(data: Vec<u8>) -> Result<AmcacheInventory> {
+    let hive =
+        Hive::from_bytes(data).map_err(|e| RecentEnablerError::AmcacheReadFailed(e.to_string()))?;
+    parse_amcache(&hive).map_err(|e| RecentEnablerError::AmcacheReadFailed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hive::test_support::HiveBuilder;
+
+    fn build_amcache() -> Hive {
+        let mut b = HiveBuilder::new();
+
+        let path = b.string_value("LowerCaseLongPath", r"c:\tools\old.exe");
+        let old = b.key("0000aaaa", 132_000_000_000_000_000, &[], &[path]);
+
+        let path = b.string_value("LowerCaseLongPath", r"c:\tools\new.exe");
+        let file_id = b.string_value("FileId", "0000A94A8FE5CCB19BA61C4C0873D391E987982FBBD3");
+        let link = b.string_value("LinkDate", "06/15/2020 12:30:20");
+        let publisher = b.string_value("Publisher", "contoso");
+        let size = b.value("Size", crate::hive::REG_QWORD, &4096u64.to_le_bytes());
+        let new = b.key(
+            "0000bbbb",
+            132_539_328_000_000_000,
+            &[],
+            &[path, file_id, link, publisher, size],
+        );
+        let files = b.key("InventoryApplicationFile", 0, &[old, new], &[]);
+
+        let name = b.string_value("Name", "Contoso Tools");
+        let app = b.key("0000cccc", 132_539_328_000_000_000, &[], &[name]);
+        let apps = b.key("InventoryApplication", 0, &[app], &[]);
+
+        let root_key = b.key("Root", 0, &[files, apps], &[]);
+        let hive_root = b.key(
+            "{11517B7C-E79D-4E20-961B-75A811715ADD}",
+            0,
+            &[root_key],
+            &[],
+        );
+        Hive::from_bytes(b.finish(hive_root)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_amcache() {
+        let inventory = parse_amcache(&build_amcache()).unwrap();
+        assert_eq!(inventory.files.len(), 2);
+        assert_eq!(inventory.applications.len(), 1);
+
+        let newest = &inventory.files[0];
+        assert_eq!(newest.path, r"c:\tools\new.exe");
+        assert_eq!(
+            newest.sha1.as_deref(),
+            Some("a94a8fe5ccb19ba61c4c0873d391e987982fbbd3")
+        );
+        assert_eq!(newest.publisher.as_deref(), Some("contoso"));
+        assert_eq!(newest.size, Some(4096));
+        assert_eq!(
+            newest
+                .link_date
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            1_592_224_220
+        );
+        assert_eq!(inventory.files[1].path, r"c:\tools\old.exe");
+        assert_eq!(
+            inventory.applications[0].name.as_deref(),
+            Some("Contoso Tools")
+        );
+    }
+
+    #[test]
+    fn test_parse_amcache_skips_corrupt_entries() {
+        let mut b = HiveBuilder::new();
+        let path = b.string_value("LowerCaseLongPath", r"c:\tools\good.exe");
+        let good = b.key("0000aaaa", 0, &[], &[path]);
+        // Value list pointing outside the hive
+        let bad = b.key("0000bbbb", 0, &[], &[0x00FF_FFF0]);
+        let files = b.key("InventoryApplicationFile", 0, &[good, bad], &[]);
+        let root_key = b.key("Root", 0, &[files], &[]);
+        let hive_root = b.key("{hive}", 0, &[root_key], &[]);
+        let hive = Hive::from_bytes(b.finish(hive_root)).unwrap();
+
+        let inventory = parse_amcache(&hive).unwrap();
+        assert_eq!(inventory.files.len(), 1);
+        assert_eq!(inventory.files[0].path, r"c:\tools\good.exe");
+        assert_eq!(inventory.skipped, 1);
+    }
+
+    #[test]
+    fn test_parse_amcache_date() {
+        assert!(parse_amcache_date("01/02/2021 03:04:05").is_some());
+        assert!(parse_amcache_date("01/02/2021").is_some());
+        assert!(parse_amcache_date("not a date").is_none());
+    }
+}
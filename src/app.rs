@@ -1,148 +1,511 @@
+use crate::history::{History, Outcome};
 use crate::ui;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
 use iced::widget::{button, column, container, row, scrollable, space, text};
-use iced::{Element, Fill, Task};
-use recent_enabler::{service, status, utils, RecentEnablerError};
+use iced::{Element, Fill, Subscription, Task};
+use recent_enabler::broker::{self, Operation};
+use recent_enabler::token::{AccessLevel, TokenInfo};
+use recent_enabler::watcher::{self, FolderChange, WatchedFolder};
+use recent_enabler::{report, service, status, utils, RecentEnablerError};
+use std::time::Duration;
+
+/// How long the folders must stay quiet before the cards are refreshed
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Longest a steady stream of changes can postpone the refresh
+const WATCH_MAX_DELAY: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub enum Message {
     EnableRecent,
+    EnableUserAssist,
     EnableSysMain,
+    EnableLastAccess,
     EnableSystemRestore,
     Refresh,
+    ExportReport,
+    ReportExported(Result<std::path::PathBuf, RecentEnablerError>),
+    CopyHistory,
+    ExportHistory,
+    HistoryExported(Result<std::path::PathBuf, RecentEnablerError>),
     RecentChecked(Result<status::RecentStatus, RecentEnablerError>),
+    /// Result of a refresh started by the folder watcher, not kept in history
+    RecentRefreshed(Result<status::RecentStatus, RecentEnablerError>),
+    UserAssistChecked(Result<status::UserAssistStatus, RecentEnablerError>),
     SysMainChecked(Result<status::SysMainStatus, RecentEnablerError>),
+    SysMainRefreshed(Result<status::SysMainStatus, RecentEnablerError>),
+    LastAccessChecked(Result<status::LastAccessStatus, RecentEnablerError>),
     SystemRestoreChecked(Result<status::SystemRestoreStatus, RecentEnablerError>),
     RecentEnabled(Result<(), RecentEnablerError>),
+    UserAssistEnabled(Result<(), RecentEnablerError>),
     SysMainEnabled(Result<(), RecentEnablerError>),
+    LastAccessEnabled(Result<(), RecentEnablerError>),
     SystemRestoreEnabled(Result<(), RecentEnablerError>),
+    FilesChanged(Vec<FolderChange>),
+    WatchFailed(RecentEnablerError),
     OpenRecentFolder,
     OpenPrefetchFolder,
-    RestartAsAdmin,
+    RestartAsAdmin(Option<Operation>),
+    ConfirmPendingAction,
+    CancelPendingAction,
+    PrivilegedDone(Operation, Result<(), RecentEnablerError>),
+}
+
+/// Status shown by one card, with its own loading flag and last error
+///
+/// The last successful status stays visible while a refresh is running or
+/// after it failed.
+pub struct Card<T> {
+    pub status: Option<T>,
+    pub loading: bool,
+    pub error: Option<String>,
+    /// Files changed while a check was running; refresh once more after it
+    pub refresh_queued: bool,
+}
+
+impl<T> Default for Card<T> {
+    fn default() -> Self {
+        Self {
+            status: None,
+            loading: true,
+            error: None,
+            refresh_queued: false,
+        }
+    }
+}
+
+impl<T> Card<T> {
+    fn start(&mut self) {
+        self.loading = true;
+    }
+
+    fn finish(&mut self, result: Result<T, RecentEnablerError>) {
+        self.loading = false;
+        match result {
+            Ok(status) => {
+                self.status = Some(status);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_russian()),
+        }
+    }
+
+    fn fail(&mut self, error: &RecentEnablerError) {
+        self.loading = false;
+        self.error = Some(error.to_russian());
+    }
 }
 
 #[derive(Default)]
 pub struct State {
-    pub recent_status: Option<status::RecentStatus>,
-    pub sysmain_status: Option<status::SysMainStatus>,
-    pub system_restore_status: Option<status::SystemRestoreStatus>,
-    pub status_message: String,
+    pub recent: Card<status::RecentStatus>,
+    pub userassist: Card<status::UserAssistStatus>,
+    pub sysmain: Card<status::SysMainStatus>,
+    pub last_access: Card<status::LastAccessStatus>,
+    pub system_restore: Card<status::SystemRestoreStatus>,
+    pub recent_change: Option<FolderChange>,
+    pub prefetch_change: Option<FolderChange>,
+    pub history: History,
+    /// A report is being built; the export button is disabled until it's written
+    pub exporting_report: bool,
+    /// Action requested before restarting as administrator, awaiting confirmation
+    pub pending_action: Option<Operation>,
     pub is_admin: bool,
+    /// The elevated helper couldn't be started; cards offer a restart instead
+    pub broker_unavailable: bool,
+    /// Why the process isn't elevated, when the token could be queried
+    pub access_level: Option<AccessLevel>,
+}
+
+/// How a card runs an operation that needs administrator rights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Elevation {
+    /// Already elevated, run in-process
+    Admin,
+    /// Run in the elevated helper
+    Broker,
+    /// Restart elevated and resume the operation there
+    Restart,
 }
 
 impl State {
+    fn elevation(&self) -> Elevation {
+        if self.is_admin {
+            Elevation::Admin
+        } else if self.broker_unavailable {
+            Elevation::Restart
+        } else {
+            Elevation::Broker
+        }
+    }
+
+    /// Fall back to restarting once the helper fails to start or drops out
+    fn note_privileged_result(&mut self, result: &Result<(), RecentEnablerError>) {
+        if matches!(result, Err(RecentEnablerError::BrokerFailed(_))) {
+            self.broker_unavailable = true;
+        }
+    }
+
     pub fn new() -> Self {
         Self {
             is_admin: utils::is_admin(),
+            access_level: TokenInfo::current().ok().map(|info| info.access_level()),
             ..Default::default()
         }
     }
 }
 
-pub fn init() -> (State, Task<Message>) {
-    (
-        State::new(),
-        Task::batch(vec![
-            Task::perform(async { service::check_recent() }, Message::RecentChecked),
-            Task::perform(async { service::check_sysmain() }, Message::SysMainChecked),
-            Task::perform(
-                async { service::check_system_restore() },
-                Message::SystemRestoreChecked,
-            ),
-        ]),
+pub fn init(pending_action: Option<Operation>) -> (State, Task<Message>) {
+    let mut state = State {
+        pending_action,
+        ..State::new()
+    };
+    let task = check_all(&mut state);
+    (state, task)
+}
+
+fn check_all(state: &mut State) -> Task<Message> {
+    state.recent.start();
+    state.userassist.start();
+    state.sysmain.start();
+    state.last_access.start();
+    state.system_restore.start();
+    Task::batch(vec![
+        Task::perform(async { service::check_recent() }, Message::RecentChecked),
+        Task::perform(
+            async { service::check_userassist() },
+            Message::UserAssistChecked,
+        ),
+        Task::perform(async { service::check_sysmain() }, Message::SysMainChecked),
+        Task::perform(
+            async { service::check_last_access() },
+            Message::LastAccessChecked,
+        ),
+        Task::perform(
+            async { service::check_system_restore() },
+            Message::SystemRestoreChecked,
+        ),
+    ])
+}
+
+fn check_recent(state: &mut State) -> Task<Message> {
+    state.recent.start();
+    Task::perform(async { service::check_recent() }, Message::RecentChecked)
+}
+
+fn check_sysmain(state: &mut State) -> Task<Message> {
+    state.sysmain.start();
+    Task::perform(async { service::check_sysmain() }, Message::SysMainChecked)
+}
+
+/// Background refresh after Recent changed, coalesced with a running check
+fn refresh_recent(state: &mut State) -> Task<Message> {
+    if state.recent.loading {
+        state.recent.refresh_queued = true;
+        return Task::none();
+    }
+    state.recent.start();
+    Task::perform(async { service::check_recent() }, Message::RecentRefreshed)
+}
+
+fn refresh_sysmain(state: &mut State) -> Task<Message> {
+    if state.sysmain.loading {
+        state.sysmain.refresh_queued = true;
+        return Task::none();
+    }
+    state.sysmain.start();
+    Task::perform(
+        async { service::check_sysmain() },
+        Message::SysMainRefreshed,
     )
 }
 
+fn finish_recent(
+    state: &mut State,
+    result: Result<status::RecentStatus, RecentEnablerError>,
+) -> Task<Message> {
+    state.recent.finish(result);
+    if std::mem::take(&mut state.recent.refresh_queued) {
+        refresh_recent(state)
+    } else {
+        Task::none()
+    }
+}
+
+fn finish_sysmain(
+    state: &mut State,
+    result: Result<status::SysMainStatus, RecentEnablerError>,
+) -> Task<Message> {
+    state.sysmain.finish(result);
+    if std::mem::take(&mut state.sysmain.refresh_queued) {
+        refresh_sysmain(state)
+    } else {
+        Task::none()
+    }
+}
+
+pub fn subscription(_state: &State) -> Subscription<Message> {
+    Subscription::run(watch_folders)
+}
+
+/// Forward Recent and Prefetch file changes, one message per burst
+fn watch_folders() -> impl Stream<Item = Message> {
+    iced::stream::channel(100, async |mut output: mpsc::Sender<Message>| {
+        let (sender, mut changes) = mpsc::unbounded();
+        let (_watcher, errors) = match watcher::watch_folders(move |change| {
+            let _ = sender.unbounded_send(change);
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                let _ = output.send(Message::WatchFailed(e)).await;
+                return;
+            }
+        };
+
+        // Prefetch can only be watched with administrator rights, the card already says so
+        let is_admin = utils::is_admin();
+        for (folder, e) in errors {
+            if folder == WatchedFolder::Recent || is_admin {
+                let _ = output.send(Message::WatchFailed(e)).await;
+            }
+        }
+
+        while let Some(change) = changes.next().await {
+            // Wait for the burst to settle, but refresh at least every WATCH_MAX_DELAY
+            let deadline = tokio::time::Instant::now() + WATCH_MAX_DELAY;
+            let mut batch = vec![change];
+            loop {
+                let quiet = WATCH_DEBOUNCE
+                    .min(deadline.saturating_duration_since(tokio::time::Instant::now()));
+                match tokio::time::timeout(quiet, changes.next()).await {
+                    Ok(Some(change)) => batch.push(change),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+            if output.send(Message::FilesChanged(batch)).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
 #[allow(clippy::too_many_lines)]
 pub fn update(state: &mut State, message: Message) -> Task<Message> {
     match message {
-        Message::Refresh => Task::batch(vec![
-            Task::perform(async { service::check_recent() }, Message::RecentChecked),
-            Task::perform(async { service::check_sysmain() }, Message::SysMainChecked),
+        Message::Refresh => check_all(state),
+        Message::ExportReport => {
+            if state.exporting_report {
+                return Task::none();
+            }
+            state.exporting_report = true;
             Task::perform(
-                async { service::check_system_restore() },
-                Message::SystemRestoreChecked,
-            ),
-        ]),
+                async {
+                    let report = report::build_report();
+                    let path = report::default_report_path(&report);
+                    report.write_html(&path).map(|()| path)
+                },
+                Message::ReportExported,
+            )
+        }
+        Message::ReportExported(result) => {
+            state.exporting_report = false;
+            if let Ok(path) = &result {
+                let _ = std::process::Command::new("explorer").arg(path).spawn();
+            }
+            let details = result
+                .as_ref()
+                .map(|path| format!("Отчёт сохранён: {}", path.display()))
+                .unwrap_or_default();
+            state.history.record("Экспорт отчёта", &result, &details);
+            Task::none()
+        }
+        Message::CopyHistory => iced::clipboard::write(state.history.to_text()),
+        Message::ExportHistory => {
+            let text = state.history.to_text();
+            Task::perform(
+                async move {
+                    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+                    let path = report::desktop_folder().join(format!("history-{stamp}.txt"));
+                    std::fs::write(&path, text)
+                        .map(|()| path)
+                        .map_err(|e| RecentEnablerError::HistoryExportFailed(e.to_string()))
+                },
+                Message::HistoryExported,
+            )
+        }
+        Message::HistoryExported(result) => {
+            let details = result
+                .as_ref()
+                .map(|path| format!("История сохранена: {}", path.display()))
+                .unwrap_or_default();
+            state.history.record("Экспорт истории", &result, &details);
+            Task::none()
+        }
         Message::EnableRecent => {
             Task::perform(async { service::enable_recent() }, Message::RecentEnabled)
         }
-        Message::EnableSysMain => {
-            Task::perform(async { service::enable_sysmain() }, Message::SysMainEnabled)
-        }
+        Message::EnableUserAssist => Task::perform(
+            async { service::enable_userassist() },
+            Message::UserAssistEnabled,
+        ),
+        Message::EnableSysMain => Task::perform(
+            async { broker::run_privileged(Operation::EnableSysMain) },
+            Message::SysMainEnabled,
+        ),
+        Message::EnableLastAccess => Task::perform(
+            async { broker::run_privileged(Operation::EnableLastAccess) },
+            Message::LastAccessEnabled,
+        ),
         Message::EnableSystemRestore => Task::perform(
-            async { service::enable_system_restore() },
+            async { broker::run_privileged(Operation::EnableSystemRestore) },
             Message::SystemRestoreEnabled,
         ),
         Message::RecentChecked(result) => {
-            match result {
-                Ok(status) => {
-                    state.recent_status = Some(status);
-                    state.status_message.clear();
-                }
-                Err(e) => state.status_message = format!("Ошибка Recent: {}", e.to_russian()),
-            }
+            state.history.record("Проверка Recent", &result, "OK");
+            finish_recent(state, result)
+        }
+        Message::RecentRefreshed(result) => finish_recent(state, result),
+        Message::UserAssistChecked(result) => {
+            state.history.record("Проверка UserAssist", &result, "OK");
+            state.userassist.finish(result);
             Task::none()
         }
         Message::SysMainChecked(result) => {
+            state.history.record("Проверка Prefetch", &result, "OK");
+            finish_sysmain(state, result)
+        }
+        Message::SysMainRefreshed(result) => finish_sysmain(state, result),
+        Message::LastAccessChecked(result) => {
+            state
+                .history
+                .record("Проверка времени доступа", &result, "OK");
+            state.last_access.finish(result);
+            Task::none()
+        }
+        Message::SystemRestoreChecked(result) => {
+            state
+                .history
+                .record("Проверка System Restore", &result, "OK");
+            state.system_restore.finish(result);
+            Task::none()
+        }
+        Message::RecentEnabled(result) => {
+            state.history.record(
+                "Включение Recent",
+                &result,
+                "Запись в Recent успешно включена!",
+            );
             match result {
-                Ok(status) => {
-                    state.sysmain_status = Some(status);
-                    state.status_message.clear();
+                Ok(()) => check_recent(state),
+                Err(e) => {
+                    state.recent.fail(&e);
+                    Task::none()
                 }
-                Err(e) => state.status_message = format!("Ошибка Prefetch: {}", e.to_russian()),
             }
-            Task::none()
         }
-        Message::SystemRestoreChecked(result) => {
+        Message::UserAssistEnabled(result) => {
+            state.history.record(
+                "Включение UserAssist",
+                &result,
+                "Отслеживание запуска программ успешно включено!",
+            );
             match result {
-                Ok(status) => {
-                    state.system_restore_status = Some(status);
-                    state.status_message.clear();
+                Ok(()) => {
+                    state.userassist.start();
+                    Task::perform(
+                        async { service::check_userassist() },
+                        Message::UserAssistChecked,
+                    )
                 }
                 Err(e) => {
-                    state.status_message = format!("Ошибка System Restore: {}", e.to_russian());
+                    state.userassist.fail(&e);
+                    Task::none()
                 }
             }
-            Task::none()
         }
-        Message::RecentEnabled(result) => match result {
-            Ok(()) => {
-                state.status_message = "Запись в Recent успешно включена!".to_string();
-                Task::perform(async { service::check_recent() }, Message::RecentChecked)
-            }
-            Err(e) => {
-                state.status_message = e.to_russian();
-                Task::none()
+        Message::SysMainEnabled(result) => {
+            state.note_privileged_result(&result);
+            state.history.record(
+                "Включение Prefetch",
+                &result,
+                "Служба Prefetch успешно включена и запущена!",
+            );
+            match result {
+                Ok(()) => Task::batch(vec![check_recent(state), check_sysmain(state)]),
+                Err(e) => {
+                    state.sysmain.fail(&e);
+                    Task::none()
+                }
             }
-        },
-        Message::SysMainEnabled(result) => match result {
-            Ok(()) => {
-                state.status_message = "Служба Prefetch успешно включена и запущена!".to_string();
-                Task::batch(vec![
-                    Task::perform(async { service::check_recent() }, Message::RecentChecked),
-                    Task::perform(async { service::check_sysmain() }, Message::SysMainChecked),
-                ])
+        }
+        Message::LastAccessEnabled(result) => {
+            state.note_privileged_result(&result);
+            state.history.record(
+                "Включение времени доступа",
+                &result,
+                "Обновление времени доступа включено. Требуется перезагрузка.",
+            );
+            match result {
+                Ok(()) => {
+                    state.last_access.start();
+                    Task::perform(
+                        async { service::check_last_access() },
+                        Message::LastAccessChecked,
+                    )
+                }
+                Err(e) => {
+                    state.last_access.fail(&e);
+                    Task::none()
+                }
             }
-            Err(e) => {
-                state.status_message = e.to_russian();
-                Task::none()
+        }
+        Message::SystemRestoreEnabled(result) => {
+            state.note_privileged_result(&result);
+            state.history.record(
+                "Включение System Restore",
+                &result,
+                "System Restore успешно включена на диске C:!",
+            );
+            match result {
+                Ok(()) => {
+                    state.system_restore.start();
+                    Task::perform(
+                        async { service::check_system_restore() },
+                        Message::SystemRestoreChecked,
+                    )
+                }
+                Err(e) => {
+                    state.system_restore.fail(&e);
+                    Task::none()
+                }
             }
-        },
-        Message::SystemRestoreEnabled(result) => match result {
-            Ok(()) => {
-                state.status_message = "System Restore успешно включена на диске C:!".to_string();
-                Task::perform(
-                    async { service::check_system_restore() },
-                    Message::SystemRestoreChecked,
-                )
+        }
+        Message::FilesChanged(changes) => {
+            let mut tasks = Vec::new();
+            if let Some(change) = changes
+                .iter()
+                .rfind(|change| change.folder == WatchedFolder::Recent)
+            {
+                state.recent_change = Some(change.clone());
+                tasks.push(refresh_recent(state));
             }
-            Err(e) => {
-                state.status_message = e.to_russian();
-                Task::none()
+            if let Some(change) = changes
+                .iter()
+                .rfind(|change| change.folder == WatchedFolder::Prefetch)
+            {
+                state.prefetch_change = Some(change.clone());
+                tasks.push(refresh_sysmain(state));
             }
-        },
+            Task::batch(tasks)
+        }
+        Message::WatchFailed(e) => {
+            state
+                .history
+                .push("Отслеживание папок", Outcome::Failure(e.to_russian()));
+            Task::none()
+        }
         Message::OpenRecentFolder => {
-            if let Some(status) = &state.recent_status {
+            if let Some(status) = &state.recent.status {
                 let _ = std::process::Command::new("explorer")
                     .arg(&status.path)
                     .spawn();
@@ -150,44 +513,85 @@ pub fn update(state: &mut State, message: Message) -> Task<Message> {
             Task::none()
         }
         Message::OpenPrefetchFolder => {
-            if let Some(status) = &state.sysmain_status {
+            if let Some(status) = &state.sysmain.status {
                 let _ = std::process::Command::new("explorer")
                     .arg(&status.prefetch_path)
                     .spawn();
             }
             Task::none()
         }
-        Message::RestartAsAdmin => {
-            let _ = utils::restart_as_admin();
+        Message::RestartAsAdmin(pending) => {
+            if let Err(e) = broker::restart_with_pending_action(pending) {
+                state.history.push(
+                    "Перезапуск от администратора",
+                    Outcome::Failure(e.to_russian()),
+                );
+            }
+            Task::none()
+        }
+        Message::ConfirmPendingAction => match state.pending_action.take() {
+            Some(Operation::EnableSysMain) => update(state, Message::EnableSysMain),
+            Some(Operation::EnableLastAccess) => update(state, Message::EnableLastAccess),
+            Some(Operation::EnableSystemRestore) => update(state, Message::EnableSystemRestore),
+            Some(operation) => Task::perform(
+                async move { broker::run_privileged(operation) },
+                move |result| Message::PrivilegedDone(operation, result),
+            ),
+            None => Task::none(),
+        },
+        Message::CancelPendingAction => {
+            if let Some(operation) = state.pending_action.take() {
+                state.history.push(
+                    operation.as_russian(),
+                    Outcome::Failure("Отменено пользователем".to_string()),
+                );
+            }
+            Task::none()
+        }
+        Message::PrivilegedDone(operation, result) => {
+            state.note_privileged_result(&result);
+            state
+                .history
+                .record(operation.as_russian(), &result, "Выполнено");
             Task::none()
         }
     }
 }
 
 pub fn view(state: &State) -> Element<'_, Message> {
-    let mut content = column![view_header()].spacing(5).padding(15);
-
-    if !state.is_admin {
-        content = content.push(view_admin_hint());
-    }
+    let mut content = column![view_header(state.exporting_report)]
+        .spacing(5)
+        .padding(15);
 
-    if !state.status_message.is_empty() {
-        content = content.push(view_status_message(&state.status_message));
+    if let Some(operation) = state.pending_action {
+        content = content.push(view_pending_action(operation));
+    } else if !state.is_admin {
+        content = content.push(view_admin_hint(state.access_level));
     }
 
     content = content
         .push(space().height(15))
-        .push(view_recent_card(state.recent_status.as_ref()))
+        .push(view_recent_card(
+            &state.recent,
+            state.recent_change.as_ref(),
+        ))
+        .push(space().height(15))
+        .push(view_userassist_card(&state.userassist))
         .push(space().height(15))
         .push(view_sysmain_card(
-            state.sysmain_status.as_ref(),
-            state.is_admin,
+            &state.sysmain,
+            state.prefetch_change.as_ref(),
+            state.elevation(),
         ))
         .push(space().height(15))
+        .push(view_last_access_card(&state.last_access, state.elevation()))
+        .push(space().height(15))
         .push(view_system_restore_card(
-            state.system_restore_status.as_ref(),
-            state.is_admin,
-        ));
+            &state.system_restore,
+            state.elevation(),
+        ))
+        .push(space().height(15))
+        .push(view_history(&state.history));
 
     container(scrollable(content))
         .width(Fill)
@@ -195,12 +599,19 @@ pub fn view(state: &State) -> Element<'_, Message> {
         .into()
 }
 
-fn view_header() -> Element<'static, Message> {
+fn view_header(exporting_report: bool) -> Element<'static, Message> {
     row![
         text("Recent & Prefetch Manager")
             .size(26)
             .color(iced::Color::from_rgb(0.9, 0.9, 1.0)),
         space().width(Fill),
+        button(if exporting_report {
+            "Экспорт..."
+        } else {
+            "Экспорт отчёта"
+        })
+        .on_press_maybe((!exporting_report).then_some(Message::ExportReport))
+        .padding([8, 16]),
         button("Обновить")
             .on_press(Message::Refresh)
             .padding([8, 16]),
@@ -211,13 +622,23 @@ fn view_header() -> Element<'static, Message> {
     .into()
 }
 
-fn view_admin_hint() -> Element<'static, Message> {
+fn view_admin_hint(access_level: Option<AccessLevel>) -> Element<'static, Message> {
+    let hint = match access_level {
+        Some(
+            level @ (AccessLevel::FilteredAdmin
+            | AccessLevel::FilteredToken
+            | AccessLevel::BackupOperator
+            | AccessLevel::StandardUser),
+        ) => level.hint_russian(),
+        Some(AccessLevel::Elevated) | None => {
+            "Для полного доступа к функциям запустите программу с правами администратора"
+        }
+    };
+
     container(
         row![
-            text("Для полного доступа к функциям запустите программу с правами администратора")
-                .size(13)
-                .width(Fill),
-            ui::restart_button(Message::RestartAsAdmin).padding([6, 12]),
+            text(hint).size(13).width(Fill),
+            ui::restart_button(Message::RestartAsAdmin(None)).padding([6, 12]),
         ]
         .spacing(10)
         .align_y(iced::Alignment::Center),
@@ -237,29 +658,72 @@ fn view_admin_hint() -> Element<'static, Message> {
     .into()
 }
 
-fn view_status_message(msg: &str) -> Element<'_, Message> {
-    container(text(msg).size(14))
-        .padding(12)
-        .style(|_| container::Style {
-            background: Some(iced::Background::Color(iced::Color::from_rgb(
-                0.2, 0.25, 0.15,
-            ))),
-            border: iced::Border {
-                color: iced::Color::from_rgb(0.5, 0.6, 0.3),
-                width: 1.0,
-                radius: 6.0.into(),
-            },
-            ..Default::default()
-        })
+/// Placeholder shown until a card's first check succeeds
+fn view_card_placeholder<'a, T>(card: &'a Card<T>, loading: &'a str) -> Element<'a, Message> {
+    let content: Element<'a, Message> = match &card.error {
+        Some(error) => text(error)
+            .size(14)
+            .color(iced::Color::from_rgb(1.0, 0.4, 0.4))
+            .width(Fill)
+            .into(),
+        None => text(loading).size(16).width(Fill).into(),
+    };
+    container(content)
+        .padding(20)
+        .style(container::rounded_box)
+        .width(Fill)
         .into()
 }
 
-fn view_recent_card(status: Option<&status::RecentStatus>) -> Element<'_, Message> {
-    let Some(status) = status else {
-        return container(text("Загрузка статуса Recent...").size(16).width(Fill))
-            .padding(20)
-            .style(container::rounded_box)
-            .into();
+/// Refresh indicator and the last error of a card that already has a status
+fn view_card_state<T>(card: &Card<T>) -> iced::widget::Column<'_, Message> {
+    let mut col = column![].spacing(6);
+    if card.loading {
+        col = col.push(
+            text("Обновление...")
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        );
+    }
+    if let Some(error) = &card.error {
+        col = col.push(
+            text(error)
+                .size(13)
+                .color(iced::Color::from_rgb(1.0, 0.4, 0.4)),
+        );
+    }
+    col
+}
+
+/// Files skipped while counting, with the first few reasons
+fn view_unreadable_row(count: usize, examples: &[String]) -> Option<Element<'_, Message>> {
+    if count == 0 {
+        return None;
+    }
+    let mut details = column![].spacing(2);
+    for example in examples {
+        details = details.push(
+            text(example)
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        );
+    }
+    Some(
+        column![
+            ui::info_row("Недоступных файлов:", ui::value_text(&count)),
+            details,
+        ]
+        .spacing(4)
+        .into(),
+    )
+}
+
+fn view_recent_card<'a>(
+    card: &'a Card<status::RecentStatus>,
+    change: Option<&'a FolderChange>,
+) -> Element<'a, Message> {
+    let Some(status) = &card.status else {
+        return view_card_placeholder(card, "Загрузка статуса Recent...");
     };
 
     let mut content = column![
@@ -276,16 +740,60 @@ fn view_recent_card(status: Option<&status::RecentStatus>) -> Element<'_, Messag
             )
         ),
         ui::info_row("Файлов:", ui::value_text(&status.files_count)),
+        ui::info_row("Списков переходов:", ui::value_text(&status.jumplist_count)),
         ui::file_info_rows(status.oldest_time.as_ref(), status.newest_time.as_ref()),
+        ui::info_row(
+            "Документов Office:",
+            if status.office_mru_error.is_some() {
+                ui::status_text("НЕДОСТУПНО", false)
+            } else {
+                ui::value_text(&status.office_mru.len())
+            },
+        ),
         ui::info_row(
             "Путь:",
             text(&status.path)
                 .size(12)
                 .color(iced::Color::from_rgb(0.6, 0.6, 0.6))
         ),
+        ui::info_row(
+            "Источник пути:",
+            text(&status.path_source)
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6))
+        ),
     ]
     .spacing(10)
-    .padding(22);
+    .padding(22)
+    .push(view_card_state(card));
+
+    if status.is_network {
+        content = content.push(
+            text("Папка Recent перенаправлена на сетевой ресурс: счётчики и время файлов могут быть неполными")
+                .size(13)
+                .color(iced::Color::from_rgb(1.0, 0.7, 0.3)),
+        );
+    }
+
+    if let Some(change) = change {
+        content = content.push(view_change_row(change));
+    }
+    if let Some(row) = view_unreadable_row(status.unreadable_count, &status.unreadable_examples) {
+        content = content.push(row);
+    }
+
+    if let Some(entry) = status.office_mru.first() {
+        let display = entry.last_opened.map_or_else(
+            || format!("{}: {}", entry.app, entry.path),
+            |time| format!("{}: {} ({})", entry.app, entry.path, ui::format_time(time)),
+        );
+        content = content.push(ui::info_row(
+            "Последний документ:",
+            text(display)
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        ));
+    }
 
     if status.is_disabled {
         content = content.push(space().height(15)).push(
@@ -309,15 +817,157 @@ fn view_recent_card(status: Option<&status::RecentStatus>) -> Element<'_, Messag
         .into()
 }
 
-fn view_sysmain_card(
-    status: Option<&status::SysMainStatus>,
-    is_admin: bool,
-) -> Element<'_, Message> {
-    let Some(status) = status else {
-        return container(text("Загрузка статуса Prefetch...").size(16).width(Fill))
-            .padding(20)
-            .style(container::rounded_box)
-            .into();
+fn view_change_row(change: &FolderChange) -> Element<'_, Message> {
+    ui::info_row(
+        "Последнее изменение:",
+        text(format!(
+            "{} {}",
+            change.kind.as_russian(),
+            change.file_name()
+        ))
+        .size(12)
+        .color(iced::Color::from_rgb(0.5, 0.9, 0.5)),
+    )
+    .into()
+}
+
+/// Enable button for an operation that needs administrator rights
+///
+/// Without them the button runs the operation in the elevated helper (one UAC
+/// prompt). Only if the helper can't be used does it become a restart button
+/// that resumes the operation in a new elevated window.
+fn view_privileged_button(
+    operation: Operation,
+    on_press: Message,
+    elevation: Elevation,
+) -> Element<'static, Message> {
+    let (action, note) = match elevation {
+        Elevation::Admin => {
+            let enable = button(operation.as_russian())
+                .on_press(on_press)
+                .padding(10);
+            return container(enable).center_x(Fill).into();
+        }
+        Elevation::Broker => (
+            button(operation.as_russian()).on_press(on_press),
+            "Потребуется подтверждение UAC",
+        ),
+        Elevation::Restart => (
+            ui::restart_button(Message::RestartAsAdmin(Some(operation))),
+            "Помощник недоступен: программа перезапустится от имени администратора и продолжит действие",
+        ),
+    };
+
+    let col = column![
+        action.padding(10),
+        text(note)
+            .size(12)
+            .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+    ]
+    .spacing(6)
+    .align_x(iced::Alignment::Center);
+    container(col).center_x(Fill).into()
+}
+
+/// Confirmation for the action carried over from the non-elevated instance
+fn view_pending_action(operation: Operation) -> Element<'static, Message> {
+    container(
+        row![
+            text(format!(
+                "Выполнить действие, запрошенное перед перезапуском: «{}»?",
+                operation.as_russian()
+            ))
+            .size(13)
+            .width(Fill),
+            button("Выполнить")
+                .on_press(Message::ConfirmPendingAction)
+                .padding([6, 12]),
+            button("Отмена")
+                .on_press(Message::CancelPendingAction)
+                .padding([6, 12]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+    )
+    .padding(12)
+    .style(|_| container::Style {
+        background: Some(iced::Background::Color(iced::Color::from_rgb(
+            0.15, 0.2, 0.25,
+        ))),
+        border: iced::Border {
+            color: iced::Color::from_rgb(0.3, 0.5, 0.6),
+            width: 1.0,
+            radius: 6.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+fn view_userassist_card(card: &Card<status::UserAssistStatus>) -> Element<'_, Message> {
+    let Some(status) = &card.status else {
+        return view_card_placeholder(card, "Загрузка статуса UserAssist...");
+    };
+
+    let mut content = column![
+        text("UserAssist").size(22),
+        ui::info_row(
+            "Статус:",
+            ui::status_text(
+                if status.is_disabled {
+                    "ОТКЛЮЧЕНО"
+                } else {
+                    "ВКЛЮЧЕНО"
+                },
+                !status.is_disabled
+            )
+        ),
+        ui::info_row("Записей:", ui::value_text(&status.entries_count)),
+        ui::file_info_rows(status.oldest_time.as_ref(), status.newest_time.as_ref()),
+    ]
+    .spacing(10)
+    .padding(22)
+    .push(view_card_state(card));
+
+    if let Some(ref program) = status.last_program {
+        content = content.push(ui::info_row(
+            "Последний запуск:",
+            text(program)
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        ));
+    }
+
+    if status.is_disabled {
+        content = content.push(space().height(15)).push(
+            container(
+                button("Включить отслеживание программ")
+                    .on_press(Message::EnableUserAssist)
+                    .padding(10),
+            )
+            .center_x(Fill),
+        );
+    }
+
+    container(content)
+        .width(Fill)
+        .style(|theme| {
+            ui::card_style(
+                theme,
+                iced::Color::from_rgb(0.2, 0.2, 0.25),
+                iced::Color::from_rgb(0.4, 0.4, 0.55),
+            )
+        })
+        .into()
+}
+
+fn view_sysmain_card<'a>(
+    card: &'a Card<status::SysMainStatus>,
+    change: Option<&'a FolderChange>,
+    elevation: Elevation,
+) -> Element<'a, Message> {
+    let Some(status) = &card.status else {
+        return view_card_placeholder(card, "Загрузка статуса Prefetch...");
     };
 
     let mut content = column![
@@ -336,7 +986,8 @@ fn view_sysmain_card(
         ui::info_row("Тип запуска:", ui::value_text(&status.startup_type)),
     ]
     .spacing(10)
-    .padding(22);
+    .padding(22)
+    .push(view_card_state(card));
 
     // Show error message if prefetch folder is inaccessible
     if let Some(ref error) = status.prefetch_error {
@@ -365,12 +1016,34 @@ fn view_sysmain_card(
                 "Файлов (.pf):",
                 ui::value_text(&status.prefetch_count),
             ))
+            .push(ui::info_row(
+                "Layout.ini и .db:",
+                ui::value_text(&status.prefetch_other_count),
+            ))
             .push(ui::file_info_rows(
                 status.oldest_time.as_ref(),
                 status.newest_time.as_ref(),
             ));
+        if let Some(change) = change {
+            content = content.push(view_change_row(change));
+        }
+        if let Some(row) = view_unreadable_row(
+            status.prefetch_unreadable_count,
+            &status.prefetch_unreadable_examples,
+        ) {
+            content = content.push(row);
+        }
     }
 
+    content = content.push(ui::info_row(
+        "Записей ShimCache:",
+        if status.shimcache_error.is_some() {
+            ui::status_text("НЕДОСТУПНО", false)
+        } else {
+            ui::value_text(&status.shimcache_count)
+        },
+    ));
+
     content = content.push(ui::info_row(
         "Путь:",
         text(&status.prefetch_path)
@@ -379,16 +1052,13 @@ fn view_sysmain_card(
     ));
 
     if !status.is_running || !status.is_auto {
-        content = content.push(space().height(15)).push(if is_admin {
-            container(
-                button("Включить службу Prefetch")
-                    .on_press(Message::EnableSysMain)
-                    .padding(10),
-            )
-            .center_x(Fill)
-        } else {
-            ui::warning_box("Требуются права администратора", Message::RestartAsAdmin)
-        });
+        content = content
+            .push(space().height(15))
+            .push(view_privileged_button(
+                Operation::EnableSysMain,
+                Message::EnableSysMain,
+                elevation,
+            ));
     }
 
     container(content)
@@ -402,20 +1072,78 @@ fn view_sysmain_card(
         .into()
 }
 
-fn view_system_restore_card(
-    status: Option<&status::SystemRestoreStatus>,
-    is_admin: bool,
+fn view_last_access_card(
+    card: &Card<status::LastAccessStatus>,
+    elevation: Elevation,
 ) -> Element<'_, Message> {
-    let Some(status) = status else {
-        return container(
-            text("Загрузка статуса System Restore...")
-                .size(16)
-                .width(Fill),
-        )
-        .padding(20)
-        .style(container::rounded_box)
+    let Some(status) = &card.status else {
+        return view_card_placeholder(card, "Загрузка статуса времени доступа...");
+    };
+
+    let mut content = column![
+        text("Время последнего доступа (NTFS)").size(22),
+        ui::info_row(
+            "Статус:",
+            ui::status_text(
+                if status.is_disabled {
+                    "ОТКЛЮЧЕНО"
+                } else {
+                    "ВКЛЮЧЕНО"
+                },
+                !status.is_disabled
+            )
+        ),
+        ui::info_row("Режим:", ui::value_text(&status.mode)),
+    ]
+    .spacing(10)
+    .padding(22)
+    .push(view_card_state(card));
+
+    if let Some(value) = status.value {
+        content = content.push(ui::info_row(
+            "Значение:",
+            text(format!("0x{value:08X}"))
+                .size(12)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        ));
+    }
+
+    if status.reboot_required {
+        content = content.push(
+            text("Изменения вступят в силу после перезагрузки")
+                .size(13)
+                .color(iced::Color::from_rgb(1.0, 0.7, 0.3)),
+        );
+    }
+
+    if status.is_disabled {
+        content = content
+            .push(space().height(15))
+            .push(view_privileged_button(
+                Operation::EnableLastAccess,
+                Message::EnableLastAccess,
+                elevation,
+            ));
+    }
+
+    container(content)
         .width(Fill)
-        .into();
+        .style(|theme| {
+            ui::card_style(
+                theme,
+                iced::Color::from_rgb(0.2, 0.22, 0.15),
+                iced::Color::from_rgb(0.45, 0.5, 0.3),
+            )
+        })
+        .into()
+}
+
+fn view_system_restore_card(
+    card: &Card<status::SystemRestoreStatus>,
+    elevation: Elevation,
+) -> Element<'_, Message> {
+    let Some(status) = &card.status else {
+        return view_card_placeholder(card, "Загрузка статуса System Restore...");
     };
 
     let mut content = column![
@@ -433,27 +1161,17 @@ fn view_system_restore_card(
         ),
     ]
     .spacing(10)
-    .padding(22);
+    .padding(22)
+    .push(view_card_state(card));
 
-    // Show enable button or admin warning if not enabled
     if !status.is_enabled {
-        content = content.push(space().height(15));
-
-        if is_admin {
-            content = content.push(
-                container(
-                    button("Включить System Restore на C:")
-                        .on_press(Message::EnableSystemRestore)
-                        .padding(10),
-                )
-                .center_x(Fill),
-            );
-        } else {
-            content = content.push(ui::warning_box(
-                "Требуются права администратора",
-                Message::RestartAsAdmin,
+        content = content
+            .push(space().height(15))
+            .push(view_privileged_button(
+                Operation::EnableSystemRestore,
+                Message::EnableSystemRestore,
+                elevation,
             ));
-        }
     }
 
     container(content)
@@ -467,3 +1185,61 @@ fn view_system_restore_card(
         })
         .into()
 }
+
+fn view_history(history: &History) -> Element<'_, Message> {
+    let mut entries = column![].spacing(4);
+    if history.is_empty() {
+        entries = entries.push(
+            text("Операций пока не было")
+                .size(13)
+                .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        );
+    }
+    for entry in history.entries() {
+        let (details, color) = match &entry.outcome {
+            Outcome::Success(details) => (details, iced::Color::from_rgb(0.4, 1.0, 0.4)),
+            Outcome::Failure(details) => (details, iced::Color::from_rgb(1.0, 0.4, 0.4)),
+        };
+        let time: chrono::DateTime<chrono::Local> = entry.time.into();
+        entries = entries.push(
+            row![
+                text(time.format("%H:%M:%S").to_string())
+                    .size(12)
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6))
+                    .width(60),
+                text(entry.operation).size(12).width(180),
+                text(details).size(12).color(color).width(Fill),
+            ]
+            .spacing(8),
+        );
+    }
+
+    let content = column![
+        row![
+            text("История операций").size(22),
+            space().width(Fill),
+            button("Копировать")
+                .on_press(Message::CopyHistory)
+                .padding([6, 12]),
+            button("Экспорт")
+                .on_press(Message::ExportHistory)
+                .padding([6, 12]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center),
+        scrollable(entries).height(200),
+    ]
+    .spacing(10)
+    .padding(22);
+
+    container(content)
+        .width(Fill)
+        .style(|theme| {
+            ui::card_style(
+                theme,
+                iced::Color::from_rgb(0.17, 0.17, 0.17),
+                iced::Color::from_rgb(0.35, 0.35, 0.35),
+            )
+        })
+        .into()
+}
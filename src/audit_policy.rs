@@ -0,0 +1,259 @@
+use crate::error::{RecentEnablerError, Result};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// "Process Creation" subcategory (event 4688)
+pub const PROCESS_CREATION_GUID: &str = "{0CCE922B-69AE-11D9-BED3-505054503030}";
+
+/// Subcategories worth auditing for investigations: (name, GUID, success, failure)
+pub const DEFAULT_SUBCATEGORIES: &[(&str, &str, bool, bool)] = &[
+    ("Process Creation", PROCESS_CREATION_GUID, true, false),
+    (
+        "Process Termination",
+        "{0CCE922C-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "Logon",
+        "{0CCE9215-69AE-11D9-BED3-505054503030}",
+        true,
+        true,
+    ),
+    (
+        "Special Logon",
+        "{0CCE921B-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "Security System Extension",
+        "{0CCE9211-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "Other Object Access Events",
+        "{0CCE9227-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "User Account Management",
+        "{0CCE9235-69AE-11D9-BED3-505054503030}",
+        true,
+        true,
+    ),
+    (
+        "Security Group Management",
+        "{0CCE9237-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "Audit Policy Change",
+        "{0CCE922F-69AE-11D9-BED3-505054503030}",
+        true,
+        false,
+    ),
+    (
+        "Credential Validation",
+        "{0CCE923F-69AE-11D9-BED3-505054503030}",
+        true,
+        true,
+    ),
+];
+
+/// Column positions in `auditpol /r` output; headers are localized, positions are not
+const COLUMN_SUBCATEGORY: usize = 2;
+const COLUMN_GUID: usize = 3;
+const COLUMN_SETTING_VALUE: usize = 6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuditSetting {
+    pub success: bool,
+    pub failure: bool,
+}
+
+impl AuditSetting {
+    /// Decode the numeric `Setting Value` column (bit 0 success, bit 1 failure)
+    #[must_use]
+    pub const fn from_value(value: u8) -> Self {
+        Self {
+            success: value & 1 != 0,
+            failure: value & 2 != 0,
+        }
+    }
+
+    /// Whether this setting audits at least everything `target` asks for
+    #[must_use]
+    pub const fn covers(self, target: Self) -> bool {
+        (self.success || !target.success) && (self.failure || !target.failure)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubcategoryPolicy {
+    pub name: String,
+    pub guid: String,
+    pub setting: AuditSetting,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditTarget {
+    pub name: String,
+    pub guid: String,
+    pub setting: AuditSetting,
+}
+
+/// Build the default subcategory list
+#[must_use]
+pub fn default_subcategories() -> Vec<AuditTarget> {
+    DEFAULT_SUBCATEGORIES
+        .iter()
+        .map(|&(name, guid, success, failure)| AuditTarget {
+            name: name.to_string(),
+            guid: guid.to_string(),
+            setting: AuditSetting { success, failure },
+        })
+        .collect()
+}
+
+/// Parse `auditpol /get /category:* /r` CSV output
+///
+/// The header line and rows without a subcategory GUID are skipped.
+///
+/// # Errors
+///
+/// Returns error if a subcategory row has an invalid setting value
+pub fn parse_auditpol_csv(output: &str) -> Result<Vec<SubcategoryPolicy>> {
+    let mut policies = Vec::new();
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let Some(guid) = fields.get(COLUMN_GUID).filter(|g| g.starts_with('{')) else {
+            continue;
+        };
+        let value = fields
+            .get(COLUMN_SETTING_VALUE)
+            .and_then(|v| v.parse::<u8>().ok())
+            .ok_or_else(|| {
+                RecentEnablerError::AuditPolicyReadFailed(format!("invalid setting in: {line}"))
+            })?;
+
+        policies.push(SubcategoryPolicy {
+            name: fields[COLUMN_SUBCATEGORY].to_string(),
+            guid: guid.to_ascii_uppercase(),
+            setting: AuditSetting::from_value(value),
+        });
+    }
+
+    Ok(policies)
+}
+
+/// Find the targets whose current policy doesn't cover the requested setting
+#[must_use]
+pub fn pending_changes<'a>(
+    policies: &[SubcategoryPolicy],
+    targets: &'a [AuditTarget],
+) -> Vec<&'a AuditTarget> {
+    targets
+        .iter()
+        .filter(|target| {
+            policies
+                .iter()
+                .find(|p| p.guid.eq_ignore_ascii_case(&target.guid))
+                .is_some_and(|p| !p.setting.covers(target.setting))
+        })
+        .collect()
+}
+
+/// Arguments for `auditpol /set` adding `target` to the current setting
+///
+/// Auditing already in place is never turned off.
+#[must_use]
+pub fn set_args(current: AuditSetting, target: &AuditTarget) -> Vec<String> {
+    let mut args = vec!["/set".to_string(), format!("/subcategory:{}", target.guid)];
+    if target.setting.success && !current.success {
+        args.push("/success:enable".to_string());
+    }
+    if target.setting.failure && !current.failure {
+        args.push("/failure:enable".to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPTURED: &str = include_str!("../tests/fixtures/auditpol/get_all_en.csv");
+
+    #[test]
+    fn test_parse_auditpol_csv() {
+        let policies = parse_auditpol_csv(CAPTURED).unwrap();
+        assert_eq!(policies.len(), 20);
+
+        let logon = policies.iter().find(|p| p.name == "Logon").unwrap();
+        assert_eq!(logon.guid, "{0CCE9215-69AE-11D9-BED3-505054503030}");
+        assert_eq!(
+            logon.setting,
+            AuditSetting {
+                success: true,
+                failure: true
+            }
+        );
+
+        let process = policies
+            .iter()
+            .find(|p| p.guid == PROCESS_CREATION_GUID)
+            .unwrap();
+        assert_eq!(process.name, "Process Creation");
+        assert_eq!(process.setting, AuditSetting::default());
+    }
+
+    #[test]
+    fn test_parse_localized_output() {
+        // Russian headers and setting names; only the GUID and value columns matter
+        let output = "Имя компьютера,Целевой объект политики,Подкатегория,GUID подкатегории,\
+                      Параметр включения,Параметр исключения,Значение параметра\r\n\
+                      WS-042,Система,Создание процесса,{0cce922b-69ae-11d9-bed3-505054503030},\
+                      Успех,,1\r\n";
+        let policies = parse_auditpol_csv(output).unwrap();
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].guid, PROCESS_CREATION_GUID);
+        assert!(policies[0].setting.success);
+        assert!(!policies[0].setting.failure);
+    }
+
+    #[test]
+    fn test_pending_changes() {
+        let policies = parse_auditpol_csv(CAPTURED).unwrap();
+        let targets = default_subcategories();
+        let pending: Vec<&str> = pending_changes(&policies, &targets)
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+
+        assert!(pending.contains(&"Process Creation"));
+        assert!(pending.contains(&"User Account Management"));
+        assert!(!pending.contains(&"Logon"));
+        assert!(!pending.contains(&"Special Logon"));
+
+        let user_accounts = targets
+            .iter()
+            .find(|t| t.name == "User Account Management")
+            .unwrap();
+        assert_eq!(
+            set_args(AuditSetting::from_value(1), user_accounts),
+            [
+                "/set",
+                "/subcategory:{0CCE9235-69AE-11D9-BED3-505054503030}",
+                "/failure:enable"
+            ]
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+use crate::utils;
+use winreg::enums::HKEY_LOCAL_MACHINE;
+
+const CMDLINE_POLICY_PATH: &str =
+    r"SOFTWARE\Microsoft\Windows\CurrentVersion\Policies\System\Audit";
+
+const CMDLINE_POLICY_VALUE: &str = "ProcessCreationIncludeCmdLine_Enabled";
+
+/// Read the effective audit policy of all subcategories
+///
+/// # Errors
+///
+/// Returns error if `auditpol` fails (it requires administrator rights)
+pub fn get_audit_policy() -> Result<Vec<SubcategoryPolicy>> {
+    let output = utils::hidden_command("auditpol")
+        .args(["/get", "/category:*", "/r"])
+        .output()
+        .map_err(|e| {
+            RecentEnablerError::AuditPolicyReadFailed(format!("Failed to run auditpol: {e}"))
+        })?;
+    if !output.status.success() {
+        return Err(RecentEnablerError::AuditPolicyReadFailed(
+            utils::command_error(&output),
+        ));
+    }
+    parse_auditpol_csv(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Apply the targets that are not yet covered by the current policy
+///
+/// # Errors
+///
+/// Returns error if `auditpol` fails for any subcategory
+pub fn apply_audit_policy(targets: &[AuditTarget]) -> Result {
+    let policies = get_audit_policy()?;
+
+    for target in pending_changes(&policies, targets) {
+        let current = policies
+            .iter()
+            .find(|p| p.guid.eq_ignore_ascii_case(&target.guid))
+            .map(|p| p.setting)
+            .unwrap_or_default();
+
+        let output = utils::hidden_command("auditpol")
+            .args(set_args(current, target))
+            .output()
+            .map_err(|e| {
+                RecentEnablerError::AuditPolicyEnableFailed(format!("Failed to run auditpol: {e}"))
+            })?;
+        if !output.status.success() {
+            return Err(RecentEnablerError::AuditPolicyEnableFailed(format!(
+                "{}: {}",
+                target.name,
+                utils::command_error(&output)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check if 4688 events include the process command line
+#[must_use]
+pub fn is_cmdline_enabled() -> bool {
+    utils::read_reg_dword(
+        HKEY_LOCAL_MACHINE,
+        CMDLINE_POLICY_PATH,
+        CMDLINE_POLICY_VALUE,
+    )
+    .unwrap_or(0)
+        != 0
+}
+
+/// Include the command line in process creation events
+///
+/// # Errors
+///
+/// Returns error if the policy key cannot be written
+pub fn enable_cmdline() -> Result {
+    utils::write_reg_dword(
+        HKEY_LOCAL_MACHINE,
+        CMDLINE_POLICY_PATH,
+        CMDLINE_POLICY_VALUE,
+        1,
+    )
+    .map_err(|e| RecentEnablerError::AuditPolicyEnableFailed(e.to_string()))
+}
@@ -0,0 +1,88 @@
+use crate::{binary, utils};
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BamSource {
+    /// Background Activity Moderator
+    Bam,
+    /// Desktop Activity Moderator
+    Dam,
+}
+
+#[derive(Debug, Clone)]
+pub struct BamEntry {
+    pub source: BamSource,
+    pub sid: String,
+    /// Path as stored by the driver (`\Device\HarddiskVolumeN\...` or an app ID)
+    pub raw_path: String,
+    /// Path with the volume device translated to a drive letter when possible
+    pub path: String,
+    pub last_execution: Option<SystemTime>,
+}
+
+/// Parse the execution time from a BAM/DAM value (FILETIME in the first 8 bytes)
+#[must_use]
+pub fn parse_bam_value(data: &[u8]) -> Option<SystemTime> {
+    utils::filetime_to_system_time(binary::read_u64(data, 0)?)
+}
+
+/// Replace a `\Device\...` volume prefix with its drive letter
+///
+/// `device_map` holds `(device, drive)` pairs such as
+/// `("\Device\HarddiskVolume3", "C:")`. Unmatched paths are returned unchanged.
+#[must_use]
+pub fn translate_device_path(path: &str, device_map: &[(String, String)]) -> String {
+    for (device, drive) in device_map {
+        let Some(prefix) = path.get(..device.len()) else {
+            continue;
+        };
+        let rest = &path[device.len()..];
+        if prefix.eq_ignore_ascii_case(device) && (rest.is_empty() || rest.starts_with('\\')) {
+            return format!("{drive}{rest}");
+        }
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_device_path() {
+        let map = vec![
+            (r"\Device\HarddiskVolume1".to_string(), "D:".to_string()),
+            (r"\Device\HarddiskVolume10".to_string(), "E:".to_string()),
+        ];
+        assert_eq!(
+            translate_device_path(r"\Device\HarddiskVolume10\tools\x.exe", &map),
+            r"E:\tools\x.exe"
+        );
+        assert_eq!(
+            translate_device_path(r"\device\harddiskvolume1\a.exe", &map),
+            r"D:\a.exe"
+        );
+        assert_eq!(
+            translate_device_path("Microsoft.Windows.Explorer", &map),
+            "Microsoft.Windows.Explorer"
+        );
+    }
+
+    #[test]
+    fn test_parse_bam_value() {
+        let mut data = 132_539_328_000_000_000u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0u8; 16]);
+        let secs = parse_bam_value(&data)
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(secs, 1_609_459_200);
+        assert!(parse_bam_value(&data[..4]).is_none());
+    }
+}
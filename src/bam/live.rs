@@ -0,0 +1,127 @@
+use super::*;
+
+use crate::error::{RecentEnablerError, Result};
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+const BAM_SERVICE_PATH: &str = r"SYSTEM\CurrentControlSet\Services\bam";
+
+/// `UserSettings` locations: Windows 10 1809+ first, then the original layout
+const SETTINGS_PATHS: &[(BamSource, &str)] = &[
+    (
+        BamSource::Bam,
+        r"SYSTEM\CurrentControlSet\Services\bam\State\UserSettings",
+    ),
+    (
+        BamSource::Bam,
+        r"SYSTEM\CurrentControlSet\Services\bam\UserSettings",
+    ),
+    (
+        BamSource::Dam,
+        r"SYSTEM\CurrentControlSet\Services\dam\State\UserSettings",
+    ),
+    (
+        BamSource::Dam,
+        r"SYSTEM\CurrentControlSet\Services\dam\UserSettings",
+    ),
+];
+
+/// Service start value meaning "disabled"
+const SERVICE_DISABLED: u32 = 4;
+
+/// Default start value of the `bam` driver (system start)
+const SERVICE_SYSTEM_START: u32 = 1;
+
+/// Build the `\Device\HarddiskVolumeN` to drive letter mapping
+#[must_use]
+pub fn get_device_map() -> Vec<(String, String)> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::QueryDosDeviceW;
+
+    let mut map = Vec::new();
+    for letter in 'A'..='Z' {
+        let drive = format!("{letter}:");
+        let name: Vec<u16> = drive.encode_utf16().chain(Some(0)).collect();
+        let mut target = vec![0u16; 1024];
+
+        let len = unsafe { QueryDosDeviceW(PCWSTR(name.as_ptr()), Some(&mut target)) } as usize;
+        if len == 0 {
+            continue;
+        }
+
+        // The buffer is a multi-string; the first entry is the active mapping
+        let end = target.iter().position(|&c| c == 0).unwrap_or(len);
+        map.push((String::from_utf16_lossy(&target[..end]), drive));
+    }
+    map
+}
+
+/// Check if the `bam` driver is disabled
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_bam_disabled() -> Result<bool> {
+    let start = utils::read_reg_dword(HKEY_LOCAL_MACHINE, BAM_SERVICE_PATH, "Start");
+    Ok(start == Some(SERVICE_DISABLED))
+}
+
+/// Set the `bam` driver back to its default start type
+///
+/// Takes effect after a reboot.
+///
+/// # Errors
+///
+/// Returns error if the service key cannot be written
+pub fn enable_bam() -> Result {
+    utils::write_reg_dword(
+        HKEY_LOCAL_MACHINE,
+        BAM_SERVICE_PATH,
+        "Start",
+        SERVICE_SYSTEM_START,
+    )
+    .map_err(|e| RecentEnablerError::BamEnableFailed(e.to_string()))
+}
+
+/// List per-user executables recorded by BAM and DAM
+///
+/// # Errors
+///
+/// Returns error if a `UserSettings` key exists but cannot be read
+pub fn get_bam_entries() -> Result<Vec<BamEntry>> {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let device_map = get_device_map();
+    let mut entries = Vec::new();
+
+    for (source, settings_path) in SETTINGS_PATHS {
+        let settings = match hklm.open_subkey(settings_path) {
+            Ok(key) => key,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(RecentEnablerError::BamReadFailed(format!(
+                    "{settings_path}: {e}"
+                )))
+            }
+        };
+
+        for sid in settings.enum_keys().filter_map(std::result::Result::ok) {
+            let Ok(user_key) = settings.open_subkey(&sid) else {
+                continue;
+            };
+            for (name, value) in user_key.enum_values().filter_map(std::result::Result::ok) {
+                if name == "Version" || name == "SequenceNumber" {
+                    continue;
+                }
+                entries.push(BamEntry {
+                    source: *source,
+                    sid: sid.clone(),
+                    path: translate_device_path(&name, &device_map),
+                    raw_path: name,
+                    last_execution: parse_bam_value(&value.bytes),
+                });
+            }
+        }
+    }
+
+    Ok(entries)
+}
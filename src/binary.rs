@@ -0,0 +1,97 @@
+//! Little-endian readers shared by the artifact parsers
+//!
+//! All readers are bounds-checked and return `None` instead of panicking on
+//! truncated data, since every input comes from untrusted on-disk structures.
+
+#[must_use]
+pub fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+#[must_use]
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+#[must_use]
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[must_use]
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(buf))
+}
+
+/// Read a NUL-terminated single-byte string, stopping at the end of data
+#[must_use]
+pub fn read_ascii_z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Some(bytes[..end].iter().map(|&b| char::from(b)).collect())
+}
+
+/// Read a NUL-terminated UTF-16LE string, stopping at the end of data
+#[must_use]
+pub fn read_utf16_z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+/// Format a 16-byte little-endian GUID as `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`
+#[must_use]
+pub fn format_guid(data: &[u8], offset: usize) -> Option<String> {
+    let d4 = data.get(offset.checked_add(8)?..offset.checked_add(16)?)?;
+    Some(format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        read_u32(data, offset)?,
+        read_u16(data, offset + 4)?,
+        read_u16(data, offset + 6)?,
+        d4[0],
+        d4[1],
+        d4[2],
+        d4[3],
+        d4[4],
+        d4[5],
+        d4[6],
+        d4[7],
+    ))
+}
+
+/// Format a binary security identifier as `S-R-A-S1-S2-...`
+#[must_use]
+pub fn format_sid(data: &[u8]) -> Option<String> {
+    let revision = read_u8(data, 0)?;
+    let sub_count = usize::from(read_u8(data, 1)?);
+    let authority = data
+        .get(2..8)?
+        .iter()
+        .fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+
+    let sub_authorities = (0..sub_count)
+        .map(|i| read_u32(data, 8 + i * 4).map(|v| format!("-{v}")))
+        .collect::<Option<String>>()?;
+    Some(format!("S-{revision}-{authority}{sub_authorities}"))
+}
+
+/// Find the first occurrence of `needle` in `data` at or after `from`
+#[must_use]
+pub fn find_bytes(data: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    data.get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|pos| pos + from)
+}
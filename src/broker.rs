@@ -0,0 +1,422 @@
+use crate::error::{RecentEnablerError, Result};
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Read, Write};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Bumped whenever requests or responses change shape
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Command-line flag that starts the executable in helper mode
+pub const BROKER_FLAG: &str = "--broker";
+
+/// Longest accepted message line; requests are a few dozen bytes
+const MAX_MESSAGE_LEN: u64 = 64 * 1024;
+
+/// Command-line flag carrying the action to resume after [`restart_with_pending_action`]
+pub const PENDING_ACTION_FLAG: &str = "--pending-action";
+
+/// Arguments for the elevated instance: the original ones plus the pending action
+///
+/// A pending action left over from an earlier restart is replaced, everything
+/// else is passed through unchanged and in order.
+#[must_use]
+pub fn restart_args(original: &[String], pending: Option<Operation>) -> Vec<String> {
+    let mut args = original.to_vec();
+    take_pending_action(&mut args);
+    if let Some(operation) = pending {
+        args.push(PENDING_ACTION_FLAG.to_string());
+        args.push(operation.as_arg().to_string());
+    }
+    args
+}
+
+/// Remove the pending action flag from `args` and parse its value
+///
+/// Values outside the [`Operation`] set are dropped rather than acted on.
+pub fn take_pending_action(args: &mut Vec<String>) -> Option<Operation> {
+    let mut pending = None;
+    while let Some(pos) = args.iter().position(|arg| arg == PENDING_ACTION_FLAG) {
+        let value = (pos + 1 < args.len()).then(|| args.remove(pos + 1));
+        args.remove(pos);
+        pending = pending.or_else(|| value.as_deref().and_then(Operation::from_arg));
+    }
+    pending
+}
+
+/// Restart as administrator and offer `pending` once the new instance starts
+///
+/// Used when the helper can't be started; the original arguments are kept.
+///
+/// # Errors
+///
+/// Returns error if the elevated instance cannot be started
+pub fn restart_with_pending_action(pending: Option<Operation>) -> Result {
+    let original: Vec<String> = std::env::args().skip(1).collect();
+    utils::restart_as_admin(&restart_args(&original, pending))
+}
+
+/// The closed set of privileged operations the helper will run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    EnableActivities,
+    EnableSysMain,
+    EnableBam,
+    EnableSrum,
+    EnableEventLog,
+    EnablePowerShellLogging,
+    EnableAuditPolicy,
+    EnableLastAccess,
+    EnableUsnJournal,
+    EnableSystemRestore,
+}
+
+impl Operation {
+    pub const ALL: [Self; 10] = [
+        Self::EnableActivities,
+        Self::EnableSysMain,
+        Self::EnableBam,
+        Self::EnableSrum,
+        Self::EnableEventLog,
+        Self::EnablePowerShellLogging,
+        Self::EnableAuditPolicy,
+        Self::EnableLastAccess,
+        Self::EnableUsnJournal,
+        Self::EnableSystemRestore,
+    ];
+
+    /// Command-line spelling, used for [`PENDING_ACTION_FLAG`]
+    #[must_use]
+    pub fn as_arg(self) -> &'static str {
+        match self {
+            Self::EnableActivities => "enable-activities",
+            Self::EnableSysMain => "enable-sysmain",
+            Self::EnableBam => "enable-bam",
+            Self::EnableSrum => "enable-srum",
+            Self::EnableEventLog => "enable-eventlog",
+            Self::EnablePowerShellLogging => "enable-powershell-logging",
+            Self::EnableAuditPolicy => "enable-audit-policy",
+            Self::EnableLastAccess => "enable-last-access",
+            Self::EnableUsnJournal => "enable-usn-journal",
+            Self::EnableSystemRestore => "enable-system-restore",
+        }
+    }
+
+    #[must_use]
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operation| operation.as_arg() == arg)
+    }
+
+    #[must_use]
+    pub fn as_russian(self) -> &'static str {
+        match self {
+            Self::EnableActivities => "Включить журнал действий",
+            Self::EnableSysMain => "Включить службу Prefetch",
+            Self::EnableBam => "Включить BAM",
+            Self::EnableSrum => "Включить SRUM",
+            Self::EnableEventLog => "Включить журналы событий",
+            Self::EnablePowerShellLogging => "Включить журналирование PowerShell",
+            Self::EnableAuditPolicy => "Настроить политику аудита",
+            Self::EnableLastAccess => "Включить обновление времени доступа",
+            Self::EnableUsnJournal => "Включить USN-журналы",
+            Self::EnableSystemRestore => "Включить System Restore на C:",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Request {
+    pub version: u32,
+    /// Echoed in the response so the client can match them up
+    pub id: u64,
+    pub operation: Operation,
+}
+
+/// A request whose operation has not been checked against [`Operation`] yet
+#[derive(Deserialize)]
+struct RawRequest {
+    version: u32,
+    id: u64,
+    operation: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub version: u32,
+    pub id: u64,
+    pub result: Result<(), RecentEnablerError>,
+}
+
+/// Write one message as a line of JSON
+fn write_message(writer: &mut impl Write, message: &impl Serialize) -> Result {
+    let mut line =
+        serde_json::to_vec(message).map_err(|e| RecentEnablerError::BrokerFailed(e.to_string()))?;
+    line.push(b'\n');
+    writer
+        .write_all(&line)
+        .and_then(|()| writer.flush())
+        .map_err(|e| RecentEnablerError::BrokerFailed(e.to_string()))
+}
+
+/// Read one line, `None` once the other side has closed the pipe
+fn read_message(reader: &mut impl BufRead) -> Result<Option<String>> {
+    let mut line = String::new();
+    let read = reader
+        .take(MAX_MESSAGE_LEN)
+        .read_line(&mut line)
+        .map_err(|e| RecentEnablerError::BrokerFailed(e.to_string()))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') {
+        return Err(RecentEnablerError::BrokerFailed(
+            "message too long or truncated".to_string(),
+        ));
+    }
+    Ok(Some(line))
+}
+
+/// Decode one request line and run it, rejecting other versions and unknown operations
+pub fn handle_request(line: &str, execute: impl FnOnce(Operation) -> Result) -> Response {
+    let raw: RawRequest = match serde_json::from_str(line) {
+        Ok(raw) => raw,
+        Err(e) => {
+            return Response {
+                version: PROTOCOL_VERSION,
+                id: 0,
+                result: Err(RecentEnablerError::BrokerRejected(format!(
+                    "malformed request: {e}"
+                ))),
+            }
+        }
+    };
+
+    let result = if raw.version == PROTOCOL_VERSION {
+        serde_json::from_value::<Operation>(raw.operation)
+            .map_err(|e| RecentEnablerError::BrokerRejected(format!("unknown operation: {e}")))
+            .and_then(execute)
+    } else {
+        Err(RecentEnablerError::BrokerRejected(format!(
+            "unsupported protocol version {} (expected {PROTOCOL_VERSION})",
+            raw.version
+        )))
+    };
+
+    Response {
+        version: PROTOCOL_VERSION,
+        id: raw.id,
+        result,
+    }
+}
+
+/// Answer requests until the client closes the connection
+///
+/// # Errors
+///
+/// Returns error if the connection fails; rejected requests are answered, not returned
+pub fn serve(
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+    mut execute: impl FnMut(Operation) -> Result,
+) -> Result {
+    while let Some(line) = read_message(&mut reader)? {
+        let response = handle_request(&line, &mut execute);
+        write_message(&mut writer, &response)?;
+    }
+    Ok(())
+}
+
+/// Sends operations to a helper and waits for each result
+pub struct BrokerClient<R, W> {
+    reader: R,
+    writer: W,
+    next_id: u64,
+}
+
+impl<R: BufRead, W: Write> BrokerClient<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            next_id: 1,
+        }
+    }
+
+    /// Run `operation` in the helper
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecentEnablerError::BrokerFailed`] if the connection breaks or the
+    /// response does not match the request, otherwise the operation's own error
+    pub fn call(&mut self, operation: Operation) -> Result {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_message(
+            &mut self.writer,
+            &Request {
+                version: PROTOCOL_VERSION,
+                id,
+                operation,
+            },
+        )?;
+
+        let line = read_message(&mut self.reader)?.ok_or_else(|| {
+            RecentEnablerError::BrokerFailed("helper closed the connection".to_string())
+        })?;
+        let response: Response = serde_json::from_str(&line)
+            .map_err(|e| RecentEnablerError::BrokerFailed(format!("malformed response: {e}")))?;
+        if response.version != PROTOCOL_VERSION || response.id != id {
+            return Err(RecentEnablerError::BrokerFailed(format!(
+                "unexpected response {} (version {}) to request {id}",
+                response.id, response.version
+            )));
+        }
+        response.result
+    }
+}
+
+/// Prefix of every broker pipe name; the rest is `{server pid}-{nonce}`
+const PIPE_PREFIX: &str = r"\\.\pipe\recent-enabler-broker-";
+
+/// Process id of the server a broker pipe name was created for, `None` for foreign names
+#[must_use]
+pub fn server_pid(pipe_name: &str) -> Option<u32> {
+    let (pid, nonce) = pipe_name.strip_prefix(PIPE_PREFIX)?.split_once('-')?;
+    if nonce.is_empty() || !nonce.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    pid.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    fn loopback(
+        execute: impl FnMut(Operation) -> Result + Send + 'static,
+    ) -> (
+        BrokerClient<BufReader<std::io::PipeReader>, std::io::PipeWriter>,
+        std::thread::JoinHandle<Result>,
+    ) {
+        let (request_reader, request_writer) = std::io::pipe().unwrap();
+        let (response_reader, response_writer) = std::io::pipe().unwrap();
+        let server = std::thread::spawn(move || {
+            serve(BufReader::new(request_reader), response_writer, execute)
+        });
+        (
+            BrokerClient::new(BufReader::new(response_reader), request_writer),
+            server,
+        )
+    }
+
+    #[test]
+    fn test_loopback() {
+        let (mut client, server) = loopback(|operation| match operation {
+            Operation::EnableSysMain => Ok(()),
+            _ => Err(RecentEnablerError::SystemRestoreRequiresAdmin),
+        });
+
+        assert!(client.call(Operation::EnableSysMain).is_ok());
+        assert!(matches!(
+            client.call(Operation::EnableSystemRestore),
+            Err(RecentEnablerError::SystemRestoreRequiresAdmin)
+        ));
+
+        drop(client);
+        assert!(server.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_restart_args() {
+        let original: Vec<String> = [
+            "--root",
+            r"D:\mnt\C",
+            PENDING_ACTION_FLAG,
+            "enable-bam",
+            "--profile",
+            r"C:\Users\Иван Петров",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let args = restart_args(&original, Some(Operation::EnableSysMain));
+        assert_eq!(
+            args,
+            [
+                "--root",
+                r"D:\mnt\C",
+                "--profile",
+                r"C:\Users\Иван Петров",
+                PENDING_ACTION_FLAG,
+                "enable-sysmain",
+            ]
+        );
+
+        let mut parsed = args;
+        assert_eq!(
+            take_pending_action(&mut parsed),
+            Some(Operation::EnableSysMain)
+        );
+        assert_eq!(
+            parsed,
+            original[..2]
+                .iter()
+                .chain(&original[4..])
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        let mut hostile: Vec<String> = [PENDING_ACTION_FLAG, "format-c", "--report"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(take_pending_action(&mut hostile), None);
+        assert_eq!(hostile, ["--report"]);
+
+        let mut dangling = vec![PENDING_ACTION_FLAG.to_string()];
+        assert_eq!(take_pending_action(&mut dangling), None);
+        assert!(dangling.is_empty());
+    }
+
+    #[test]
+    fn test_server_pid() {
+        assert_eq!(
+            server_pid(r"\\.\pipe\recent-enabler-broker-4242-1700000000123"),
+            Some(4242)
+        );
+        assert_eq!(server_pid(r"\\.\pipe\recent-enabler-broker-4242"), None);
+        assert_eq!(server_pid(r"\\.\pipe\recent-enabler-broker-x-1"), None);
+        assert_eq!(server_pid(r"\\.\pipe\other-4242-1"), None);
+        assert_eq!(
+            server_pid(r"\\.\pipe\recent-enabler-broker-4242-1\..\x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rejected_requests() {
+        let never = |_: Operation| -> Result { panic!("rejected request must not run") };
+
+        let response = handle_request(r#"{"version":2,"id":7,"operation":"FormatDisk"}"#, never);
+        assert_eq!(response.id, 7);
+        assert!(matches!(
+            response.result,
+            Err(RecentEnablerError::BrokerRejected(_))
+        ));
+
+        let response = handle_request(r#"{"version":1,"id":8,"operation":"EnableSysMain"}"#, never);
+        assert_eq!(response.id, 8);
+        assert!(matches!(
+            response.result,
+            Err(RecentEnablerError::BrokerRejected(_))
+        ));
+
+        assert!(handle_request("not json", never).result.is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use super::*;
+
+use crate::service;
+
+impl Operation {
+    /// Run the operation in this process
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying `service::enable_*` call
+    pub fn execute(self) -> Result {
+        match self {
+            Self::EnableActivities => service::enable_activities(),
+            Self::EnableSysMain => service::enable_sysmain(),
+            Self::EnableBam => service::enable_bam(),
+            Self::EnableSrum => service::enable_srum(),
+            Self::EnableEventLog => service::enable_eventlog(),
+            Self::EnablePowerShellLogging => service::enable_powershell_logging(),
+            Self::EnableAuditPolicy => service::enable_audit_policy(),
+            Self::EnableLastAccess => service::enable_last_access(),
+            Self::EnableUsnJournal => service::enable_usn_journal(),
+            Self::EnableSystemRestore => service::enable_system_restore(),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod pipe {
+    use super::{BrokerClient, BROKER_FLAG};
+    use crate::error::{RecentEnablerError, Result};
+    use crate::utils;
+    use std::fs::File;
+    use std::io::{BufReader, Read, Write};
+    use std::sync::Arc;
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{
+        CloseHandle, LocalFree, ERROR_BROKEN_PIPE, ERROR_IO_PENDING, ERROR_PIPE_CONNECTED, HANDLE,
+        HLOCAL, WAIT_OBJECT_0, WAIT_TIMEOUT,
+    };
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+    use windows::Win32::Storage::FileSystem::{
+        ReadFile, WriteFile, FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAG_OVERLAPPED,
+        PIPE_ACCESS_DUPLEX,
+    };
+    use windows::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, GetNamedPipeServerProcessId, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_WAIT,
+    };
+    use windows::Win32::System::Threading::{CreateEventW, WaitForMultipleObjects};
+    use windows::Win32::System::IO::{CancelIoEx, GetOverlappedResult, OVERLAPPED};
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+    };
+
+    /// How long the helper has to connect once the UAC prompt is accepted
+    const CONNECT_TIMEOUT_MS: u32 = 30_000;
+
+    /// Only elevated administrators and SYSTEM may open the pipe
+    const PIPE_SDDL: &str = "D:P(A;;GA;;;SY)(A;;GA;;;BA)";
+
+    pub type PipeClient = BrokerClient<BufReader<PipeEnd>, PipeEnd>;
+
+    /// Owned kernel handle, closed on drop
+    struct OwnedHandle(HANDLE);
+
+    impl Drop for OwnedHandle {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+
+    // Kernel handles may be used from any thread
+    unsafe impl Send for OwnedHandle {}
+    unsafe impl Sync for OwnedHandle {}
+
+    /// Server end of the pipe, opened for overlapped I/O so connecting can time out
+    struct ServerPipe {
+        pipe: OwnedHandle,
+        /// Manual-reset event signalled when the pending operation completes
+        event: OwnedHandle,
+    }
+
+    impl ServerPipe {
+        /// Start an operation with `start` and wait for it to complete
+        fn transfer(
+            &self,
+            start: impl FnOnce(*mut OVERLAPPED) -> windows::core::Result<()>,
+        ) -> std::io::Result<usize> {
+            let mut overlapped = OVERLAPPED {
+                hEvent: self.event.0,
+                ..Default::default()
+            };
+            if let Err(e) = start(&raw mut overlapped) {
+                if e.code() != ERROR_IO_PENDING.to_hresult() {
+                    return Err(e.into());
+                }
+            }
+            let mut transferred = 0u32;
+            match unsafe {
+                GetOverlappedResult(
+                    self.pipe.0,
+                    &raw const overlapped,
+                    &raw mut transferred,
+                    true,
+                )
+            } {
+                Ok(()) => Ok(transferred as usize),
+                // The helper closed its end: end of stream
+                Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => Ok(0),
+                Err(e) => Err(e.into()),
+            }
+        }
+    }
+
+    /// One direction of a connected [`ServerPipe`]
+    pub struct PipeEnd(Arc<ServerPipe>);
+
+    impl Read for PipeEnd {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let pipe = self.0.pipe.0;
+            self.0
+                .transfer(|overlapped| unsafe { ReadFile(pipe, Some(buf), None, Some(overlapped)) })
+        }
+    }
+
+    impl Write for PipeEnd {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let pipe = self.0.pipe.0;
+            self.0.transfer(|overlapped| unsafe {
+                WriteFile(pipe, Some(buf), None, Some(overlapped))
+            })
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn failed(context: &str, e: impl std::fmt::Display) -> RecentEnablerError {
+        RecentEnablerError::BrokerFailed(format!("{context}: {e}"))
+    }
+
+    /// Create a single-instance local pipe, start the helper elevated and wait for it to connect
+    ///
+    /// Gives up if the helper exits or has not connected within [`CONNECT_TIMEOUT_MS`].
+    pub fn spawn_elevated() -> Result<PipeClient> {
+        let nonce = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let name = format!("{}{}-{nonce}", super::PIPE_PREFIX, std::process::id());
+        let pipe = create_pipe(&name)?;
+        let event = OwnedHandle(
+            unsafe { CreateEventW(None, true, false, PCWSTR::null()) }
+                .map_err(|e| failed("failed to create event", e))?,
+        );
+
+        let mut overlapped = OVERLAPPED {
+            hEvent: event.0,
+            ..Default::default()
+        };
+        if let Err(e) = unsafe { ConnectNamedPipe(pipe.0, Some(&raw mut overlapped)) } {
+            if e.code() == ERROR_PIPE_CONNECTED.to_hresult() {
+                unsafe {
+                    let _ = windows::Win32::System::Threading::SetEvent(event.0);
+                }
+            } else if e.code() != ERROR_IO_PENDING.to_hresult() {
+                return Err(failed("failed to listen on pipe", e));
+            }
+        }
+
+        let connected = launch(&name).and_then(|helper| {
+            let wait =
+                unsafe { WaitForMultipleObjects(&[event.0, helper.0], false, CONNECT_TIMEOUT_MS) };
+            match wait {
+                WAIT_OBJECT_0 => Ok(()),
+                WAIT_TIMEOUT => Err(RecentEnablerError::BrokerFailed(
+                    "helper did not connect in time".to_string(),
+                )),
+                _ => Err(RecentEnablerError::BrokerFailed(
+                    "helper exited before connecting".to_string(),
+                )),
+            }
+        });
+        if let Err(e) = connected {
+            // The connect must be finished before `overlapped` goes out of scope
+            unsafe {
+                let _ = CancelIoEx(pipe.0, Some(&raw const overlapped));
+                let mut transferred = 0u32;
+                let _ =
+                    GetOverlappedResult(pipe.0, &raw const overlapped, &raw mut transferred, true);
+            }
+            return Err(e);
+        }
+
+        let server = Arc::new(ServerPipe { pipe, event });
+        Ok(BrokerClient::new(
+            BufReader::new(PipeEnd(Arc::clone(&server))),
+            PipeEnd(server),
+        ))
+    }
+
+    /// Create the pipe with a DACL that keeps out everyone but the elevated helper
+    fn create_pipe(name: &str) -> Result<OwnedHandle> {
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                &HSTRING::from(PIPE_SDDL),
+                SDDL_REVISION_1,
+                &raw mut descriptor,
+                None,
+            )
+        }
+        .map_err(|e| failed("failed to build pipe security descriptor", e))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let attributes = SECURITY_ATTRIBUTES {
+            nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+            lpSecurityDescriptor: descriptor.0,
+            bInheritHandle: false.into(),
+        };
+
+        // FIRST_PIPE_INSTANCE fails if another process already squats on the name
+        let handle = unsafe {
+            CreateNamedPipeW(
+                &HSTRING::from(name),
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE | FILE_FLAG_OVERLAPPED,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                1,
+                4096,
+                4096,
+                0,
+                Some(&raw const attributes),
+            )
+        };
+        let error = std::io::Error::last_os_error();
+        unsafe {
+            let _ = LocalFree(Some(HLOCAL(descriptor.0)));
+        }
+        if handle.is_invalid() {
+            return Err(failed("failed to create pipe", error));
+        }
+        Ok(OwnedHandle(handle))
+    }
+
+    /// Start this executable elevated in helper mode and return its process handle
+    ///
+    /// Fails if the UAC prompt is declined.
+    fn launch(pipe_name: &str) -> Result<OwnedHandle> {
+        let exe_path = std::env::current_exe().map_err(|e| {
+            RecentEnablerError::WindowsPathNotFound(format!("Failed to get executable path: {e}"))
+        })?;
+        let file = HSTRING::from(exe_path.as_os_str());
+        let parameters = HSTRING::from(format!(
+            "{} {}",
+            utils::quote_windows_arg(BROKER_FLAG),
+            utils::quote_windows_arg(pipe_name)
+        ));
+        let verb = HSTRING::from("runas");
+
+        #[allow(clippy::cast_possible_truncation)]
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC,
+            lpVerb: PCWSTR(verb.as_ptr()),
+            lpFile: PCWSTR(file.as_ptr()),
+            lpParameters: PCWSTR(parameters.as_ptr()),
+            // SW_HIDE
+            nShow: 0,
+            ..Default::default()
+        };
+        unsafe { ShellExecuteExW(&raw mut info) }
+            .map_err(|e| failed("failed to start helper", e))?;
+        if info.hProcess.is_invalid() {
+            return Err(RecentEnablerError::BrokerFailed(
+                "helper was started without a process handle".to_string(),
+            ));
+        }
+        Ok(OwnedHandle(info.hProcess))
+    }
+
+    /// Connect to the pipe created by the GUI, refusing servers other than the GUI process
+    pub fn connect(pipe_name: &str) -> Result<(BufReader<File>, File)> {
+        let expected = super::server_pid(pipe_name).ok_or_else(|| {
+            RecentEnablerError::BrokerRejected(format!("unexpected pipe name {pipe_name}"))
+        })?;
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(pipe_name)
+            .map_err(|e| RecentEnablerError::BrokerFailed(format!("{pipe_name}: {e}")))?;
+
+        let mut server = 0u32;
+        let handle = HANDLE(std::os::windows::io::AsRawHandle::as_raw_handle(&file));
+        unsafe { GetNamedPipeServerProcessId(handle, &raw mut server) }
+            .map_err(|e| failed("failed to query pipe server", e))?;
+        if server != expected {
+            return Err(RecentEnablerError::BrokerRejected(format!(
+                "{pipe_name} is served by process {server}, expected {expected}"
+            )));
+        }
+
+        let reader = file
+            .try_clone()
+            .map_err(|e| RecentEnablerError::BrokerFailed(e.to_string()))?;
+        Ok((BufReader::new(reader), file))
+    }
+}
+
+/// Helper mode entry point: serve privileged operations over `pipe_name`
+///
+/// # Errors
+///
+/// Returns error if the helper is not elevated or the pipe fails
+pub fn run_broker(pipe_name: &str) -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::BrokerFailed(
+            "helper must run as administrator".to_string(),
+        ));
+    }
+
+    #[cfg(windows)]
+    {
+        let (reader, writer) = pipe::connect(pipe_name)?;
+        serve(reader, writer, Operation::execute)
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err(RecentEnablerError::BrokerFailed(format!(
+            "{pipe_name}: the helper is only supported on Windows"
+        )))
+    }
+}
+
+/// Run a privileged operation, through an elevated helper unless already administrator
+///
+/// The helper is started (with a UAC prompt) on first use and reused afterwards;
+/// a broken connection is dropped so the next call starts a new helper.
+///
+/// # Errors
+///
+/// Returns error if the helper cannot be started or the operation fails
+pub fn run_privileged(operation: Operation) -> Result {
+    if utils::is_admin() {
+        return operation.execute();
+    }
+
+    #[cfg(windows)]
+    {
+        static SESSION: std::sync::Mutex<Option<pipe::PipeClient>> = std::sync::Mutex::new(None);
+
+        // A panic while holding the lock leaves the session usable or `None`, never half-built
+        let mut session = SESSION
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if session.is_none() {
+            *session = Some(pipe::spawn_elevated()?);
+        }
+        let result = session
+            .as_mut()
+            .map_or_else(|| Ok(()), |client| client.call(operation));
+        if matches!(result, Err(RecentEnablerError::BrokerFailed(_))) {
+            *session = None;
+        }
+        result
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err(RecentEnablerError::BrokerFailed(format!(
+            "{operation:?}: the helper is only supported on Windows"
+        )))
+    }
+}
@@ -0,0 +1,648 @@
+//! Triage collection of Recent, jump lists, Prefetch and registry hives
+//!
+//! Files are hashed while they are copied into a ZIP archive or a directory.
+//! Original timestamps are read before copying (copying can update the access
+//! time) and recorded in `manifest.json`; `custody.json` records who collected
+//! what, where and when.
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    recent, sysmain, token, utils, vss,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+pub const CUSTODY_NAME: &str = "custody.json";
+
+/// Hives collected by default: `(file name, source)`
+///
+/// Loaded hives are locked, so they're exported with `reg save`; Amcache isn't
+/// mounted under a predefined key and is copied from a shadow copy instead.
+pub const DEFAULT_HIVES: &[(&str, HiveSource)] = &[
+    ("NTUSER.DAT", HiveSource::Registry("HKCU")),
+    (
+        "UsrClass.dat",
+        HiveSource::Registry(r"HKCU\Software\Classes"),
+    ),
+    ("SYSTEM", HiveSource::Registry(r"HKLM\SYSTEM")),
+    ("SOFTWARE", HiveSource::Registry(r"HKLM\SOFTWARE")),
+    (
+        "Amcache.hve",
+        HiveSource::File(r"%SystemRoot%\AppCompat\Programs\Amcache.hve"),
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HiveSource {
+    /// Registry key exported with `reg save`
+    Registry(&'static str),
+    /// Hive file path, environment variables allowed; read from the newest
+    /// shadow copy when the live file is held open
+    File(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiveTarget {
+    /// File name inside the `Hives` folder of the collection
+    pub name: String,
+    pub source: HiveSource,
+}
+
+#[must_use]
+pub fn default_hives() -> Vec<HiveTarget> {
+    DEFAULT_HIVES
+        .iter()
+        .map(|&(name, source)| HiveTarget {
+            name: name.to_string(),
+            source,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Zip,
+    Directory,
+}
+
+#[derive(Debug, Clone)]
+pub struct CollectOptions {
+    /// ZIP file or directory to create
+    pub output: PathBuf,
+    pub format: OutputFormat,
+    /// Recent folder with `AutomaticDestinations` and `CustomDestinations`
+    pub include_recent: bool,
+    pub include_prefetch: bool,
+    pub hives: Vec<HiveTarget>,
+    /// Name recorded as the collector in the chain of custody
+    pub collector: String,
+}
+
+impl CollectOptions {
+    /// Collect everything, recording the current user as collector
+    #[must_use]
+    pub fn new(output: impl Into<PathBuf>, format: OutputFormat) -> Self {
+        Self {
+            output: output.into(),
+            format,
+            include_recent: true,
+            include_prefetch: true,
+            hives: default_hives(),
+            collector: std::env::var("USERNAME").unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    pub source_path: String,
+    /// Path inside the collection, `/`-separated
+    pub archive_path: String,
+    pub size: u64,
+    pub sha256: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accessed: Option<String>,
+}
+
+/// A file or source that couldn't be collected
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedEntry {
+    pub source_path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+impl Manifest {
+    fn skip(&mut self, source_path: impl Into<String>, reason: impl ToString) {
+        self.skipped.push(SkippedEntry {
+            source_path: source_path.into(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainOfCustody {
+    pub collector: String,
+    pub host: String,
+    pub tool: String,
+    /// UTC, RFC 3339
+    pub started: String,
+    pub finished: String,
+    pub output: String,
+    pub format: OutputFormat,
+    pub file_count: usize,
+    pub total_size: u64,
+    pub skipped_count: usize,
+    pub manifest_sha256: String,
+}
+
+fn format_utc(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn collect_error(msg: impl std::fmt::Display) -> RecentEnablerError {
+    RecentEnablerError::CollectFailed(msg.to_string())
+}
+
+/// Copy `reader` to `writer`, returning the byte count and SHA-256
+///
+/// # Errors
+///
+/// Returns error if reading or writing fails
+pub fn copy_hashed(
+    reader: &mut (impl Read + ?Sized),
+    writer: &mut (impl Write + ?Sized),
+) -> std::io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+
+    let mut digest = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(digest, "{byte:02x}");
+    }
+    Ok((size, digest))
+}
+
+/// Destination of a collection
+trait EvidenceSink {
+    /// Store the contents of `reader` at `archive_path`, returning size and SHA-256
+    fn add(&mut self, archive_path: &str, reader: &mut dyn Read) -> std::io::Result<(u64, String)>;
+
+    fn finish(self: Box<Self>) -> std::io::Result<()>;
+}
+
+struct ZipSink {
+    writer: zip::ZipWriter<std::fs::File>,
+}
+
+impl EvidenceSink for ZipSink {
+    fn add(&mut self, archive_path: &str, reader: &mut dyn Read) -> std::io::Result<(u64, String)> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(true);
+        self.writer
+            .start_file(archive_path, options)
+            .map_err(std::io::Error::other)?;
+        copy_hashed(reader, &mut self.writer)
+    }
+
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        self.writer.finish().map_err(std::io::Error::other)?;
+        Ok(())
+    }
+}
+
+struct DirectorySink {
+    root: PathBuf,
+}
+
+impl EvidenceSink for DirectorySink {
+    fn add(&mut self, archive_path: &str, reader: &mut dyn Read) -> std::io::Result<(u64, String)> {
+        let path = archive_path
+            .split('/')
+            .fold(self.root.clone(), |path, part| path.join(part));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(path)?;
+        copy_hashed(reader, &mut file)
+    }
+
+    fn finish(self: Box<Self>) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn open_sink(options: &CollectOptions) -> Result<Box<dyn EvidenceSink>> {
+    let output_error =
+        |e: std::io::Error| collect_error(format!("{}: {e}", options.output.display()));
+    match options.format {
+        OutputFormat::Zip => {
+            let file = std::fs::File::create_new(&options.output).map_err(output_error)?;
+            Ok(Box::new(ZipSink {
+                writer: zip::ZipWriter::new(file),
+            }))
+        }
+        OutputFormat::Directory => {
+            std::fs::create_dir_all(&options.output).map_err(output_error)?;
+            if std::fs::read_dir(&options.output)
+                .map_err(output_error)?
+                .next()
+                .is_some()
+            {
+                return Err(collect_error(format!(
+                    "{}: output directory is not empty",
+                    options.output.display()
+                )));
+            }
+            Ok(Box::new(DirectorySink {
+                root: options.output.clone(),
+            }))
+        }
+    }
+}
+
+/// Copy every file below `source` into `prefix`, keeping the relative layout
+fn collect_directory(
+    sink: &mut dyn EvidenceSink,
+    source: &Path,
+    prefix: &str,
+    scan: &utils::ScanOptions,
+    manifest: &mut Manifest,
+) {
+    let walker = match utils::walk_directory(source, scan) {
+        Ok(walker) => walker,
+        Err(e) => {
+            manifest.skip(source.display().to_string(), e);
+            return;
+        }
+    };
+
+    // Timestamps are taken from the walk, before any file is opened for copying
+    let entries: Vec<_> = walker.collect();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(unreadable) => {
+                manifest.skip(unreadable.path.display().to_string(), unreadable.reason);
+                continue;
+            }
+        };
+
+        let relative = entry.path.strip_prefix(source).unwrap_or(&entry.path);
+        let archive_path = std::iter::once(prefix.to_string())
+            .chain(
+                relative
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+            )
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let copied = utils::open_for_backup(&entry.path)
+            .and_then(|mut file| sink.add(&archive_path, &mut file));
+        match copied {
+            Ok((size, sha256)) => manifest.files.push(ManifestEntry {
+                source_path: entry.path.display().to_string(),
+                archive_path,
+                size,
+                sha256,
+                created: entry.created.map(format_utc),
+                modified: entry.modified.map(format_utc),
+                accessed: entry.accessed.map(format_utc),
+            }),
+            Err(e) => manifest.skip(entry.path.display().to_string(), e),
+        }
+    }
+}
+
+/// Export a loaded hive with `reg save` into a temporary file
+fn save_hive(key: &str, name: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("recent-enabler-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let output = utils::hidden_command("reg")
+        .args(["save", key])
+        .arg(&path)
+        .arg("/y")
+        .output()
+        .map_err(|e| collect_error(format!("Failed to run reg: {e}")))?;
+    if !output.status.success() {
+        return Err(collect_error(format!(
+            "{key}: {}",
+            utils::command_error(&output)
+        )));
+    }
+    Ok(path)
+}
+
+fn collect_hive(sink: &mut dyn EvidenceSink, hive: &HiveTarget, manifest: &mut Manifest) {
+    let archive_path = format!("Hives/{}", hive.name);
+    match hive.source {
+        HiveSource::Registry(key) => match save_hive(key, &hive.name) {
+            Ok(path) => {
+                let copied = utils::open_for_backup(&path)
+                    .and_then(|mut file| sink.add(&archive_path, &mut file));
+                let _ = std::fs::remove_file(&path);
+                // Times of a `reg save` export describe the export, not the hive
+                record_hive(manifest, key.to_string(), archive_path, copied, None);
+            }
+            Err(e) => manifest.skip(key, e),
+        },
+        HiveSource::File(path) => {
+            let path = PathBuf::from(utils::expand_env_vars(path));
+            let live_metadata = std::fs::metadata(&path).ok();
+            // Hives in use are held open exclusively, so this usually reads a shadow copy
+            match vss::read_locked_file(&path, RecentEnablerError::CollectFailed) {
+                Ok(file) => {
+                    let (source_path, metadata) =
+                        match file.snapshot.as_ref().and_then(|s| s.map_path(&path)) {
+                            Some(snapshot_path) => (
+                                snapshot_path.display().to_string(),
+                                std::fs::metadata(&snapshot_path).ok(),
+                            ),
+                            None => (path.display().to_string(), live_metadata),
+                        };
+                    let copied = sink.add(&archive_path, &mut file.data.as_slice());
+                    record_hive(
+                        manifest,
+                        source_path,
+                        archive_path,
+                        copied,
+                        metadata.as_ref(),
+                    );
+                }
+                Err(e) => manifest.skip(path.display().to_string(), e),
+            }
+        }
+    }
+}
+
+fn record_hive(
+    manifest: &mut Manifest,
+    source_path: String,
+    archive_path: String,
+    copied: std::io::Result<(u64, String)>,
+    metadata: Option<&std::fs::Metadata>,
+) {
+    let time = |get: fn(&std::fs::Metadata) -> std::io::Result<SystemTime>| {
+        metadata.and_then(|m| get(m).ok()).map(format_utc)
+    };
+    match copied {
+        Ok((size, sha256)) => manifest.files.push(ManifestEntry {
+            source_path,
+            archive_path,
+            size,
+            sha256,
+            created: time(std::fs::Metadata::created),
+            modified: time(std::fs::Metadata::modified),
+            accessed: time(std::fs::Metadata::accessed),
+        }),
+        Err(e) => manifest.skip(source_path, e),
+    }
+}
+
+/// Collect the selected artifacts into a ZIP archive or directory
+///
+/// Files that can't be read are listed in the manifest's `skipped` section
+/// rather than aborting the collection.
+///
+/// # Errors
+///
+/// Returns error if the output cannot be created or finalized
+pub fn collect_evidence(options: &CollectOptions) -> Result<ChainOfCustody> {
+    token::with_backup_semantics(|| collect_with_backup_semantics(options))
+}
+
+fn collect_with_backup_semantics(options: &CollectOptions) -> Result<ChainOfCustody> {
+    let started = SystemTime::now();
+    let mut sink = open_sink(options)?;
+    let mut manifest = Manifest::default();
+
+    if options.include_recent {
+        match recent::get_recent_folder() {
+            Ok(folder) => collect_directory(
+                sink.as_mut(),
+                &folder,
+                "Recent",
+                &utils::ScanOptions::new(&["*"], 1),
+                &mut manifest,
+            ),
+            Err(e) => manifest.skip("Recent", e),
+        }
+    }
+
+    if options.include_prefetch {
+        match sysmain::get_prefetch_folder() {
+            Ok(folder) => collect_directory(
+                sink.as_mut(),
+                &folder,
+                "Prefetch",
+                &utils::ScanOptions::new(&["*"], 0),
+                &mut manifest,
+            ),
+            Err(e) => manifest.skip("Prefetch", e),
+        }
+    }
+
+    for hive in &options.hives {
+        collect_hive(sink.as_mut(), hive, &mut manifest);
+    }
+
+    finish_collection(sink, options, manifest, started)
+}
+
+/// Write the manifest and chain of custody, then close the sink
+fn finish_collection(
+    mut sink: Box<dyn EvidenceSink>,
+    options: &CollectOptions,
+    manifest: Manifest,
+    started: SystemTime,
+) -> Result<ChainOfCustody> {
+    let write_error =
+        |e: std::io::Error| collect_error(format!("{}: {e}", options.output.display()));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(collect_error)?;
+    let (_, manifest_sha256) = sink
+        .add(MANIFEST_NAME, &mut manifest_json.as_slice())
+        .map_err(write_error)?;
+
+    let custody = ChainOfCustody {
+        collector: options.collector.clone(),
+        host: std::env::var("COMPUTERNAME").unwrap_or_default(),
+        tool: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        started: format_utc(started),
+        finished: format_utc(SystemTime::now()),
+        output: options.output.display().to_string(),
+        format: options.format,
+        file_count: manifest.files.len(),
+        total_size: manifest.files.iter().map(|file| file.size).sum(),
+        skipped_count: manifest.skipped.len(),
+        manifest_sha256,
+    };
+    let custody_json = serde_json::to_vec_pretty(&custody).map_err(collect_error)?;
+    sink.add(CUSTODY_NAME, &mut custody_json.as_slice())
+        .map_err(write_error)?;
+
+    sink.finish().map_err(write_error)?;
+    Ok(custody)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn source_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "recent-enabler-collect-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("AutomaticDestinations")).unwrap();
+        std::fs::write(root.join("report.docx.lnk"), b"abc").unwrap();
+        std::fs::write(
+            root.join("AutomaticDestinations")
+                .join("5f7b5f1e01b83767.automaticDestinations-ms"),
+            b"jump",
+        )
+        .unwrap();
+        root
+    }
+
+    fn options(output: PathBuf, format: OutputFormat) -> CollectOptions {
+        CollectOptions {
+            output,
+            format,
+            include_recent: false,
+            include_prefetch: false,
+            hives: Vec::new(),
+            collector: "analyst".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_copy_hashed() {
+        let mut output = Vec::new();
+        let (size, sha256) = copy_hashed(&mut &b"abc"[..], &mut output).unwrap();
+        assert_eq!(size, 3);
+        assert_eq!(sha256, ABC_SHA256);
+        assert_eq!(output, b"abc");
+    }
+
+    #[test]
+    fn test_collect_to_directory() {
+        let source = source_tree("dir");
+        let output = source.with_extension("out");
+        let _ = std::fs::remove_dir_all(&output);
+        let options = options(output.clone(), OutputFormat::Directory);
+
+        let mut sink = open_sink(&options).unwrap();
+        let mut manifest = Manifest::default();
+        collect_directory(
+            sink.as_mut(),
+            &source,
+            "Recent",
+            &utils::ScanOptions::new(&["*"], 1),
+            &mut manifest,
+        );
+        collect_directory(
+            sink.as_mut(),
+            // A file where a folder is expected can't be listed
+            &source.join("report.docx.lnk"),
+            "Prefetch",
+            &utils::ScanOptions::new(&["*"], 0),
+            &mut manifest,
+        );
+        let custody = finish_collection(sink, &options, manifest, SystemTime::now()).unwrap();
+
+        assert_eq!(custody.file_count, 2);
+        assert_eq!(custody.total_size, 7);
+        assert_eq!(custody.skipped_count, 1);
+        assert_eq!(custody.collector, "analyst");
+        assert_eq!(
+            std::fs::read(output.join("Recent").join("report.docx.lnk")).unwrap(),
+            b"abc"
+        );
+
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(output.join(MANIFEST_NAME)).unwrap()).unwrap();
+        let files = manifest["files"].as_array().unwrap();
+        let lnk = files
+            .iter()
+            .find(|file| file["archive_path"] == "Recent/report.docx.lnk")
+            .unwrap();
+        assert_eq!(lnk["sha256"], ABC_SHA256);
+        assert!(lnk["modified"].as_str().unwrap().ends_with('Z'));
+        assert!(files.iter().any(|file| file["archive_path"]
+            == "Recent/AutomaticDestinations/5f7b5f1e01b83767.automaticDestinations-ms"));
+
+        // Collecting into a non-empty directory would mix evidence sets
+        assert!(open_sink(&options).is_err());
+
+        std::fs::remove_dir_all(&source).unwrap();
+        std::fs::remove_dir_all(&output).unwrap();
+    }
+
+    #[test]
+    fn test_collect_to_zip() {
+        let source = source_tree("zip");
+        let output = source.with_extension("zip");
+        let _ = std::fs::remove_file(&output);
+        let options = options(output.clone(), OutputFormat::Zip);
+
+        let mut sink = open_sink(&options).unwrap();
+        let mut manifest = Manifest::default();
+        collect_directory(
+            sink.as_mut(),
+            &source,
+            "Recent",
+            &utils::ScanOptions::new(&["*.lnk"], 0),
+            &mut manifest,
+        );
+        let custody = finish_collection(sink, &options, manifest, SystemTime::now()).unwrap();
+        assert_eq!(custody.file_count, 1);
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["Recent/report.docx.lnk", CUSTODY_NAME, MANIFEST_NAME]
+        );
+
+        let mut content = String::new();
+        archive
+            .by_name("Recent/report.docx.lnk")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "abc");
+
+        let mut custody_json = String::new();
+        archive
+            .by_name(CUSTODY_NAME)
+            .unwrap()
+            .read_to_string(&mut custody_json)
+            .unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&custody_json).unwrap();
+        assert_eq!(stored["manifest_sha256"], custody.manifest_sha256);
+        assert_eq!(stored["format"], "zip");
+
+        std::fs::remove_dir_all(&source).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
+}
@@ -2,7 +2,8 @@ use thiserror::Error;
 
 pub type Result<T = (), E = RecentEnablerError> = std::result::Result<T, E>;
 
-#[derive(Error, Debug, Clone)]
+/// Serializable so the elevated helper can return errors to the GUI unchanged
+#[derive(Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum RecentEnablerError {
     #[error("Failed to get Recent folder path: {0}")]
     RecentFolderNotFound(String),
@@ -19,15 +20,72 @@ pub enum RecentEnablerError {
     #[error("Recent is already enabled")]
     RecentAlreadyEnabled,
 
+    #[error("Failed to read Office File MRU: {0}")]
+    OfficeMruReadFailed(String),
+
+    #[error("Failed to read activity history: {0}")]
+    ActivitiesReadFailed(String),
+
+    #[error("Failed to enable activity history: {0}")]
+    ActivitiesEnableFailed(String),
+
+    #[error("Activity history is already enabled")]
+    ActivitiesAlreadyEnabled,
+
+    #[error("Administrator privileges required to enable activity history")]
+    ActivitiesRequiresAdmin,
+
+    #[error("Failed to read UserAssist registry data: {0}")]
+    UserAssistRegistryReadFailed(String),
+
+    #[error("Failed to enable UserAssist: {0}")]
+    UserAssistEnableFailed(String),
+
+    #[error("UserAssist program tracking is already enabled")]
+    UserAssistAlreadyEnabled,
+
+    #[error("Failed to read ShellBags: {0}")]
+    ShellBagsReadFailed(String),
+
+    #[error("Failed to enable ShellBags: {0}")]
+    ShellBagsEnableFailed(String),
+
+    #[error("ShellBag saving is already enabled")]
+    ShellBagsAlreadyEnabled,
+
     #[error("Failed to get Prefetch folder path: {0}")]
     PrefetchFolderNotFound(String),
 
     #[error("Failed to read Prefetch folder statistics: {0}")]
     PrefetchInfoFailed(String),
 
+    #[error("Failed to parse Prefetch file: {0}")]
+    PrefetchParseFailed(String),
+
+    #[error("Failed to read Amcache: {0}")]
+    AmcacheReadFailed(String),
+
+    #[error("Failed to parse registry hive: {0}")]
+    HiveParseFailed(String),
+
+    #[error("Failed to read ShimCache: {0}")]
+    ShimCacheReadFailed(String),
+
+    #[error("Failed to parse ShimCache: {0}")]
+    ShimCacheParseFailed(String),
+
     #[error("Failed to open Service Control Manager: {0}")]
     ServiceManagerOpenFailed(String),
 
+    #[error("Failed to open {0} service: {1}")]
+    ServiceNotFound(String, String),
+
+    #[error("Failed to query {0} service status: {1}")]
+    ServiceStatusQueryFailed(String, String),
+
+    #[error("Failed to query {0} service configuration: {1}")]
+    ServiceConfigQueryFailed(String, String),
+
     #[error("Failed to open SysMain service: {0}")]
     SysMainServiceNotFound(String),
 
@@ -46,6 +104,84 @@ pub enum RecentEnablerError {
     #[error("Administrator privileges required to enable SysMain service")]
     SysMainRequiresAdmin,
 
+    #[error("Failed to read BAM entries: {0}")]
+    BamReadFailed(String),
+
+    #[error("Failed to enable BAM service: {0}")]
+    BamEnableFailed(String),
+
+    #[error("BAM service is already enabled")]
+    BamAlreadyEnabled,
+
+    #[error("Administrator privileges required to enable BAM service")]
+    BamRequiresAdmin,
+
+    #[error("Failed to parse ESE database: {0}")]
+    EseParseFailed(String),
+
+    #[error("Failed to read SRUM database: {0}")]
+    SrumReadFailed(String),
+
+    #[error("Failed to enable SRUM: {0}")]
+    SrumEnableFailed(String),
+
+    #[error("SRUM is already enabled")]
+    SrumAlreadyEnabled,
+
+    #[error("Administrator privileges required to enable SRUM")]
+    SrumRequiresAdmin,
+
+    #[error("Failed to configure event log channel: {0}")]
+    EventLogEnableFailed(String),
+
+    #[error("All event log channels are already enabled and sized")]
+    EventLogAlreadyEnabled,
+
+    #[error("Administrator privileges required to configure event logs")]
+    EventLogRequiresAdmin,
+
+    #[error("Failed to enable PowerShell logging: {0}")]
+    PowerShellLoggingEnableFailed(String),
+
+    #[error("PowerShell logging is already enabled")]
+    PowerShellLoggingAlreadyEnabled,
+
+    #[error("Administrator privileges required to enable PowerShell logging")]
+    PowerShellLoggingRequiresAdmin,
+
+    #[error("Failed to enable last-access timestamp updates: {0}")]
+    LastAccessEnableFailed(String),
+
+    #[error("Last-access timestamp updates are already enabled")]
+    LastAccessAlreadyEnabled,
+
+    #[error("Administrator privileges required to enable last-access timestamp updates")]
+    LastAccessRequiresAdmin,
+
+    #[error("Failed to query USN journal: {0}")]
+    UsnJournalReadFailed(String),
+
+    #[error("Failed to create USN journal: {0}")]
+    UsnJournalEnableFailed(String),
+
+    #[error("USN journals are already active and sized")]
+    UsnJournalAlreadyEnabled,
+
+    #[error("Administrator privileges required to configure USN journals")]
+    UsnJournalRequiresAdmin,
+
+    #[error("Failed to read audit policy: {0}")]
+    AuditPolicyReadFailed(String),
+
+    #[error("Failed to configure audit policy: {0}")]
+    AuditPolicyEnableFailed(String),
+
+    #[error("Audit policy is already configured")]
+    AuditPolicyAlreadyEnabled,
+
+    #[error("Administrator privileges required to configure audit policy")]
+    AuditPolicyRequiresAdmin,
+
     #[error("Failed to check System Restore status: {0}")]
     SystemRestoreCheckFailed(String),
 
@@ -58,6 +194,39 @@ pub enum RecentEnablerError {
     #[error("System Restore is already enabled")]
     SystemRestoreAlreadyEnabled,
 
+    #[error("Failed to list restore points: {0}")]
+    RestorePointsReadFailed(String),
+
+    #[error("Failed to read shadow copies: {0}")]
+    ShadowCopyReadFailed(String),
+
+    #[error("Failed to export timeline: {0}")]
+    TimelineExportFailed(String),
+
+    #[error("Failed to write readiness report: {0}")]
+    ReportExportFailed(String),
+
+    #[error("Failed to collect evidence: {0}")]
+    CollectFailed(String),
+
+    #[error("Failed to watch folder: {0}")]
+    WatchFailed(String),
+
+    #[error("Failed to export operation history: {0}")]
+    HistoryExportFailed(String),
+
+    #[error("Elevated helper failed: {0}")]
+    BrokerFailed(String),
+
+    #[error("Elevated helper rejected the request: {0}")]
+    BrokerRejected(String),
+
+    #[error("Failed to query process token: {0}")]
+    TokenQueryFailed(String),
+
+    #[error("Failed to enable privilege: {0}")]
+    PrivilegeEnableFailed(String),
+
     #[error("Failed to get Windows system path: {0}")]
     WindowsPathNotFound(String),
 
@@ -83,13 +252,48 @@ impl RecentEnablerError {
             }
             Self::RecentEnableFailed(e) => format!("Не удалось включить Recent: {e}"),
             Self::RecentAlreadyEnabled => "Запись в Recent уже включена".to_string(),
+            Self::OfficeMruReadFailed(e) => {
+                format!("Не удалось прочитать списки документов Office: {e}")
+            }
+            Self::ActivitiesReadFailed(e) => {
+                format!("Не удалось прочитать журнал действий: {e}")
+            }
+            Self::ActivitiesEnableFailed(e) => format!("Не удалось включить журнал действий: {e}"),
+            Self::ActivitiesAlreadyEnabled => "Журнал действий уже включён".to_string(),
+            Self::ActivitiesRequiresAdmin => {
+                "Требуются права администратора для включения журнала действий".to_string()
+            }
+            Self::UserAssistRegistryReadFailed(e) => {
+                format!("Не удалось прочитать данные UserAssist из реестра: {e}")
+            }
+            Self::UserAssistEnableFailed(e) => format!("Не удалось включить UserAssist: {e}"),
+            Self::UserAssistAlreadyEnabled => {
+                "Отслеживание запуска программ (UserAssist) уже включено".to_string()
+            }
+            Self::ShellBagsReadFailed(e) => format!("Не удалось прочитать ShellBags: {e}"),
+            Self::ShellBagsEnableFailed(e) => format!("Не удалось включить ShellBags: {e}"),
+            Self::ShellBagsAlreadyEnabled => "Сохранение ShellBags уже включено".to_string(),
             Self::PrefetchFolderNotFound(e) => format!("Не удалось найти папку Prefetch: {e}"),
             Self::PrefetchInfoFailed(e) => {
                 format!("Не удалось прочитать статистику Prefetch: {e}")
             }
+            Self::PrefetchParseFailed(e) => format!("Не удалось разобрать файл Prefetch: {e}"),
+            Self::AmcacheReadFailed(e) => format!("Не удалось прочитать Amcache: {e}"),
+            Self::HiveParseFailed(e) => format!("Не удалось разобрать файл реестра: {e}"),
+            Self::ShimCacheReadFailed(e) => format!("Не удалось прочитать ShimCache: {e}"),
+            Self::ShimCacheParseFailed(e) => format!("Не удалось разобрать ShimCache: {e}"),
             Self::ServiceManagerOpenFailed(e) => {
                 format!("Не удалось открыть Service Control Manager: {e}")
             }
+            Self::ServiceNotFound(service, e) => {
+                format!("Не удалось открыть службу {service}: {e}")
+            }
+            Self::ServiceStatusQueryFailed(service, e) => {
+                format!("Не удалось получить статус службы {service}: {e}")
+            }
+            Self::ServiceConfigQueryFailed(service, e) => {
+                format!("Не удалось получить конфигурацию службы {service}: {e}")
+            }
             Self::SysMainServiceNotFound(e) => format!("Не удалось открыть службу SysMain: {e}"),
             Self::SysMainStatusQueryFailed(e) => {
                 format!("Не удалось получить статус службы SysMain: {e}")
@@ -102,6 +306,65 @@ impl RecentEnablerError {
             Self::SysMainRequiresAdmin => {
                 "Требуются права администратора для включения службы Prefetch".to_string()
             }
+            Self::BamReadFailed(e) => format!("Не удалось прочитать записи BAM: {e}"),
+            Self::BamEnableFailed(e) => format!("Не удалось включить службу BAM: {e}"),
+            Self::BamAlreadyEnabled => "Служба BAM уже включена".to_string(),
+            Self::BamRequiresAdmin => {
+                "Требуются права администратора для включения службы BAM".to_string()
+            }
+            Self::EseParseFailed(e) => format!("Не удалось разобрать базу данных ESE: {e}"),
+            Self::SrumReadFailed(e) => format!("Не удалось прочитать базу SRUM: {e}"),
+            Self::SrumEnableFailed(e) => format!("Не удалось включить SRUM: {e}"),
+            Self::SrumAlreadyEnabled => "Сбор данных SRUM уже включён".to_string(),
+            Self::SrumRequiresAdmin => {
+                "Требуются права администратора для включения SRUM".to_string()
+            }
+            Self::EventLogEnableFailed(e) => {
+                format!("Не удалось настроить журнал событий: {e}")
+            }
+            Self::EventLogAlreadyEnabled => {
+                "Все журналы событий уже включены и имеют нужный размер".to_string()
+            }
+            Self::EventLogRequiresAdmin => {
+                "Требуются права администратора для настройки журналов событий".to_string()
+            }
+            Self::PowerShellLoggingEnableFailed(e) => {
+                format!("Не удалось включить журналирование PowerShell: {e}")
+            }
+            Self::PowerShellLoggingAlreadyEnabled => {
+                "Журналирование PowerShell уже включено".to_string()
+            }
+            Self::PowerShellLoggingRequiresAdmin => {
+                "Требуются права администратора для включения журналирования PowerShell".to_string()
+            }
+            Self::LastAccessEnableFailed(e) => {
+                format!("Не удалось включить обновление времени доступа: {e}")
+            }
+            Self::LastAccessAlreadyEnabled => {
+                "Обновление времени последнего доступа уже включено".to_string()
+            }
+            Self::LastAccessRequiresAdmin => {
+                "Требуются права администратора для включения обновления времени доступа"
+                    .to_string()
+            }
+            Self::UsnJournalReadFailed(e) => format!("Не удалось запросить журнал USN: {e}"),
+            Self::UsnJournalEnableFailed(e) => format!("Не удалось создать журнал USN: {e}"),
+            Self::UsnJournalAlreadyEnabled => {
+                "Журналы USN уже активны и имеют нужный размер".to_string()
+            }
+            Self::UsnJournalRequiresAdmin => {
+                "Требуются права администратора для настройки журналов USN".to_string()
+            }
+            Self::AuditPolicyReadFailed(e) => {
+                format!("Не удалось прочитать политику аудита: {e}")
+            }
+            Self::AuditPolicyEnableFailed(e) => {
+                format!("Не удалось настроить политику аудита: {e}")
+            }
+            Self::AuditPolicyAlreadyEnabled => "Политика аудита уже настроена".to_string(),
+            Self::AuditPolicyRequiresAdmin => {
+                "Требуются права администратора для настройки политики аудита".to_string()
+            }
             Self::SystemRestoreCheckFailed(e) => {
                 format!("Не удалось проверить статус System Restore: {e}")
             }
@@ -114,6 +377,27 @@ impl RecentEnablerError {
             Self::SystemRestoreAlreadyEnabled => {
                 "System Restore уже включена на диске C:".to_string()
             }
+            Self::RestorePointsReadFailed(e) => {
+                format!("Не удалось получить список точек восстановления: {e}")
+            }
+            Self::ShadowCopyReadFailed(e) => {
+                format!("Не удалось прочитать теневые копии: {e}")
+            }
+            Self::TimelineExportFailed(e) => format!("Не удалось экспортировать хронологию: {e}"),
+            Self::ReportExportFailed(e) => format!("Не удалось сохранить отчёт: {e}"),
+            Self::CollectFailed(e) => format!("Не удалось собрать артефакты: {e}"),
+            Self::WatchFailed(e) => format!("Не удалось отслеживать папку: {e}"),
+            Self::HistoryExportFailed(e) => format!("Не удалось сохранить историю: {e}"),
+            Self::BrokerFailed(e) => {
+                format!("Ошибка помощника с правами администратора: {e}")
+            }
+            Self::BrokerRejected(e) => {
+                format!("Помощник с правами администратора отклонил запрос: {e}")
+            }
+            Self::TokenQueryFailed(e) => {
+                format!("Не удалось получить сведения о токене процесса: {e}")
+            }
+            Self::PrivilegeEnableFailed(e) => format!("Не удалось включить привилегию: {e}"),
             Self::WindowsPathNotFound(e) => {
                 format!("Не удалось получить путь к Windows: {e}")
             }
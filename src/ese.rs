@@ -0,0 +1,1060 @@
+//! Extensible Storage Engine (ESE / JET Blue) database reader
+//!
+//! Walks the catalog and table B-trees of copied databases such as
+//! `SRUDB.dat`. Tagged values compressed with the 7-bit schemes are unpacked;
+//! long values stored outside the record and other compression schemes are
+//! listed in [`EseRecord::unreadable`]. Transaction logs are not replayed, so
+//! dirty databases show the state of their last flush.
+
+use crate::{
+    binary,
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use std::path::Path;
+use std::time::SystemTime;
+
+const FILE_SIGNATURE: u32 = 0x89AB_CDEF;
+/// Father data page of the `MSysObjects` catalog table
+const CATALOG_FDP: u32 = 4;
+const LARGE_PAGE_SIZE: usize = 16384;
+const PAGE_HEADER_SIZE: usize = 40;
+const LARGE_PAGE_HEADER_SIZE: usize = 80;
+
+const PAGE_FLAG_LEAF: u32 = 0x0002;
+
+const TAG_FLAG_DEFUNCT: u8 = 0x02;
+const TAG_FLAG_COMMON_KEY: u8 = 0x04;
+
+const TAGGED_HAS_FLAGS: u16 = 0x4000;
+const TAGGED_FLAG_COMPRESSED: u8 = 0x02;
+const TAGGED_FLAG_LONG_VALUE: u8 = 0x04;
+const VARIABLE_EMPTY: u16 = 0x8000;
+
+/// Compression scheme in the top five bits of a compressed value's first byte
+const COMPRESSION_7BIT_ASCII: u8 = 1;
+const COMPRESSION_7BIT_UNICODE: u8 = 2;
+
+const CATALOG_TYPE_TABLE: i64 = 1;
+const CATALOG_TYPE_COLUMN: i64 = 2;
+
+const CODEPAGE_UTF16: u32 = 1200;
+
+/// Guard against cyclic child pointers in corrupted databases
+const MAX_TREE_DEPTH: usize = 16;
+
+fn parse_error(msg: impl Into<String>) -> RecentEnablerError {
+    RecentEnablerError::EseParseFailed(msg.into())
+}
+
+/// JET column types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Bit,
+    UnsignedByte,
+    Short,
+    Long,
+    Currency,
+    IeeeSingle,
+    IeeeDouble,
+    DateTime,
+    Binary,
+    Text,
+    LongBinary,
+    LongText,
+    UnsignedLong,
+    LongLong,
+    Guid,
+    UnsignedShort,
+    Unknown(u32),
+}
+
+impl ColumnType {
+    #[must_use]
+    pub const fn from_raw(value: u32) -> Self {
+        match value {
+            1 => Self::Bit,
+            2 => Self::UnsignedByte,
+            3 => Self::Short,
+            4 => Self::Long,
+            5 => Self::Currency,
+            6 => Self::IeeeSingle,
+            7 => Self::IeeeDouble,
+            8 => Self::DateTime,
+            9 => Self::Binary,
+            10 => Self::Text,
+            11 => Self::LongBinary,
+            12 => Self::LongText,
+            14 => Self::UnsignedLong,
+            15 => Self::LongLong,
+            16 => Self::Guid,
+            17 => Self::UnsignedShort,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// Size of the column in the fixed data area, `None` for variable types
+    #[must_use]
+    pub const fn fixed_size(self) -> Option<usize> {
+        match self {
+            Self::Bit | Self::UnsignedByte => Some(1),
+            Self::Short | Self::UnsignedShort => Some(2),
+            Self::Long | Self::UnsignedLong | Self::IeeeSingle => Some(4),
+            Self::Currency | Self::IeeeDouble | Self::DateTime | Self::LongLong => Some(8),
+            Self::Guid => Some(16),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EseValue {
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    /// OLE Automation date
+    DateTime(f64),
+    Binary(Vec<u8>),
+    Text(String),
+    Guid(String),
+}
+
+impl EseValue {
+    /// Interpret any integer value as `i64`
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Bool(v) => Some(i64::from(v)),
+            Self::U8(v) => Some(i64::from(v)),
+            Self::I16(v) => Some(i64::from(v)),
+            Self::U16(v) => Some(i64::from(v)),
+            Self::I32(v) => Some(i64::from(v)),
+            Self::U32(v) => Some(i64::from(v)),
+            Self::I64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(s) | Self::Guid(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Interpret a `DateTime` column as `SystemTime`
+    #[must_use]
+    pub fn as_system_time(&self) -> Option<SystemTime> {
+        match *self {
+            Self::DateTime(days) => utils::ole_date_to_system_time(days),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EseColumn {
+    pub id: u32,
+    pub name: String,
+    pub column_type: ColumnType,
+    pub codepage: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct EseTable {
+    pub name: String,
+    fdp_page: u32,
+    pub columns: Vec<EseColumn>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EseRecord {
+    /// Non-null column values in column id order
+    pub values: Vec<(String, EseValue)>,
+    /// Columns holding a value this reader can't decode: a long value stored
+    /// outside the record, or a compression scheme other than 7-bit
+    pub unreadable: Vec<String>,
+}
+
+impl EseRecord {
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&EseValue> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    #[must_use]
+    pub fn is_unreadable(&self, name: &str) -> bool {
+        self.unreadable.iter().any(|n| n == name)
+    }
+}
+
+/// Page tag: flags plus the value with its flag bits cleared
+struct PageEntry {
+    flags: u8,
+    data: Vec<u8>,
+}
+
+pub struct EseDatabase {
+    data: Vec<u8>,
+    page_size: usize,
+    tables: Vec<EseTable>,
+}
+
+impl EseDatabase {
+    /// Parse a database from its raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the header or catalog is invalid
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        if binary::read_u32(&data, 4) != Some(FILE_SIGNATURE) {
+            return Err(parse_error("missing ESE signature"));
+        }
+        let page_size = binary::read_u32(&data, 236)
+            .map(|size| size as usize)
+            .filter(|size| size.is_power_of_two() && *size >= 2048)
+            .ok_or_else(|| parse_error("invalid page size"))?;
+
+        let mut db = Self {
+            data,
+            page_size,
+            tables: Vec::new(),
+        };
+        db.tables = db.read_catalog()?;
+        Ok(db)
+    }
+
+    /// Read and parse a database file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or is not an ESE database
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .map_err(|e| RecentEnablerError::EseParseFailed(format!("{}: {e}", path.display())))?;
+        Self::from_bytes(data)
+    }
+
+    #[must_use]
+    pub fn tables(&self) -> &[EseTable] {
+        &self.tables
+    }
+
+    #[must_use]
+    pub fn table(&self, name: &str) -> Option<&EseTable> {
+        self.tables.iter().find(|t| t.name == name)
+    }
+
+    /// Decode all records of a table
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the table B-tree or a record is corrupted
+    pub fn records(&self, table: &EseTable) -> Result<Vec<EseRecord>> {
+        let large = self.is_large_pages();
+        self.leaf_records(table.fdp_page)?
+            .iter()
+            .map(|data| parse_record(data, &table.columns, large))
+            .collect()
+    }
+
+    const fn is_large_pages(&self) -> bool {
+        self.page_size >= LARGE_PAGE_SIZE
+    }
+
+    fn page(&self, number: u32) -> Result<&[u8]> {
+        // The header and its shadow copy occupy the first two pages
+        let start = (number as usize + 1) * self.page_size;
+        self.data
+            .get(start..start + self.page_size)
+            .ok_or_else(|| parse_error(format!("page {number} out of bounds")))
+    }
+
+    /// Flags and record entries (tags 1..n) of a page
+    fn page_entries(&self, number: u32) -> Result<(u32, Vec<PageEntry>)> {
+        let page = self.page(number)?;
+        let large = self.is_large_pages();
+        let header_size = if large {
+            LARGE_PAGE_HEADER_SIZE
+        } else {
+            PAGE_HEADER_SIZE
+        };
+        let field_mask = if large { 0x7FFF } else { 0x1FFF };
+        let truncated = || parse_error(format!("truncated page {number}"));
+
+        let flags = binary::read_u32(page, 36).ok_or_else(truncated)?;
+        let tag_count = usize::from(binary::read_u16(page, 34).ok_or_else(truncated)?);
+
+        let mut entries = Vec::new();
+        for index in 1..tag_count {
+            let tag_offset = page
+                .len()
+                .checked_sub(4 * (index + 1))
+                .ok_or_else(truncated)?;
+            let size = binary::read_u16(page, tag_offset).ok_or_else(truncated)?;
+            let raw_offset = binary::read_u16(page, tag_offset + 2).ok_or_else(truncated)?;
+            let start = header_size + usize::from(raw_offset & field_mask);
+            let mut data = page
+                .get(start..start + usize::from(size & field_mask))
+                .ok_or_else(truncated)?
+                .to_vec();
+
+            // Large pages keep the tag flags in the top bits of the first word
+            #[allow(clippy::cast_possible_truncation)]
+            let tag_flags = if large {
+                let flags = data.get(1).map_or(0, |b| b >> 5);
+                if let Some(b) = data.get_mut(1) {
+                    *b &= 0x1F;
+                }
+                flags
+            } else {
+                (raw_offset >> 13) as u8
+            };
+
+            entries.push(PageEntry {
+                flags: tag_flags,
+                data,
+            });
+        }
+
+        Ok((flags, entries))
+    }
+
+    /// Collect the record data of every leaf under a B-tree root
+    fn leaf_records(&self, root: u32) -> Result<Vec<Vec<u8>>> {
+        let mut records = Vec::new();
+        self.walk_tree(root, 0, &mut records)?;
+        Ok(records)
+    }
+
+    fn walk_tree(&self, page: u32, depth: usize, records: &mut Vec<Vec<u8>>) -> Result {
+        if depth > MAX_TREE_DEPTH {
+            return Err(parse_error("B-tree too deep"));
+        }
+
+        let (flags, entries) = self.page_entries(page)?;
+        for entry in entries {
+            if entry.flags & TAG_FLAG_DEFUNCT != 0 {
+                continue;
+            }
+            let value = strip_key(&entry)?;
+            if flags & PAGE_FLAG_LEAF != 0 {
+                records.push(value.to_vec());
+            } else {
+                let child = binary::read_u32(value, 0)
+                    .ok_or_else(|| parse_error(format!("missing child page on page {page}")))?;
+                self.walk_tree(child, depth + 1, records)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_catalog(&self) -> Result<Vec<EseTable>> {
+        let columns = catalog_columns();
+        let mut tables: Vec<(i64, EseTable)> = Vec::new();
+        let mut table_columns: Vec<(i64, EseColumn)> = Vec::new();
+
+        for data in self.leaf_records(CATALOG_FDP)? {
+            let record = parse_record(&data, &columns, self.is_large_pages())?;
+            let int = |name| record.get(name).and_then(EseValue::as_i64);
+            let (Some(objid), Some(kind), Some(id), Some(type_or_fdp)) = (
+                int("ObjidTable"),
+                int("Type"),
+                int("Id"),
+                int("ColtypOrPgnoFDP"),
+            ) else {
+                continue;
+            };
+            let name = record
+                .get("Name")
+                .and_then(EseValue::as_str)
+                .unwrap_or_default()
+                .to_string();
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            match kind {
+                CATALOG_TYPE_TABLE => tables.push((
+                    objid,
+                    EseTable {
+                        name,
+                        fdp_page: type_or_fdp as u32,
+                        columns: Vec::new(),
+                    },
+                )),
+                CATALOG_TYPE_COLUMN => table_columns.push((
+                    objid,
+                    EseColumn {
+                        id: id as u32,
+                        name,
+                        column_type: ColumnType::from_raw(type_or_fdp as u32),
+                        codepage: int("PagesOrLocale").unwrap_or(0) as u32,
+                    },
+                )),
+                _ => {}
+            }
+        }
+
+        for (objid, column) in table_columns {
+            if let Some((_, table)) = tables.iter_mut().find(|(id, _)| *id == objid) {
+                table.columns.push(column);
+            }
+        }
+        Ok(tables
+            .into_iter()
+            .map(|(_, mut table)| {
+                table.columns.sort_by_key(|c| c.id);
+                table
+            })
+            .collect())
+    }
+}
+
+/// Fixed schema of `MSysObjects`
+fn catalog_columns() -> Vec<EseColumn> {
+    [
+        (1, "ObjidTable", ColumnType::Long),
+        (2, "Type", ColumnType::Short),
+        (3, "Id", ColumnType::Long),
+        (4, "ColtypOrPgnoFDP", ColumnType::Long),
+        (5, "SpaceUsage", ColumnType::Long),
+        (6, "Flags", ColumnType::Long),
+        (7, "PagesOrLocale", ColumnType::Long),
+        (8, "RootFlag", ColumnType::Bit),
+        (9, "RecordOffset", ColumnType::Short),
+        (10, "LCMapFlags", ColumnType::Long),
+        (11, "KeyMost", ColumnType::Short),
+        (12, "LVChunkMax", ColumnType::Long),
+        (128, "Name", ColumnType::Text),
+    ]
+    .into_iter()
+    .map(|(id, name, column_type)| EseColumn {
+        id,
+        name: name.to_string(),
+        column_type,
+        codepage: 0,
+    })
+    .collect()
+}
+
+/// Skip the key prefix of a page entry
+fn strip_key(entry: &PageEntry) -> Result<&[u8]> {
+    let truncated = || parse_error("truncated page entry");
+    let mut offset = 0;
+    if entry.flags & TAG_FLAG_COMMON_KEY != 0 {
+        offset += 2;
+    }
+    let local_key = usize::from(binary::read_u16(&entry.data, offset).ok_or_else(truncated)?);
+    entry
+        .data
+        .get(offset + 2 + local_key..)
+        .ok_or_else(truncated)
+}
+
+fn decode_value(column: &EseColumn, data: &[u8]) -> Option<EseValue> {
+    let bytes = |n: usize| data.get(..n);
+    Some(match column.column_type {
+        ColumnType::Bit => EseValue::Bool(*data.first()? != 0),
+        ColumnType::UnsignedByte => EseValue::U8(*data.first()?),
+        ColumnType::Short => EseValue::I16(i16::from_le_bytes(bytes(2)?.try_into().ok()?)),
+        ColumnType::UnsignedShort => EseValue::U16(binary::read_u16(data, 0)?),
+        ColumnType::Long => EseValue::I32(i32::from_le_bytes(bytes(4)?.try_into().ok()?)),
+        ColumnType::UnsignedLong => EseValue::U32(binary::read_u32(data, 0)?),
+        ColumnType::Currency | ColumnType::LongLong => {
+            EseValue::I64(i64::from_le_bytes(bytes(8)?.try_into().ok()?))
+        }
+        ColumnType::IeeeSingle => EseValue::F32(f32::from_le_bytes(bytes(4)?.try_into().ok()?)),
+        ColumnType::IeeeDouble => EseValue::F64(f64::from_le_bytes(bytes(8)?.try_into().ok()?)),
+        ColumnType::DateTime => EseValue::DateTime(f64::from_le_bytes(bytes(8)?.try_into().ok()?)),
+        ColumnType::Guid => EseValue::Guid(binary::format_guid(data, 0)?),
+        ColumnType::Text | ColumnType::LongText => {
+            EseValue::Text(if column.codepage == CODEPAGE_UTF16 {
+                binary::read_utf16_z(data, 0)?
+            } else {
+                let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                data[..end].iter().map(|&b| char::from(b)).collect()
+            })
+        }
+        ColumnType::Binary | ColumnType::LongBinary | ColumnType::Unknown(_) => {
+            EseValue::Binary(data.to_vec())
+        }
+    })
+}
+
+/// Unpack a value compressed with the 7-bit ASCII or Unicode scheme
+///
+/// The low three bits of the header byte hold the number of bits used in the
+/// last byte, minus one. Unicode values widen each character to UTF-16LE.
+fn decompress_7bit(data: &[u8]) -> Option<Vec<u8>> {
+    let (&header, packed) = data.split_first()?;
+    let unicode = match header >> 3 {
+        COMPRESSION_7BIT_ASCII => false,
+        COMPRESSION_7BIT_UNICODE => true,
+        _ => return None,
+    };
+    let total_bits = packed.len().checked_sub(1)? * 8 + usize::from(header & 0x07) + 1;
+
+    let mut output = Vec::with_capacity(total_bits / 7 * if unicode { 2 } else { 1 });
+    let mut bytes = packed.iter();
+    let mut buffer = 0u32;
+    let mut buffered = 0;
+    for _ in 0..total_bits / 7 {
+        while buffered < 7 {
+            buffer |= u32::from(*bytes.next()?) << buffered;
+            buffered += 8;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        output.push((buffer & 0x7F) as u8);
+        if unicode {
+            output.push(0);
+        }
+        buffer >>= 7;
+        buffered -= 7;
+    }
+    Some(output)
+}
+
+/// Decode a table record into its non-null column values
+fn parse_record(data: &[u8], columns: &[EseColumn], large_pages: bool) -> Result<EseRecord> {
+    let truncated = || parse_error("truncated record");
+    let last_fixed = u32::from(binary::read_u8(data, 0).ok_or_else(truncated)?);
+    let last_variable = u32::from(binary::read_u8(data, 1).ok_or_else(truncated)?);
+    let variable_offset = usize::from(binary::read_u16(data, 2).ok_or_else(truncated)?);
+    let column = |id: u32| columns.iter().find(|c| c.id == id);
+    let mut record = EseRecord::default();
+
+    // Fixed columns, followed by a null bitmap right before the variable offsets
+    let bitmap_start = variable_offset
+        .checked_sub(last_fixed.div_ceil(8) as usize)
+        .ok_or_else(truncated)?;
+    let mut offset = 4;
+    for id in 1..=last_fixed {
+        // Sizes of later columns are unknown without a definition
+        let Some(def) = column(id) else { break };
+        let Some(size) = def.column_type.fixed_size() else {
+            break;
+        };
+        let bit = (id - 1) as usize;
+        let is_null = data
+            .get(bitmap_start + bit / 8)
+            .is_some_and(|b| b & (1 << (bit % 8)) != 0);
+        let value = data.get(offset..offset + size).ok_or_else(truncated)?;
+        offset += size;
+        if !is_null {
+            if let Some(value) = decode_value(def, value) {
+                record.values.push((def.name.clone(), value));
+            }
+        }
+    }
+
+    // Variable columns: an array of end offsets, high bit marking empty values
+    let variable_count = last_variable.saturating_sub(127) as usize;
+    let variable_data = variable_offset + variable_count * 2;
+    let mut previous_end = 0;
+    for index in 0..variable_count {
+        let raw = binary::read_u16(data, variable_offset + index * 2).ok_or_else(truncated)?;
+        let end = usize::from(raw & !VARIABLE_EMPTY);
+        if raw & VARIABLE_EMPTY == 0 {
+            let value = data
+                .get(variable_data + previous_end..variable_data + end)
+                .ok_or_else(truncated)?;
+            #[allow(clippy::cast_possible_truncation)]
+            if let Some(def) = column(128 + index as u32) {
+                if let Some(value) = decode_value(def, value) {
+                    record.values.push((def.name.clone(), value));
+                }
+            }
+        }
+        previous_end = end;
+    }
+
+    // Tagged columns: (id, offset) pairs, the first offset giving the array size
+    let tagged = data.get(variable_data + previous_end..).unwrap_or_default();
+    let offset_mask = if large_pages { 0x7FFF } else { 0x3FFF };
+    let Some(first) = binary::read_u16(tagged, 2) else {
+        return Ok(record);
+    };
+    let tagged_count = usize::from(first & offset_mask) / 4;
+    for index in 0..tagged_count {
+        let id = binary::read_u16(tagged, index * 4).ok_or_else(truncated)?;
+        let raw = binary::read_u16(tagged, index * 4 + 2).ok_or_else(truncated)?;
+        let start = usize::from(raw & offset_mask);
+        let end = if index + 1 < tagged_count {
+            usize::from(
+                binary::read_u16(tagged, index * 4 + 6).ok_or_else(truncated)? & offset_mask,
+            )
+        } else {
+            tagged.len()
+        };
+        let mut value = std::borrow::Cow::Borrowed(tagged.get(start..end).ok_or_else(truncated)?);
+        let Some(def) = column(u32::from(id)) else {
+            continue;
+        };
+
+        if large_pages || raw & TAGGED_HAS_FLAGS != 0 {
+            let Some((&flags, rest)) = value.split_first() else {
+                continue;
+            };
+            let decompressed = if flags & TAGGED_FLAG_LONG_VALUE != 0 {
+                None
+            } else if flags & TAGGED_FLAG_COMPRESSED != 0 {
+                decompress_7bit(rest)
+            } else {
+                Some(rest.to_vec())
+            };
+            let Some(decompressed) = decompressed else {
+                record.unreadable.push(def.name.clone());
+                continue;
+            };
+            value = std::borrow::Cow::Owned(decompressed);
+        }
+
+        if let Some(value) = decode_value(def, &value) {
+            record.values.push((def.name.clone(), value));
+        }
+    }
+
+    Ok(record)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::ColumnType;
+
+    /// Builds databases with one branch root and two-record leaves per table
+    ///
+    /// Large pages (16 KiB and up) get the 80-byte page header, tag flags in
+    /// the entry data and a flags byte in front of every tagged value.
+    pub struct EseBuilder {
+        page_size: usize,
+        pages: Vec<Vec<u8>>,
+        catalog: Vec<Vec<u8>>,
+        next_objid: i32,
+    }
+
+    /// Pack text with the 7-bit scheme, as ESE does for short tagged values
+    pub fn compress_7bit(text: &str, unicode: bool) -> Vec<u8> {
+        let mut packed = Vec::new();
+        let mut buffer = 0u32;
+        let mut buffered = 0;
+        for byte in text.bytes() {
+            buffer |= u32::from(byte) << buffered;
+            buffered += 7;
+            while buffered >= 8 {
+                packed.push(buffer.to_le_bytes()[0]);
+                buffer >>= 8;
+                buffered -= 8;
+            }
+        }
+        let last_bits = if buffered == 0 {
+            8
+        } else {
+            packed.push(buffer.to_le_bytes()[0]);
+            buffered
+        };
+        let scheme = if unicode {
+            super::COMPRESSION_7BIT_UNICODE
+        } else {
+            super::COMPRESSION_7BIT_ASCII
+        };
+        let mut data = vec![(scheme << 3) | u8::try_from(last_bits - 1).unwrap()];
+        data.extend(packed);
+        data
+    }
+
+    fn keyed(key: u32, data: &[u8]) -> Vec<u8> {
+        let mut entry = 4u16.to_le_bytes().to_vec();
+        entry.extend_from_slice(&key.to_be_bytes());
+        entry.extend_from_slice(data);
+        entry
+    }
+
+    /// Encode a record; `columns` holds `(id, coltyp)` for every defined column
+    pub fn encode_record(columns: &[(u32, u32)], values: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let value = |id: u32| values.iter().find(|(i, _)| *i == id).map(|(_, v)| v);
+        let last_fixed = values
+            .iter()
+            .map(|v| v.0)
+            .filter(|&i| i < 128)
+            .max()
+            .unwrap_or(0);
+        let last_variable = values
+            .iter()
+            .map(|v| v.0)
+            .filter(|&i| (128..256).contains(&i))
+            .max()
+            .unwrap_or(127);
+
+        let mut fixed = Vec::new();
+        let mut bitmap = vec![0u8; last_fixed.div_ceil(8) as usize];
+        for id in 1..=last_fixed {
+            let coltyp = columns.iter().find(|c| c.0 == id).unwrap().1;
+            let size = ColumnType::from_raw(coltyp).fixed_size().unwrap();
+            if let Some(v) = value(id) {
+                fixed.extend_from_slice(v);
+            } else {
+                fixed.resize(fixed.len() + size, 0);
+                bitmap[(id as usize - 1) / 8] |= 1 << ((id - 1) % 8);
+            }
+        }
+
+        let mut offsets = Vec::new();
+        let mut variable = Vec::new();
+        for id in 128..=last_variable {
+            let end = if let Some(v) = value(id) {
+                variable.extend_from_slice(v);
+                u16::try_from(variable.len()).unwrap()
+            } else {
+                u16::try_from(variable.len()).unwrap() | super::VARIABLE_EMPTY
+            };
+            offsets.extend_from_slice(&end.to_le_bytes());
+        }
+
+        let tagged_values: Vec<_> = values.iter().filter(|v| v.0 >= 256).collect();
+        let mut tagged = Vec::new();
+        let mut tagged_data = Vec::new();
+        for (id, v) in &tagged_values {
+            let offset = tagged_values.len() * 4 + tagged_data.len();
+            tagged.extend_from_slice(&u16::try_from(*id).unwrap().to_le_bytes());
+            tagged.extend_from_slice(&u16::try_from(offset).unwrap().to_le_bytes());
+            tagged_data.extend_from_slice(v);
+        }
+
+        let variable_offset = 4 + fixed.len() + bitmap.len();
+        let mut record = vec![
+            u8::try_from(last_fixed).unwrap(),
+            u8::try_from(last_variable).unwrap(),
+        ];
+        record.extend_from_slice(&u16::try_from(variable_offset).unwrap().to_le_bytes());
+        record.extend(fixed);
+        record.extend(bitmap);
+        record.extend(offsets);
+        record.extend(variable);
+        record.extend(tagged);
+        record.extend(tagged_data);
+        record
+    }
+
+    fn catalog_record(
+        objid: i32,
+        kind: i16,
+        id: i32,
+        type_or_fdp: u32,
+        codepage: u32,
+        name: &str,
+    ) -> Vec<u8> {
+        let columns = [(1, 4), (2, 3), (3, 4), (4, 4), (5, 4), (6, 4), (7, 4)];
+        encode_record(
+            &columns,
+            &[
+                (1, objid.to_le_bytes().to_vec()),
+                (2, kind.to_le_bytes().to_vec()),
+                (3, id.to_le_bytes().to_vec()),
+                (4, type_or_fdp.to_le_bytes().to_vec()),
+                (7, codepage.to_le_bytes().to_vec()),
+                (128, name.as_bytes().to_vec()),
+            ],
+        )
+    }
+
+    impl EseBuilder {
+        pub fn with_page_size(page_size: usize) -> Self {
+            Self {
+                page_size,
+                // Pages 1-4 are reserved; the catalog is written to page 4
+                pages: vec![vec![0u8; page_size]; 4],
+                catalog: Vec::new(),
+                next_objid: 100,
+            }
+        }
+
+        const fn is_large_pages(&self) -> bool {
+            self.page_size >= super::LARGE_PAGE_SIZE
+        }
+
+        /// An uncompressed tagged value
+        pub fn tagged(&self, value: &[u8]) -> Vec<u8> {
+            if self.is_large_pages() {
+                [&[0], value].concat()
+            } else {
+                value.to_vec()
+            }
+        }
+
+        /// A tagged value holding `text` packed with the 7-bit Unicode scheme
+        pub fn compressed_text(&self, text: &str) -> Vec<u8> {
+            assert!(self.is_large_pages(), "small pages flag values in the tag");
+            [
+                &[super::TAGGED_FLAG_COMPRESSED][..],
+                &compress_7bit(text, true),
+            ]
+            .concat()
+        }
+
+        fn page(&self, flags: u32, entries: &[Vec<u8>]) -> Vec<u8> {
+            let header_size = if self.is_large_pages() {
+                super::LARGE_PAGE_HEADER_SIZE
+            } else {
+                super::PAGE_HEADER_SIZE
+            };
+            let mut page = vec![0u8; self.page_size];
+            let tag_count = u16::try_from(entries.len() + 1).unwrap();
+            page[34..36].copy_from_slice(&tag_count.to_le_bytes());
+            page[36..40].copy_from_slice(&flags.to_le_bytes());
+
+            let mut offset = 0usize;
+            for (index, entry) in entries.iter().enumerate() {
+                let start = header_size + offset;
+                page[start..start + entry.len()].copy_from_slice(entry);
+                let tag = self.page_size - 4 * (index + 2);
+                page[tag..tag + 2]
+                    .copy_from_slice(&u16::try_from(entry.len()).unwrap().to_le_bytes());
+                page[tag + 2..tag + 4]
+                    .copy_from_slice(&u16::try_from(offset).unwrap().to_le_bytes());
+                offset += entry.len();
+            }
+            assert!(header_size + offset < self.page_size - 4 * (entries.len() + 1));
+            page
+        }
+
+        /// Add a table; `columns` holds `(id, name, coltyp)`, text columns use UTF-16
+        pub fn table(
+            &mut self,
+            name: &str,
+            columns: &[(u32, &str, u32)],
+            rows: &[Vec<(u32, Vec<u8>)>],
+        ) {
+            let objid = self.next_objid;
+            self.next_objid += 1;
+            let types: Vec<(u32, u32)> = columns.iter().map(|c| (c.0, c.2)).collect();
+
+            let mut children = Vec::new();
+            for (chunk_index, chunk) in rows.chunks(2).enumerate() {
+                let entries: Vec<Vec<u8>> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| {
+                        let key = u32::try_from(chunk_index * 2 + i).unwrap();
+                        keyed(key, &encode_record(&types, row))
+                    })
+                    .collect();
+                let leaf = self.page(super::PAGE_FLAG_LEAF, &entries);
+                self.pages.push(leaf);
+                children.push(u32::try_from(self.pages.len()).unwrap());
+            }
+            let branch: Vec<Vec<u8>> = children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| keyed(u32::try_from(i * 2).unwrap(), &child.to_le_bytes()))
+                .collect();
+            let branch = self.page(0, &branch);
+            self.pages.push(branch);
+            let root = u32::try_from(self.pages.len()).unwrap();
+
+            self.catalog
+                .push(catalog_record(objid, 1, objid, root, 0, name));
+            for &(id, column, coltyp) in columns {
+                let codepage = if matches!(coltyp, 10 | 12) { 1200 } else { 0 };
+                self.catalog.push(catalog_record(
+                    objid,
+                    2,
+                    i32::try_from(id).unwrap(),
+                    coltyp,
+                    codepage,
+                    column,
+                ));
+            }
+        }
+
+        pub fn finish(mut self) -> Vec<u8> {
+            let entries: Vec<Vec<u8>> = self
+                .catalog
+                .iter()
+                .enumerate()
+                .map(|(i, record)| keyed(u32::try_from(i).unwrap(), record))
+                .collect();
+            self.pages[3] = self.page(super::PAGE_FLAG_LEAF, &entries);
+
+            let mut header = vec![0u8; self.page_size];
+            header[4..8].copy_from_slice(&super::FILE_SIGNATURE.to_le_bytes());
+            header[8..12].copy_from_slice(&0x620u32.to_le_bytes());
+            header[232..236].copy_from_slice(&0x14u32.to_le_bytes());
+            header[236..240].copy_from_slice(&u32::try_from(self.page_size).unwrap().to_le_bytes());
+
+            let mut data = header.clone();
+            data.extend(header);
+            for page in self.pages {
+                data.extend(page);
+            }
+            data
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{compress_7bit, EseBuilder};
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_read_table() {
+        let columns = [
+            (1, "Id", 4),
+            (2, "Flag", 1),
+            (3, "Time", 8),
+            (128, "Name", 10),
+            (129, "Blob", 9),
+            (256, "Tagged", 11),
+            (257, "Packed", 12),
+        ];
+
+        // SRUDB.dat and other current databases use large pages
+        for page_size in [4096, 32768] {
+            let mut builder = EseBuilder::with_page_size(page_size);
+            let rows: Vec<Vec<(u32, Vec<u8>)>> = (0..3i32)
+                .map(|i| {
+                    let mut row = vec![
+                        (1, i.to_le_bytes().to_vec()),
+                        (3, 44_197.0f64.to_le_bytes().to_vec()),
+                        (128, utf16(&format!("row{i}"))),
+                    ];
+                    if i == 1 {
+                        row.push((2, vec![1]));
+                        row.push((256, builder.tagged(&[0xAA, 0xBB])));
+                        if page_size >= LARGE_PAGE_SIZE {
+                            row.push((257, builder.compressed_text("explorer.exe")));
+                        }
+                    }
+                    row
+                })
+                .collect();
+            builder.table("Test", &columns, &rows);
+            let db = EseDatabase::from_bytes(builder.finish()).unwrap();
+
+            let table = db.table("Test").unwrap();
+            assert_eq!(table.columns.len(), 7);
+            let records = db.records(table).unwrap();
+            assert_eq!(records.len(), 3, "{page_size}");
+
+            let second = &records[1];
+            assert_eq!(second.get("Id").and_then(EseValue::as_i64), Some(1));
+            assert_eq!(second.get("Flag"), Some(&EseValue::Bool(true)));
+            assert_eq!(second.get("Name").and_then(EseValue::as_str), Some("row1"));
+            assert_eq!(
+                second.get("Tagged").and_then(EseValue::as_bytes),
+                Some(&[0xAA, 0xBB][..]),
+                "{page_size}"
+            );
+            let secs = second
+                .get("Time")
+                .and_then(EseValue::as_system_time)
+                .unwrap()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            assert_eq!(secs, 1_609_459_200);
+
+            if page_size >= LARGE_PAGE_SIZE {
+                assert_eq!(
+                    second.get("Packed").and_then(EseValue::as_str),
+                    Some("explorer.exe")
+                );
+            }
+
+            assert!(records[0].get("Flag").is_none());
+            assert!(records[2].get("Tagged").is_none());
+            assert!(records[2].get("Blob").is_none());
+        }
+    }
+
+    #[test]
+    fn test_decompress_7bit() {
+        // 8 characters fill exactly 7 bytes, 7 characters leave one bit in the last byte
+        for text in ["explorer", "nc.exe!", "a"] {
+            assert_eq!(
+                decompress_7bit(&compress_7bit(text, false)).unwrap(),
+                text.as_bytes()
+            );
+        }
+        assert_eq!(
+            decompress_7bit(&compress_7bit("cmd", true)).unwrap(),
+            utf16("cmd")
+        );
+        // Xpress-compressed values are not supported
+        assert_eq!(decompress_7bit(&[0x18, 0x10, 0x00, 0xAA]), None);
+    }
+
+    #[test]
+    fn test_flagged_tagged_values() {
+        let columns = vec![
+            EseColumn {
+                id: 256,
+                name: "Name".to_string(),
+                column_type: ColumnType::LongText,
+                codepage: CODEPAGE_UTF16,
+            },
+            EseColumn {
+                id: 257,
+                name: "Blob".to_string(),
+                column_type: ColumnType::LongBinary,
+                codepage: 0,
+            },
+            EseColumn {
+                id: 258,
+                name: "Packed".to_string(),
+                column_type: ColumnType::LongBinary,
+                codepage: 0,
+            },
+        ];
+        let name = [
+            &[TAGGED_FLAG_COMPRESSED][..],
+            &compress_7bit("nc.exe", true),
+        ]
+        .concat();
+        let long_value = [TAGGED_FLAG_LONG_VALUE, 0x01, 0x00, 0x00, 0x00];
+        let xpress = [TAGGED_FLAG_COMPRESSED, 0x18, 0x20, 0x00, 0xAA];
+
+        // No fixed or variable columns, then three flagged tagged values
+        let mut data = test_support::encode_record(&[], &[]);
+        let mut offset = 12;
+        for (id, value) in [(256u16, &name[..]), (257, &long_value), (258, &xpress)] {
+            data.extend(id.to_le_bytes());
+            data.extend((u16::try_from(offset).unwrap() | TAGGED_HAS_FLAGS).to_le_bytes());
+            offset += value.len();
+        }
+        data.extend(name.iter().chain(&long_value).chain(&xpress));
+
+        let record = parse_record(&data, &columns, false).unwrap();
+        assert_eq!(
+            record.get("Name").and_then(EseValue::as_str),
+            Some("nc.exe")
+        );
+        assert!(record.get("Blob").is_none());
+        assert!(record.is_unreadable("Blob"));
+        assert!(record.is_unreadable("Packed"));
+        assert!(!record.is_unreadable("Name"));
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        assert!(EseDatabase::from_bytes(vec![0u8; 8192]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const MB: u32 = 1024 * 1024;
+
+/// Channels worth enabling for investigations, with their minimum sizes
+pub const DEFAULT_CHANNELS: &[(&str, u32)] = &[
+    ("Security", 256 * MB),
+    ("System", 64 * MB),
+    ("Application", 64 * MB),
+    ("Microsoft-Windows-TaskScheduler/Operational", 64 * MB),
+    (
+        "Microsoft-Windows-DriverFrameworks-UserMode/Operational",
+        32 * MB,
+    ),
+    ("Microsoft-Windows-DNS-Client/Operational", 64 * MB),
+    ("Microsoft-Windows-PowerShell/Operational", 128 * MB),
+    ("Microsoft-Windows-WMI-Activity/Operational", 32 * MB),
+    ("Microsoft-Windows-Bits-Client/Operational", 32 * MB),
+    (
+        "Microsoft-Windows-TerminalServices-LocalSessionManager/Operational",
+        32 * MB,
+    ),
+    ("Microsoft-Windows-CAPI2/Operational", 32 * MB),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelTarget {
+    pub name: String,
+    /// Minimum maximum log size in bytes
+    pub min_size: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelConfig {
+    pub name: String,
+    pub exists: bool,
+    /// Classic log configured under the EventLog service key
+    pub classic: bool,
+    pub enabled: bool,
+    pub max_size: Option<u32>,
+    /// Raw `Retention` value (0 means overwrite as needed)
+    pub retention: Option<u32>,
+}
+
+impl ChannelConfig {
+    /// Whether the channel must be enabled or grown to reach `target`
+    #[must_use]
+    pub fn needs_change(&self, target: &ChannelTarget) -> bool {
+        self.exists && (!self.enabled || self.max_size.is_none_or(|s| s < target.min_size))
+    }
+}
+
+/// Build the default channel list
+#[must_use]
+pub fn default_channels() -> Vec<ChannelTarget> {
+    DEFAULT_CHANNELS
+        .iter()
+        .map(|&(name, min_size)| ChannelTarget {
+            name: name.to_string(),
+            min_size,
+        })
+        .collect()
+}
+
+/// Arguments for `wevtutil set-log` bringing `config` up to `target`
+#[must_use]
+pub fn set_log_args(config: &ChannelConfig, target: &ChannelTarget) -> Vec<String> {
+    let mut args = vec!["set-log".to_string(), target.name.clone()];
+    if !config.classic && !config.enabled {
+        args.push("/enabled:true".to_string());
+    }
+    if config.max_size.is_none_or(|s| s < target.min_size) {
+        args.push(format!("/maxsize:{}", target.min_size));
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(classic: bool, enabled: bool, max_size: Option<u32>) -> ChannelConfig {
+        ChannelConfig {
+            name: "Test/Operational".to_string(),
+            exists: true,
+            classic,
+            enabled,
+            max_size,
+            retention: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_needs_change() {
+        let target = ChannelTarget {
+            name: "Test/Operational".to_string(),
+            min_size: 32 * MB,
+        };
+        assert!(config(false, false, Some(64 * MB)).needs_change(&target));
+        assert!(config(false, true, Some(MB)).needs_change(&target));
+        assert!(config(false, true, None).needs_change(&target));
+        assert!(!config(false, true, Some(32 * MB)).needs_change(&target));
+
+        let missing = ChannelConfig {
+            exists: false,
+            ..config(false, false, None)
+        };
+        assert!(!missing.needs_change(&target));
+    }
+
+    #[test]
+    fn test_set_log_args() {
+        let target = ChannelTarget {
+            name: "Test/Operational".to_string(),
+            min_size: 32 * MB,
+        };
+        assert_eq!(
+            set_log_args(&config(false, false, Some(MB)), &target),
+            [
+                "set-log",
+                "Test/Operational",
+                "/enabled:true",
+                "/maxsize:33554432"
+            ]
+        );
+        assert_eq!(
+            set_log_args(&config(true, true, Some(MB)), &target),
+            ["set-log", "Test/Operational", "/maxsize:33554432"]
+        );
+    }
+}
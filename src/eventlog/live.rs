@@ -0,0 +1,85 @@
+use super::*;
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+const CHANNELS_PATH: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\WINEVT\Channels";
+
+const EVENTLOG_SERVICE_PATH: &str = r"SYSTEM\CurrentControlSet\Services\EventLog";
+
+/// Read a channel's configuration from the registry
+///
+/// Classic logs (Application, Security, System, ...) are read from the
+/// EventLog service key and are always enabled; other channels come from
+/// `WINEVT\Channels`.
+#[must_use]
+pub fn get_channel_config(name: &str) -> ChannelConfig {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let classic_path = format!(r"{EVENTLOG_SERVICE_PATH}\{name}");
+    let channel_path = format!(r"{CHANNELS_PATH}\{name}");
+
+    let classic = hklm.open_subkey(&classic_path).is_ok();
+    let exists = classic || hklm.open_subkey(&channel_path).is_ok();
+    let settings_path = if classic {
+        &classic_path
+    } else {
+        &channel_path
+    };
+    let read = |value| utils::read_reg_dword(HKEY_LOCAL_MACHINE, settings_path, value);
+
+    ChannelConfig {
+        name: name.to_string(),
+        exists,
+        classic,
+        enabled: classic
+            || utils::read_reg_dword(HKEY_LOCAL_MACHINE, &channel_path, "Enabled").unwrap_or(0)
+                != 0,
+        max_size: read("MaxSize"),
+        retention: read("Retention"),
+    }
+}
+
+/// Read the configuration of every channel in `targets`
+#[must_use]
+pub fn get_channel_configs(targets: &[ChannelTarget]) -> Vec<ChannelConfig> {
+    targets
+        .iter()
+        .map(|target| get_channel_config(&target.name))
+        .collect()
+}
+
+/// Enable and grow the channels in `targets` that need it
+///
+/// Changes go through `wevtutil` so the EventLog service applies them
+/// immediately. Missing channels are skipped.
+///
+/// # Errors
+///
+/// Returns error if `wevtutil` fails for any channel
+pub fn enable_channels(targets: &[ChannelTarget]) -> Result {
+    for target in targets {
+        let config = get_channel_config(&target.name);
+        if !config.needs_change(target) {
+            continue;
+        }
+
+        let output = utils::hidden_command("wevtutil")
+            .args(set_log_args(&config, target))
+            .output()
+            .map_err(|e| {
+                RecentEnablerError::EventLogEnableFailed(format!("Failed to run wevtutil: {e}"))
+            })?;
+        if !output.status.success() {
+            return Err(RecentEnablerError::EventLogEnableFailed(format!(
+                "{}: {}",
+                target.name,
+                utils::command_error(&output)
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use recent_enabler::RecentEnablerError;
+use std::time::SystemTime;
+
+/// Oldest entries are dropped past this size
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Success(String),
+    Failure(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub time: SystemTime,
+    pub operation: &'static str,
+    pub outcome: Outcome,
+}
+
+impl HistoryEntry {
+    /// One line of the plain-text export: time, operation and outcome separated by tabs
+    pub fn to_line(&self) -> String {
+        let datetime: chrono::DateTime<chrono::Local> = self.time.into();
+        let (result, details) = match &self.outcome {
+            Outcome::Success(details) => ("OK", details),
+            Outcome::Failure(details) => ("ОШИБКА", details),
+        };
+        format!(
+            "{}\t{}\t{result}\t{details}",
+            datetime.format("%Y-%m-%d %H:%M:%S"),
+            self.operation
+        )
+    }
+}
+
+/// Timestamped log of every check and action run from the window
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Entries, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, operation: &'static str, outcome: Outcome) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(HistoryEntry {
+            time: SystemTime::now(),
+            operation,
+            outcome,
+        });
+    }
+
+    /// Record the result of an operation, using `success` as the message on success
+    pub fn record<T>(
+        &mut self,
+        operation: &'static str,
+        result: &Result<T, RecentEnablerError>,
+        success: &str,
+    ) {
+        let outcome = match result {
+            Ok(_) => Outcome::Success(success.to_string()),
+            Err(e) => Outcome::Failure(e.to_russian()),
+        };
+        self.push(operation, outcome);
+    }
+
+    /// The whole history as tab-separated text, oldest first
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| entry.to_line() + "\n")
+            .collect()
+    }
+}
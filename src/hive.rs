@@ -0,0 +1,520 @@
+//! Offline registry hive (`regf`) reader
+//!
+//! Reads copied hive files such as `Amcache.hve` or `UsrClass.dat` without
+//! loading them into the live registry. Transaction logs are not replayed, so
+//! dirty hives show the state of their last flush.
+
+use crate::{
+    binary,
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use std::path::Path;
+use std::time::SystemTime;
+
+const BASE_BLOCK_SIZE: usize = 0x1000;
+const MAX_INLINE_DATA: usize = 16344;
+const KEY_COMP_NAME: u16 = 0x0020;
+const VALUE_COMP_NAME: u16 = 0x0001;
+const DATA_INLINE: u32 = 0x8000_0000;
+/// Guard against cyclic `ri` index lists in corrupted hives
+const MAX_INDEX_DEPTH: usize = 8;
+
+pub const REG_SZ: u32 = 1;
+pub const REG_EXPAND_SZ: u32 = 2;
+pub const REG_BINARY: u32 = 3;
+pub const REG_DWORD: u32 = 4;
+pub const REG_MULTI_SZ: u32 = 7;
+pub const REG_QWORD: u32 = 11;
+
+fn parse_error(msg: impl Into<String>) -> RecentEnablerError {
+    RecentEnablerError::HiveParseFailed(msg.into())
+}
+
+pub struct Hive {
+    data: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct HiveKey<'a> {
+    hive: &'a Hive,
+    offset: u32,
+    name: String,
+    last_written: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HiveValue {
+    pub name: String,
+    pub value_type: u32,
+    pub data: Vec<u8>,
+}
+
+impl HiveValue {
+    /// Interpret the value as a string (`REG_SZ` / `REG_EXPAND_SZ`)
+    #[must_use]
+    pub fn as_string(&self) -> Option<String> {
+        match self.value_type {
+            REG_SZ | REG_EXPAND_SZ => binary::read_utf16_z(&self.data, 0),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_u32(&self) -> Option<u32> {
+        match self.value_type {
+            REG_DWORD => binary::read_u32(&self.data, 0),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value_type {
+            REG_QWORD => binary::read_u64(&self.data, 0),
+            REG_DWORD => binary::read_u32(&self.data, 0).map(u64::from),
+            _ => None,
+        }
+    }
+}
+
+impl Hive {
+    /// Parse a hive from its raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the base block signature is missing
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        if data.len() < BASE_BLOCK_SIZE || !data.starts_with(b"regf") {
+            return Err(parse_error("missing regf signature"));
+        }
+        Ok(Self { data })
+    }
+
+    /// Read and parse a hive file
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read or is not a hive
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .map_err(|e| RecentEnablerError::HiveParseFailed(format!("{}: {e}", path.display())))?;
+        Self::from_bytes(data)
+    }
+
+    /// Get the root key of the hive
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the root cell is not a valid key node
+    pub fn root(&self) -> Result<HiveKey<'_>> {
+        let offset = binary::read_u32(&self.data, 0x24)
+            .ok_or_else(|| parse_error("truncated base block"))?;
+        self.key_at(offset)
+    }
+
+    /// Data of the cell at a hive-bin relative offset
+    fn cell(&self, offset: u32) -> Result<&[u8]> {
+        let start = BASE_BLOCK_SIZE + offset as usize;
+        let raw_size = binary::read_u32(&self.data, start)
+            .ok_or_else(|| parse_error(format!("cell 0x{offset:X} out of bounds")))?;
+        let size = raw_size.cast_signed().unsigned_abs() as usize;
+        if size < 4 {
+            return Err(parse_error(format!("cell 0x{offset:X} has invalid size")));
+        }
+        self.data
+            .get(start + 4..start + size)
+            .ok_or_else(|| parse_error(format!("cell 0x{offset:X} out of bounds")))
+    }
+
+    fn key_at(&self, offset: u32) -> Result<HiveKey<'_>> {
+        let cell = self.cell(offset)?;
+        if !cell.starts_with(b"nk") {
+            return Err(parse_error(format!("cell 0x{offset:X} is not a key node")));
+        }
+        let truncated = || parse_error(format!("key node 0x{offset:X} is truncated"));
+
+        let flags = binary::read_u16(cell, 2).ok_or_else(truncated)?;
+        let name_len = usize::from(binary::read_u16(cell, 72).ok_or_else(truncated)?);
+        let name_bytes = cell.get(76..76 + name_len).ok_or_else(truncated)?;
+        let name = decode_name(name_bytes, flags & KEY_COMP_NAME != 0);
+        let last_written =
+            utils::filetime_to_system_time(binary::read_u64(cell, 4).ok_or_else(truncated)?);
+
+        Ok(HiveKey {
+            hive: self,
+            offset,
+            name,
+            last_written,
+        })
+    }
+
+    /// Collect key node offsets from an `lf`/`lh`/`li`/`ri` subkey list
+    fn collect_subkeys(&self, list: u32, depth: usize, out: &mut Vec<u32>) -> Result {
+        if depth > MAX_INDEX_DEPTH {
+            return Err(parse_error("subkey index nested too deeply"));
+        }
+        let cell = self.cell(list)?;
+        let count = usize::from(
+            binary::read_u16(cell, 2).ok_or_else(|| parse_error("truncated subkey list"))?,
+        );
+        let (stride, nested) = match cell.get(..2) {
+            Some(b"lf" | b"lh") => (8, false),
+            Some(b"li") => (4, false),
+            Some(b"ri") => (4, true),
+            _ => return Err(parse_error(format!("unknown subkey list at 0x{list:X}"))),
+        };
+
+        for i in 0..count {
+            let offset = binary::read_u32(cell, 4 + i * stride)
+                .ok_or_else(|| parse_error("truncated subkey list"))?;
+            if nested {
+                self.collect_subkeys(offset, depth + 1, out)?;
+            } else {
+                out.push(offset);
+            }
+        }
+        Ok(())
+    }
+
+    fn value_at(&self, offset: u32) -> Result<HiveValue> {
+        let cell = self.cell(offset)?;
+        if !cell.starts_with(b"vk") {
+            return Err(parse_error(format!("cell 0x{offset:X} is not a value")));
+        }
+        let truncated = || parse_error(format!("value 0x{offset:X} is truncated"));
+
+        let name_len = usize::from(binary::read_u16(cell, 2).ok_or_else(truncated)?);
+        let raw_size = binary::read_u32(cell, 4).ok_or_else(truncated)?;
+        let data_offset = binary::read_u32(cell, 8).ok_or_else(truncated)?;
+        let value_type = binary::read_u32(cell, 12).ok_or_else(truncated)?;
+        let flags = binary::read_u16(cell, 16).ok_or_else(truncated)?;
+        let name_bytes = cell.get(20..20 + name_len).ok_or_else(truncated)?;
+
+        let size = (raw_size & !DATA_INLINE) as usize;
+        let data = if raw_size & DATA_INLINE != 0 {
+            cell.get(8..8 + size.min(4)).ok_or_else(truncated)?.to_vec()
+        } else if size == 0 {
+            Vec::new()
+        } else {
+            self.value_data(data_offset, size)?
+        };
+
+        Ok(HiveValue {
+            name: decode_name(name_bytes, flags & VALUE_COMP_NAME != 0),
+            value_type,
+            data,
+        })
+    }
+
+    fn value_data(&self, offset: u32, size: usize) -> Result<Vec<u8>> {
+        let cell = self.cell(offset)?;
+        if size > MAX_INLINE_DATA && cell.starts_with(b"db") {
+            let segments = usize::from(
+                binary::read_u16(cell, 2).ok_or_else(|| parse_error("truncated big data"))?,
+            );
+            let list = self.cell(
+                binary::read_u32(cell, 4).ok_or_else(|| parse_error("truncated big data"))?,
+            )?;
+
+            // The declared size comes from the file; trust only what the segments can hold
+            let mut data = Vec::with_capacity(size.min(segments * MAX_INLINE_DATA));
+            for i in 0..segments {
+                let segment = binary::read_u32(list, i * 4)
+                    .ok_or_else(|| parse_error("truncated big data list"))?;
+                let chunk = self.cell(segment)?;
+                let take = (size - data.len()).min(MAX_INLINE_DATA).min(chunk.len());
+                data.extend_from_slice(&chunk[..take]);
+            }
+            return Ok(data);
+        }
+
+        cell.get(..size)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| parse_error(format!("value data 0x{offset:X} is truncated")))
+    }
+}
+
+fn decode_name(bytes: &[u8], compressed: bool) -> String {
+    if compressed {
+        bytes.iter().map(|&b| char::from(b)).collect()
+    } else {
+        binary::read_utf16_z(bytes, 0).unwrap_or_default()
+    }
+}
+
+impl<'a> HiveKey<'a> {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub const fn last_written(&self) -> Option<SystemTime> {
+        self.last_written
+    }
+
+    fn node(&self) -> Result<&'a [u8]> {
+        self.hive.cell(self.offset)
+    }
+
+    /// List the direct subkeys of this key
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the subkey index is corrupted
+    pub fn subkeys(&self) -> Result<Vec<HiveKey<'a>>> {
+        let node = self.node()?;
+        let count = binary::read_u32(node, 20).unwrap_or(0);
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let list = binary::read_u32(node, 28).ok_or_else(|| parse_error("truncated key node"))?;
+
+        let mut offsets = Vec::new();
+        self.hive.collect_subkeys(list, 0, &mut offsets)?;
+        offsets.into_iter().map(|o| self.hive.key_at(o)).collect()
+    }
+
+    /// Find a direct subkey by name (case-insensitive)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the subkey index is corrupted
+    pub fn subkey(&self, name: &str) -> Result<Option<HiveKey<'a>>> {
+        Ok(self
+            .subkeys()?
+            .into_iter()
+            .find(|k| k.name.eq_ignore_ascii_case(name)))
+    }
+
+    /// Open a descendant key by backslash-separated path
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a key along the path is corrupted
+    pub fn open(&self, path: &str) -> Result<Option<HiveKey<'a>>> {
+        let mut key = self.clone();
+        for part in path.split('\\').filter(|p| !p.is_empty()) {
+            match key.subkey(part)? {
+                Some(child) => key = child,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(key))
+    }
+
+    /// List the values of this key
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the value list is corrupted
+    pub fn values(&self) -> Result<Vec<HiveValue>> {
+        let node = self.node()?;
+        let count = binary::read_u32(node, 36).unwrap_or(0) as usize;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let list_offset =
+            binary::read_u32(node, 40).ok_or_else(|| parse_error("truncated key node"))?;
+        let list = self.hive.cell(list_offset)?;
+
+        (0..count)
+            .map(|i| {
+                let offset = binary::read_u32(list, i * 4)
+                    .ok_or_else(|| parse_error("truncated value list"))?;
+                self.hive.value_at(offset)
+            })
+            .collect()
+    }
+
+    /// Find a value by name (case-insensitive, empty name is the default value)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the value list is corrupted
+    pub fn value(&self, name: &str) -> Result<Option<HiveValue>> {
+        Ok(self
+            .values()?
+            .into_iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name)))
+    }
+}
+
+/// Minimal hive writer used to build fixtures for parser tests
+#[cfg(test)]
+pub(crate) mod test_support {
+    pub struct HiveBuilder {
+        bins: Vec<u8>,
+    }
+
+    impl HiveBuilder {
+        pub fn new() -> Self {
+            let mut bins = b"hbin".to_vec();
+            bins.resize(32, 0);
+            Self { bins }
+        }
+
+        fn alloc(&mut self, data: &[u8]) -> u32 {
+            let offset = u32::try_from(self.bins.len()).unwrap();
+            let size = (data.len() + 4).next_multiple_of(8);
+            let header = -i32::try_from(size).unwrap();
+            self.bins.extend_from_slice(&header.to_le_bytes());
+            self.bins.extend_from_slice(data);
+            self.bins.resize(offset as usize + size, 0);
+            offset
+        }
+
+        pub fn value(&mut self, name: &str, value_type: u32, data: &[u8]) -> u32 {
+            let len = u32::try_from(data.len()).unwrap();
+            let (size, data_offset) = if data.len() <= 4 {
+                let mut inline = [0u8; 4];
+                inline[..data.len()].copy_from_slice(data);
+                (len | super::DATA_INLINE, u32::from_le_bytes(inline))
+            } else {
+                (len, self.alloc(data))
+            };
+            self.value_key(name, value_type, size, data_offset)
+        }
+
+        /// Value stored as a `db` big data cell; `size` may disagree with the segments
+        pub fn big_data_value(&mut self, name: &str, size: u32, segments: &[&[u8]]) -> u32 {
+            let list: Vec<u8> = segments
+                .iter()
+                .flat_map(|segment| self.alloc(segment).to_le_bytes())
+                .collect();
+            let list = self.alloc(&list);
+            let mut db = b"db".to_vec();
+            db.extend_from_slice(&u16::try_from(segments.len()).unwrap().to_le_bytes());
+            db.extend_from_slice(&list.to_le_bytes());
+            let data_offset = self.alloc(&db);
+            self.value_key(name, super::REG_BINARY, size, data_offset)
+        }
+
+        fn value_key(&mut self, name: &str, value_type: u32, size: u32, data_offset: u32) -> u32 {
+            let mut vk = b"vk".to_vec();
+            vk.extend_from_slice(&u16::try_from(name.len()).unwrap().to_le_bytes());
+            vk.extend_from_slice(&size.to_le_bytes());
+            vk.extend_from_slice(&data_offset.to_le_bytes());
+            vk.extend_from_slice(&value_type.to_le_bytes());
+            vk.extend_from_slice(&super::VALUE_COMP_NAME.to_le_bytes());
+            vk.extend_from_slice(&[0, 0]);
+            vk.extend_from_slice(name.as_bytes());
+            self.alloc(&vk)
+        }
+
+        pub fn string_value(&mut self, name: &str, value: &str) -> u32 {
+            let data: Vec<u8> = value
+                .encode_utf16()
+                .chain(Some(0))
+                .flat_map(u16::to_le_bytes)
+                .collect();
+            self.value(name, super::REG_SZ, &data)
+        }
+
+        pub fn key(&mut self, name: &str, filetime: u64, subkeys: &[u32], values: &[u32]) -> u32 {
+            let subkey_list = if subkeys.is_empty() {
+                u32::MAX
+            } else {
+                let mut lf = b"lf".to_vec();
+                lf.extend_from_slice(&u16::try_from(subkeys.len()).unwrap().to_le_bytes());
+                for offset in subkeys {
+                    lf.extend_from_slice(&offset.to_le_bytes());
+                    lf.extend_from_slice(&[0; 4]);
+                }
+                self.alloc(&lf)
+            };
+            let value_list = if values.is_empty() {
+                u32::MAX
+            } else {
+                let list: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+                self.alloc(&list)
+            };
+
+            let mut nk = vec![0u8; 76];
+            nk[0..2].copy_from_slice(b"nk");
+            nk[2..4].copy_from_slice(&super::KEY_COMP_NAME.to_le_bytes());
+            nk[4..12].copy_from_slice(&filetime.to_le_bytes());
+            nk[20..24].copy_from_slice(&u32::try_from(subkeys.len()).unwrap().to_le_bytes());
+            nk[28..32].copy_from_slice(&subkey_list.to_le_bytes());
+            nk[36..40].copy_from_slice(&u32::try_from(values.len()).unwrap().to_le_bytes());
+            nk[40..44].copy_from_slice(&value_list.to_le_bytes());
+            nk[72..74].copy_from_slice(&u16::try_from(name.len()).unwrap().to_le_bytes());
+            nk.extend_from_slice(name.as_bytes());
+            self.alloc(&nk)
+        }
+
+        pub fn finish(mut self, root: u32) -> Vec<u8> {
+            let bins_len = self.bins.len().next_multiple_of(super::BASE_BLOCK_SIZE);
+            self.bins.resize(bins_len, 0);
+            self.bins[8..12].copy_from_slice(&u32::try_from(bins_len).unwrap().to_le_bytes());
+
+            let mut hive = vec![0u8; super::BASE_BLOCK_SIZE];
+            hive[0..4].copy_from_slice(b"regf");
+            hive[0x24..0x28].copy_from_slice(&root.to_le_bytes());
+            hive[0x28..0x2C].copy_from_slice(&u32::try_from(bins_len).unwrap().to_le_bytes());
+            hive.extend_from_slice(&self.bins);
+            hive
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::HiveBuilder;
+    use super::*;
+
+    #[test]
+    fn test_read_keys_and_values() {
+        let mut builder = HiveBuilder::new();
+        let name = builder.string_value("Name", "notepad.exe");
+        let size = builder.value("Size", REG_QWORD, &1234u64.to_le_bytes());
+        let flag = builder.value("Flag", REG_DWORD, &7u32.to_le_bytes());
+        let leaf = builder.key("Leaf", 132_539_328_000_000_000, &[], &[name, size, flag]);
+        let parent = builder.key("Parent", 0, &[leaf], &[]);
+        let root = builder.key("ROOT", 0, &[parent], &[]);
+        let hive = Hive::from_bytes(builder.finish(root)).unwrap();
+
+        let root = hive.root().unwrap();
+        assert_eq!(root.name(), "ROOT");
+
+        let leaf = root.open(r"parent\LEAF").unwrap().unwrap();
+        assert_eq!(leaf.name(), "Leaf");
+        assert!(leaf.last_written().is_some());
+
+        let value = leaf.value("name").unwrap().unwrap();
+        assert_eq!(value.as_string().as_deref(), Some("notepad.exe"));
+        assert_eq!(leaf.value("Size").unwrap().unwrap().as_u64(), Some(1234));
+        assert_eq!(leaf.value("Flag").unwrap().unwrap().as_u32(), Some(7));
+        assert!(leaf.value("Missing").unwrap().is_none());
+        assert!(root.open("Parent\\Missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_big_data_values() {
+        let first = vec![0xAB; MAX_INLINE_DATA];
+        let mut builder = HiveBuilder::new();
+        let big = builder.big_data_value(
+            "Big",
+            u32::try_from(MAX_INLINE_DATA + 10).unwrap(),
+            &[&first, &[0xCD; 10]],
+        );
+        // A corrupt size far beyond the single segment it points to
+        let corrupt = builder.big_data_value("Corrupt", 0x7FFF_FFF0, &[&[0xEF; 100]]);
+        let root = builder.key("ROOT", 0, &[], &[big, corrupt]);
+        let hive = Hive::from_bytes(builder.finish(root)).unwrap();
+        let root = hive.root().unwrap();
+
+        let big = root.value("Big").unwrap().unwrap().data;
+        assert_eq!(big.len(), MAX_INLINE_DATA + 10);
+        assert_eq!(big[MAX_INLINE_DATA..], [0xCD; 10]);
+
+        let corrupt = root.value("Corrupt").unwrap().unwrap().data;
+        assert_eq!(corrupt, [0xEF; 100]);
+    }
+
+    #[test]
+    fn test_rejects_non_hive() {
+        assert!(Hive::from_bytes(vec![0u8; BASE_BLOCK_SIZE]).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::utils;
+
+/// KNOWNFOLDERID GUIDs that Explorer embeds in artifact paths, mapped to
+/// environment-based templates of their default locations.
+const KNOWN_FOLDERS: &[(&str, &str)] = &[
+    (
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}",
+        r"%SystemRoot%\System32",
+    ),
+    (
+        "{D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27}",
+        r"%SystemRoot%\SysWOW64",
+    ),
+    ("{F38BF404-1D43-42F2-9305-67DE0B28FC23}", "%SystemRoot%"),
+    ("{905E63B6-C1BF-494E-B29C-65B732D3D21A}", "%ProgramFiles%"),
+    ("{6D809377-6AF0-444B-8957-A3773F02200E}", "%ProgramW6432%"),
+    (
+        "{7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E}",
+        "%ProgramFiles(x86)%",
+    ),
+    (
+        "{F7F1ED05-9F6D-47A2-AAAE-29D317C6F066}",
+        "%CommonProgramFiles%",
+    ),
+    (
+        "{6365D5A7-0F0D-45E5-87F6-0DA56B6A4F7D}",
+        "%CommonProgramW6432%",
+    ),
+    (
+        "{DE974D24-D9C6-4D3E-BF91-F4455120B917}",
+        "%CommonProgramFiles(x86)%",
+    ),
+    ("{62AB5D82-FDC1-4DC3-A9DD-070D1D495D97}", "%ProgramData%"),
+    (
+        "{A4115719-D62E-491D-AA7C-E74B8BE3B067}",
+        r"%ProgramData%\Microsoft\Windows\Start Menu",
+    ),
+    (
+        "{0139D44E-6AFE-49F2-8690-3DAFCAE6FFB8}",
+        r"%ProgramData%\Microsoft\Windows\Start Menu\Programs",
+    ),
+    ("{5E6C858F-0E22-4760-9AFE-EA3317B67173}", "%USERPROFILE%"),
+    (
+        "{B4BFCC3A-DB2C-424C-B029-7FE99A87C641}",
+        r"%USERPROFILE%\Desktop",
+    ),
+    (
+        "{FDD39AD0-238F-46AF-ADB4-6C85480369C7}",
+        r"%USERPROFILE%\Documents",
+    ),
+    (
+        "{374DE290-123F-4565-9164-39C4925E467B}",
+        r"%USERPROFILE%\Downloads",
+    ),
+    (
+        "{A520A1A4-1780-4FF6-BD18-167343C5AF16}",
+        r"%USERPROFILE%\AppData\LocalLow",
+    ),
+    ("{F1B32785-6FBA-4FCF-9D55-7B8E7F157091}", "%LOCALAPPDATA%"),
+    ("{3EB685DB-65F9-4CF6-A03A-E3EF65729F3D}", "%APPDATA%"),
+    (
+        "{625B53C3-AB48-4EC1-BA1F-A1EF4146FC19}",
+        r"%APPDATA%\Microsoft\Windows\Start Menu",
+    ),
+    (
+        "{A77F5D77-2E2B-44C3-A6A2-ABA601054A51}",
+        r"%APPDATA%\Microsoft\Windows\Start Menu\Programs",
+    ),
+    (
+        "{AE50C081-EBD2-438A-8655-8A092E34987A}",
+        r"%APPDATA%\Microsoft\Windows\Recent",
+    ),
+    (
+        "{9E3995AB-1F9C-4F13-B827-48B24B6C7174}",
+        r"%APPDATA%\Microsoft\Internet Explorer\Quick Launch\User Pinned",
+    ),
+];
+
+/// Look up the default location template for a KNOWNFOLDERID
+#[must_use]
+pub fn known_folder_template(guid: &str) -> Option<&'static str> {
+    KNOWN_FOLDERS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(guid))
+        .map(|(_, template)| *template)
+}
+
+/// Replace a leading `{KNOWNFOLDERID}` in a path with the folder location
+///
+/// Paths that don't start with a known GUID are returned unchanged.
+#[must_use]
+pub fn substitute_known_folder(path: &str) -> String {
+    if !path.starts_with('{') {
+        return path.to_string();
+    }
+    let Some(end) = path.find('}') else {
+        return path.to_string();
+    };
+
+    let (guid, rest) = path.split_at(end + 1);
+    known_folder_template(guid).map_or_else(
+        || path.to_string(),
+        |template| format!("{}{rest}", utils::expand_env_vars(template)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_known_folder() {
+        let path =
+            substitute_known_folder(r"{6D809377-6AF0-444B-8957-A3773F02200E}\7-Zip\7zFM.exe");
+        assert!(!path.starts_with('{'));
+        assert!(path.ends_with(r"\7-Zip\7zFM.exe"));
+
+        let unknown = r"{00000000-0000-0000-0000-000000000000}\tool.exe";
+        assert_eq!(substitute_known_folder(unknown), unknown);
+        assert_eq!(substitute_known_folder(r"C:\tool.exe"), r"C:\tool.exe");
+    }
+}
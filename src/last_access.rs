@@ -0,0 +1,131 @@
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Set when Windows manages the value itself (Windows 10 1803+)
+const SYSTEM_MANAGED_FLAG: u32 = 0x2;
+/// Set when the value was chosen explicitly rather than left to defaults
+const MODE_FLAG: u32 = 0x8000_0000;
+/// Set when last-access updates are suppressed
+const DISABLED_FLAG: u32 = 0x1;
+
+/// `NtfsDisableLastAccessUpdate` value enabling user-managed updates
+pub const USER_MANAGED_ENABLED: u32 = MODE_FLAG;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastAccessMode {
+    UserManagedEnabled,
+    UserManagedDisabled,
+    /// Windows enabled updates (typically on volumes up to 128 GB)
+    SystemManagedEnabled,
+    /// Windows disabled updates (typically on volumes larger than 128 GB)
+    SystemManagedDisabled,
+}
+
+impl LastAccessMode {
+    /// Decode a raw `NtfsDisableLastAccessUpdate` value
+    ///
+    /// Legacy values without the high bit (0 and 1) are treated as user managed.
+    #[must_use]
+    pub const fn from_value(value: u32) -> Self {
+        let disabled = value & DISABLED_FLAG != 0;
+        let system_managed = value & MODE_FLAG != 0 && value & SYSTEM_MANAGED_FLAG != 0;
+        match (system_managed, disabled) {
+            (false, false) => Self::UserManagedEnabled,
+            (false, true) => Self::UserManagedDisabled,
+            (true, false) => Self::SystemManagedEnabled,
+            (true, true) => Self::SystemManagedDisabled,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        matches!(self, Self::UserManagedEnabled | Self::SystemManagedEnabled)
+    }
+
+    #[must_use]
+    pub const fn is_system_managed(self) -> bool {
+        matches!(
+            self,
+            Self::SystemManagedEnabled | Self::SystemManagedDisabled
+        )
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UserManagedEnabled => "Включено пользователем",
+            Self::UserManagedDisabled => "Отключено пользователем",
+            Self::SystemManagedEnabled => "Включено системой",
+            Self::SystemManagedDisabled => "Отключено системой",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastAccessSetting {
+    /// Raw registry value, `None` if it isn't set
+    pub value: Option<u32>,
+    pub mode: LastAccessMode,
+    /// Updates were switched on or off since the last boot and the change
+    /// isn't in effect yet
+    pub reboot_required: bool,
+}
+
+/// Whether `current` differs from the setting NTFS read at boot
+///
+/// `boot_value` is the raw value recorded before the first change this
+/// program made since boot, `None` if it made none.
+#[must_use]
+pub fn reboot_required(boot_value: Option<u32>, current: LastAccessMode) -> bool {
+    boot_value
+        .is_some_and(|value| LastAccessMode::from_value(value).is_enabled() != current.is_enabled())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_value() {
+        assert_eq!(
+            LastAccessMode::from_value(0x8000_0000),
+            LastAccessMode::UserManagedEnabled
+        );
+        assert_eq!(
+            LastAccessMode::from_value(0x8000_0001),
+            LastAccessMode::UserManagedDisabled
+        );
+        assert_eq!(
+            LastAccessMode::from_value(0x8000_0002),
+            LastAccessMode::SystemManagedEnabled
+        );
+        assert_eq!(
+            LastAccessMode::from_value(0x8000_0003),
+            LastAccessMode::SystemManagedDisabled
+        );
+        assert_eq!(
+            LastAccessMode::from_value(0),
+            LastAccessMode::UserManagedEnabled
+        );
+        assert_eq!(
+            LastAccessMode::from_value(1),
+            LastAccessMode::UserManagedDisabled
+        );
+
+        assert!(LastAccessMode::from_value(USER_MANAGED_ENABLED).is_enabled());
+        assert!(!LastAccessMode::from_value(0x8000_0003).is_enabled());
+        assert!(LastAccessMode::from_value(0x8000_0003).is_system_managed());
+    }
+
+    #[test]
+    fn test_reboot_required() {
+        let enabled = LastAccessMode::UserManagedEnabled;
+        assert!(reboot_required(Some(0x8000_0003), enabled));
+        assert!(reboot_required(Some(DISABLED_FLAG), enabled));
+        // Switching from system- to user-managed updates changes nothing NTFS does
+        assert!(!reboot_required(Some(0x8000_0002), enabled));
+        assert!(!reboot_required(None, enabled));
+    }
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WRITE, REG_OPTION_VOLATILE};
+use winreg::RegKey;
+
+const FILESYSTEM_PATH: &str = r"SYSTEM\CurrentControlSet\Control\FileSystem";
+
+const DISABLE_LAST_ACCESS_VALUE: &str = "NtfsDisableLastAccessUpdate";
+
+/// Volatile key, dropped by Windows at shutdown, holding the value NTFS read at boot
+const BOOT_STATE_PATH: &str = r"SOFTWARE\RecentEnabler";
+
+const BOOT_VALUE: &str = "NtfsDisableLastAccessUpdateAtBoot";
+
+fn read_disable_value() -> Option<u32> {
+    utils::read_reg_dword(
+        HKEY_LOCAL_MACHINE,
+        FILESYSTEM_PATH,
+        DISABLE_LAST_ACCESS_VALUE,
+    )
+}
+
+/// Remember the value in effect before the first change since boot
+fn record_boot_value() -> std::io::Result<()> {
+    let (key, _) = RegKey::predef(HKEY_LOCAL_MACHINE).create_subkey_with_options_flags(
+        BOOT_STATE_PATH,
+        REG_OPTION_VOLATILE,
+        KEY_READ | KEY_WRITE,
+    )?;
+    if key.get_value::<u32, _>(BOOT_VALUE).is_ok() {
+        return Ok(());
+    }
+    // A missing value disables updates, as legacy 1 does
+    key.set_value(BOOT_VALUE, &read_disable_value().unwrap_or(DISABLED_FLAG))
+}
+
+/// Read the `NtfsDisableLastAccessUpdate` setting
+///
+/// A missing value means the pre-1803 default, which disables updates.
+/// NTFS reads the value at boot, so a reboot is flagged when
+/// [`enable_last_access`] switched updates on since the system started.
+/// Changes made by other tools are not tracked.
+#[must_use]
+pub fn get_last_access_setting() -> LastAccessSetting {
+    let value = read_disable_value();
+    let mode = value.map_or(
+        LastAccessMode::UserManagedDisabled,
+        LastAccessMode::from_value,
+    );
+    let boot_value = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(BOOT_STATE_PATH)
+        .ok()
+        .and_then(|key| key.get_value(BOOT_VALUE).ok());
+
+    LastAccessSetting {
+        value,
+        mode,
+        reboot_required: reboot_required(boot_value, mode),
+    }
+}
+
+/// Check if NTFS last-access timestamp updates are disabled
+///
+/// # Errors
+///
+/// Returns error if registry cannot be read
+pub fn is_last_access_disabled() -> Result<bool> {
+    Ok(!get_last_access_setting().mode.is_enabled())
+}
+
+/// Enable user-managed last-access updates
+///
+/// Takes effect after a reboot; the value in effect until then is recorded
+/// in a volatile key so [`get_last_access_setting`] can report the pending reboot.
+///
+/// # Errors
+///
+/// Returns error if the value cannot be written
+pub fn enable_last_access() -> Result {
+    record_boot_value().map_err(|e| RecentEnablerError::LastAccessEnableFailed(e.to_string()))?;
+    utils::write_reg_dword(
+        HKEY_LOCAL_MACHINE,
+        FILESYSTEM_PATH,
+        DISABLE_LAST_ACCESS_VALUE,
+        USER_MANAGED_ENABLED,
+    )
+    .map_err(|e| RecentEnablerError::LastAccessEnableFailed(e.to_string()))
+}
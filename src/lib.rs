@@ -1,19 +1,60 @@
+#[cfg(all(feature = "live", not(windows)))]
+compile_error!("the `live` feature reads the running Windows system; build with `--no-default-features` elsewhere");
+
+pub mod activities;
+pub mod amcache;
+pub mod audit_policy;
+pub mod bam;
+mod binary;
+pub mod broker;
+#[cfg(feature = "live")]
+pub mod collect;
 pub mod error;
+pub mod ese;
+pub mod eventlog;
+pub mod hive;
+pub mod known_folders;
+pub mod last_access;
+pub mod office_mru;
+pub mod powershell_logging;
+pub mod prefetch;
 pub mod recent;
+pub mod report;
+#[cfg(feature = "live")]
 pub mod service;
+pub mod shell_item;
+pub mod shellbags;
+pub mod shimcache;
+pub mod srum;
 pub mod status;
 pub mod sysmain;
 pub mod system_restore;
+pub mod timeline;
+pub mod token;
+pub mod userassist;
+pub mod usn_journal;
 pub mod utils;
+pub mod vss;
+pub mod watcher;
+mod xpress;
 
 // Public, stable-ish API surface for consumers (UI / other crates)
 
+#[cfg(feature = "live")]
 pub use crate::service::{
-    check_recent, check_sysmain, check_system_restore, enable_recent, enable_sysmain,
-    enable_system_restore,
+    check_activities, check_amcache, check_audit_policy, check_bam, check_eventlog,
+    check_last_access, check_powershell_logging, check_recent, check_shadow_copies,
+    check_shellbags, check_srum, check_sysmain, check_system_restore, check_userassist,
+    check_usn_journal, enable_activities, enable_audit_policy, enable_bam, enable_eventlog,
+    enable_last_access, enable_powershell_logging, enable_recent, enable_shellbags, enable_srum,
+    enable_sysmain, enable_system_restore, enable_userassist, enable_usn_journal,
 };
 
-pub use crate::status::{RecentStatus, SysMainStatus, SystemRestoreStatus};
+pub use crate::status::{
+    ActivitiesStatus, AmcacheStatus, AuditPolicyStatus, BamStatus, EventLogStatus,
+    LastAccessStatus, PowerShellLoggingStatus, RecentStatus, ShadowCopyStatus, ShellBagsStatus,
+    SrumStatus, SysMainStatus, SystemRestoreStatus, UserAssistStatus, UsnJournalStatus,
+};
 
 pub use crate::error::{RecentEnablerError, Result};
 
@@ -21,10 +62,19 @@ pub use crate::utils::{is_admin, restart_as_admin};
 
 pub mod prelude {
     pub use crate::error::{RecentEnablerError, Result};
+    #[cfg(feature = "live")]
     pub use crate::service::{
-        check_recent, check_sysmain, check_system_restore, enable_recent, enable_sysmain,
-        enable_system_restore,
+        check_activities, check_amcache, check_audit_policy, check_bam, check_eventlog,
+        check_last_access, check_powershell_logging, check_recent, check_shadow_copies,
+        check_shellbags, check_srum, check_sysmain, check_system_restore, check_userassist,
+        check_usn_journal, enable_activities, enable_audit_policy, enable_bam, enable_eventlog,
+        enable_last_access, enable_powershell_logging, enable_recent, enable_shellbags,
+        enable_srum, enable_sysmain, enable_system_restore, enable_userassist, enable_usn_journal,
+    };
+    pub use crate::status::{
+        ActivitiesStatus, AmcacheStatus, AuditPolicyStatus, BamStatus, EventLogStatus,
+        LastAccessStatus, PowerShellLoggingStatus, RecentStatus, ShadowCopyStatus, ShellBagsStatus,
+        SrumStatus, SysMainStatus, SystemRestoreStatus, UserAssistStatus, UsnJournalStatus,
     };
-    pub use crate::status::{RecentStatus, SysMainStatus, SystemRestoreStatus};
     pub use crate::utils::{is_admin, restart_as_admin};
 }
@@ -1,12 +1,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod history;
 mod ui;
 
 use iced::Theme;
+use recent_enabler::{broker, collect, report, utils};
+use std::path::Path;
 
 fn main() -> iced::Result {
-    iced::application(app::init, app::update, app::view)
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let pending_action = broker::take_pending_action(&mut args);
+    if let Some(code) = run_cli(&args) {
+        std::process::exit(code);
+    }
+
+    iced::application(move || app::init(pending_action), app::update, app::view)
+        .subscription(app::subscription)
         .theme(|_: &_| Theme::Dark)
         .window(iced::window::Settings {
             size: iced::Size::new(600.0, 800.0),
@@ -14,3 +24,72 @@ fn main() -> iced::Result {
         })
         .run()
 }
+
+/// Handle command-line modes that run without opening the window
+///
+/// `--report [path]` writes the readiness report (to the desktop by default).
+/// `--collect <path>` collects artifacts into a ZIP (for `.zip` paths) or directory.
+/// `--broker <pipe>` serves privileged operations for a non-elevated window.
+fn run_cli(args: &[String]) -> Option<i32> {
+    match args {
+        [flag, pipe] if flag == broker::BROKER_FLAG => Some(run_broker(pipe)),
+        [flag, rest @ ..] if flag == "--report" => Some(run_report(rest)),
+        [flag, output] if flag == "--collect" => Some(run_collect(output)),
+        _ => None,
+    }
+}
+
+fn run_broker(pipe: &str) -> i32 {
+    match broker::run_broker(pipe) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn run_collect(output: &str) -> i32 {
+    utils::attach_parent_console();
+    let format = if Path::new(output)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        collect::OutputFormat::Zip
+    } else {
+        collect::OutputFormat::Directory
+    };
+
+    match collect::collect_evidence(&collect::CollectOptions::new(output, format)) {
+        Ok(custody) => {
+            println!(
+                "{}: {} files, {} skipped",
+                custody.output, custody.file_count, custody.skipped_count
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn run_report(rest: &[String]) -> i32 {
+    utils::attach_parent_console();
+    let report = report::build_report();
+    let path = rest.first().map_or_else(
+        || report::default_report_path(&report),
+        std::path::PathBuf::from,
+    );
+    match report.write_html(Path::new(&path)) {
+        Ok(()) => {
+            println!("{}", path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::utils;
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfficeMruEntry {
+    /// Office version key, e.g. `16.0`
+    pub version: String,
+    /// Application key, e.g. `Word`
+    pub app: String,
+    /// `User MRU` identity (e.g. `ADAL_...` or `LiveId_...`), `None` for the legacy list
+    pub identity: Option<String>,
+    /// Position in the list (`Item 1` is the most recent)
+    pub position: u32,
+    pub path: String,
+    pub last_opened: Option<SystemTime>,
+}
+
+/// Parse an `Item N` value in the `[F00000000][T<FILETIME>][O00000000]*path` format
+///
+/// Values without the bracketed prefix (Office 2007) are returned as a bare path.
+#[must_use]
+pub fn parse_mru_item(value: &str) -> Option<(Option<SystemTime>, String)> {
+    let mut rest = value;
+    let mut last_opened = None;
+
+    while let Some(field) = rest.strip_prefix('[') {
+        let end = field.find(']')?;
+        if let Some(hex) = field[..end].strip_prefix('T') {
+            last_opened = u64::from_str_radix(hex, 16)
+                .ok()
+                .and_then(utils::filetime_to_system_time);
+        }
+        rest = &field[end + 1..];
+    }
+
+    let path = rest.strip_prefix('*').unwrap_or(rest);
+    (!path.is_empty()).then(|| (last_opened, path.to_string()))
+}
+
+/// Position of an `Item N` value; `Item Metadata N` and other values are skipped
+#[cfg(any(feature = "live", test))]
+fn item_position(value_name: &str) -> Option<u32> {
+    value_name.strip_prefix("Item ")?.parse().ok()
+}
+
+/// Office version keys (`16.0`, `15.0`, ...) under `HKCU\Software\Microsoft\Office`
+#[cfg(any(feature = "live", test))]
+fn is_version_key(name: &str) -> bool {
+    name.split_once('.').is_some_and(|(major, minor)| {
+        !major.is_empty()
+            && major.bytes().all(|b| b.is_ascii_digit())
+            && minor.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_parse_mru_item() {
+        // 0x01D9F3A2B7C4E5D6 = 2023-09-30 13:33:37 UTC
+        let (time, path) =
+            parse_mru_item(r"[F00000000][T01D9F3A2B7C4E5D6][O00000000]*C:\Users\user\Report.docx")
+                .unwrap();
+        assert_eq!(path, r"C:\Users\user\Report.docx");
+        let elapsed = time.unwrap().duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(elapsed.as_secs(), 1_696_080_817);
+
+        let (time, path) = parse_mru_item(
+            r"[F00000000][T01D9F3A2B7C4E5D6][O00000000]*https://contoso.sharepoint.com/Plan.xlsx",
+        )
+        .unwrap();
+        assert!(time.is_some());
+        assert_eq!(path, "https://contoso.sharepoint.com/Plan.xlsx");
+
+        assert_eq!(
+            parse_mru_item(r"C:\Old\Memo.doc"),
+            Some((None, r"C:\Old\Memo.doc".to_string()))
+        );
+        assert_eq!(parse_mru_item("[F00000000][T01D9"), None);
+    }
+
+    #[test]
+    fn test_item_filters() {
+        assert_eq!(item_position("Item 12"), Some(12));
+        assert_eq!(item_position("Item Metadata 1"), None);
+        assert!(is_version_key("16.0"));
+        assert!(!is_version_key("Common"));
+        assert!(!is_version_key("ClickToRun"));
+    }
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+use crate::error::{RecentEnablerError, Result};
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+
+const OFFICE_PATH: &str = r"Software\Microsoft\Office";
+
+const FILE_MRU_KEY: &str = "File MRU";
+
+const USER_MRU_KEY: &str = "User MRU";
+
+fn read_file_mru(
+    key: &RegKey,
+    version: &str,
+    app: &str,
+    identity: Option<&str>,
+    entries: &mut Vec<OfficeMruEntry>,
+) {
+    for (name, _) in key.enum_values().filter_map(std::result::Result::ok) {
+        let Some(position) = item_position(&name) else {
+            continue;
+        };
+        let Ok(value) = key.get_value::<String, _>(&name) else {
+            continue;
+        };
+        if let Some((last_opened, path)) = parse_mru_item(&value) {
+            entries.push(OfficeMruEntry {
+                version: version.to_string(),
+                app: app.to_string(),
+                identity: identity.map(str::to_string),
+                position,
+                path,
+                last_opened,
+            });
+        }
+    }
+}
+
+/// Read the File MRU lists of every installed Office version, app and identity
+///
+/// # Errors
+///
+/// Returns error if the Office key exists but cannot be opened
+pub fn read_office_mru() -> Result<Vec<OfficeMruEntry>> {
+    let office = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(OFFICE_PATH) {
+        Ok(key) => key,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(RecentEnablerError::OfficeMruReadFailed(format!(
+                "{OFFICE_PATH}: {e}"
+            )))
+        }
+    };
+
+    let mut entries = Vec::new();
+    for version in office.enum_keys().filter_map(std::result::Result::ok) {
+        if !is_version_key(&version) {
+            continue;
+        }
+        let Ok(version_key) = office.open_subkey(&version) else {
+            continue;
+        };
+
+        for app in version_key.enum_keys().filter_map(std::result::Result::ok) {
+            let Ok(app_key) = version_key.open_subkey(&app) else {
+                continue;
+            };
+
+            if let Ok(mru) = app_key.open_subkey(FILE_MRU_KEY) {
+                read_file_mru(&mru, &version, &app, None, &mut entries);
+            }
+
+            let Ok(user_mru) = app_key.open_subkey(USER_MRU_KEY) else {
+                continue;
+            };
+            for identity in user_mru.enum_keys().filter_map(std::result::Result::ok) {
+                if let Ok(mru) = user_mru.open_subkey(format!(r"{identity}\{FILE_MRU_KEY}")) {
+                    read_file_mru(&mru, &version, &app, Some(&identity), &mut entries);
+                }
+            }
+        }
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_opened));
+    Ok(entries)
+}
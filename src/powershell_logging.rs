@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const ALL_MODULES: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerShellEdition {
+    /// Windows PowerShell 5.1
+    WindowsPowerShell,
+    /// PowerShell 7+
+    PowerShellCore,
+}
+
+impl PowerShellEdition {
+    pub const ALL: [Self; 2] = [Self::WindowsPowerShell, Self::PowerShellCore];
+
+    #[must_use]
+    pub const fn policy_root(self) -> &'static str {
+        match self {
+            Self::WindowsPowerShell => r"SOFTWARE\Policies\Microsoft\Windows\PowerShell",
+            Self::PowerShellCore => r"SOFTWARE\Policies\Microsoft\PowerShellCore",
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::WindowsPowerShell => "Windows PowerShell",
+            Self::PowerShellCore => "PowerShell 7",
+        }
+    }
+}
+
+/// Where an effective policy value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicySource {
+    LocalMachine,
+    CurrentUser,
+    /// PowerShell 7 following the Windows PowerShell policy
+    WindowsPowerShell,
+    NotConfigured,
+}
+
+impl PolicySource {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::LocalMachine => "HKLM",
+            Self::CurrentUser => "HKCU",
+            Self::WindowsPowerShell => "Windows PowerShell",
+            Self::NotConfigured => "Не настроено",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicySetting {
+    pub enabled: bool,
+    pub source: PolicySource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditionLogging {
+    pub edition: PowerShellEdition,
+    pub script_block: PolicySetting,
+    pub module_logging: PolicySetting,
+    pub module_names: Vec<String>,
+    pub transcription: PolicySetting,
+    pub transcript_directory: Option<String>,
+    pub invocation_header: bool,
+}
+
+impl EditionLogging {
+    /// Script block, all-module and transcription logging are all on, with
+    /// invocation headers and an output directory for the transcripts
+    #[must_use]
+    pub fn is_fully_enabled(&self) -> bool {
+        self.script_block.enabled
+            && self.module_logging.enabled
+            && self.module_names.iter().any(|m| m == ALL_MODULES)
+            && self.transcription.enabled
+            && self.invocation_header
+            && self
+                .transcript_directory
+                .as_deref()
+                .is_some_and(|d| !d.trim().is_empty())
+    }
+}
+
+/// Default transcript folder (`%ProgramData%\PowerShellTranscripts`)
+#[must_use]
+pub fn default_transcript_directory() -> PathBuf {
+    let program_data = std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".into());
+    PathBuf::from(program_data).join("PowerShellTranscripts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_fully_enabled() {
+        let on = PolicySetting {
+            enabled: true,
+            source: PolicySource::LocalMachine,
+        };
+        let mut logging = EditionLogging {
+            edition: PowerShellEdition::PowerShellCore,
+            script_block: on,
+            module_logging: on,
+            module_names: vec!["*".to_string()],
+            transcription: PolicySetting {
+                enabled: true,
+                source: PolicySource::WindowsPowerShell,
+            },
+            transcript_directory: Some(r"C:\ProgramData\PowerShellTranscripts".to_string()),
+            invocation_header: true,
+        };
+        assert!(logging.is_fully_enabled());
+
+        // Transcription without headers or an output folder is only half configured
+        logging.invocation_header = false;
+        assert!(!logging.is_fully_enabled());
+        logging.invocation_header = true;
+        logging.transcript_directory = Some(" ".to_string());
+        assert!(!logging.is_fully_enabled());
+        logging.transcript_directory = None;
+        assert!(!logging.is_fully_enabled());
+        logging.transcript_directory = Some(r"D:\Transcripts".to_string());
+
+        logging.module_names = vec!["Microsoft.PowerShell.Utility".to_string()];
+        assert!(!logging.is_fully_enabled());
+    }
+}
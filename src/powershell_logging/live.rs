@@ -0,0 +1,192 @@
+use super::*;
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use std::path::Path;
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+use winreg::{RegKey, HKEY};
+
+const SCRIPT_BLOCK_KEY: &str = "ScriptBlockLogging";
+
+const MODULE_LOGGING_KEY: &str = "ModuleLogging";
+
+const TRANSCRIPTION_KEY: &str = "Transcription";
+
+const ENABLE_SCRIPT_BLOCK_LOGGING: &str = "EnableScriptBlockLogging";
+
+const ENABLE_MODULE_LOGGING: &str = "EnableModuleLogging";
+
+const ENABLE_TRANSCRIPTING: &str = "EnableTranscripting";
+
+const OUTPUT_DIRECTORY: &str = "OutputDirectory";
+
+const ENABLE_INVOCATION_HEADER: &str = "EnableInvocationHeader";
+
+/// PowerShell 7 switch to follow the Windows PowerShell policy instead
+const USE_WINDOWS_POWERSHELL_POLICY: &str = "UseWindowsPowerShellPolicySetting";
+
+/// Registry location an effective setting was read from
+struct PolicyLocation {
+    hkey: HKEY,
+    path: String,
+}
+
+/// Resolve a policy value, machine policy taking precedence over user policy
+fn resolve_setting(
+    edition: PowerShellEdition,
+    subkey: &str,
+    value: &str,
+) -> (PolicySetting, Option<PolicyLocation>) {
+    for (hkey, source) in [
+        (HKEY_LOCAL_MACHINE, PolicySource::LocalMachine),
+        (HKEY_CURRENT_USER, PolicySource::CurrentUser),
+    ] {
+        let path = format!(r"{}\{subkey}", edition.policy_root());
+
+        if edition == PowerShellEdition::PowerShellCore
+            && utils::read_reg_dword(hkey, &path, USE_WINDOWS_POWERSHELL_POLICY) == Some(1)
+        {
+            let (inherited, location) =
+                resolve_setting(PowerShellEdition::WindowsPowerShell, subkey, value);
+            let setting = PolicySetting {
+                enabled: inherited.enabled,
+                source: PolicySource::WindowsPowerShell,
+            };
+            return (setting, location);
+        }
+
+        if let Some(flag) = utils::read_reg_dword(hkey, &path, value) {
+            let setting = PolicySetting {
+                enabled: flag != 0,
+                source,
+            };
+            return (setting, Some(PolicyLocation { hkey, path }));
+        }
+    }
+
+    let setting = PolicySetting {
+        enabled: false,
+        source: PolicySource::NotConfigured,
+    };
+    (setting, None)
+}
+
+fn read_module_names(location: &PolicyLocation) -> Vec<String> {
+    RegKey::predef(location.hkey)
+        .open_subkey(format!(r"{}\ModuleNames", location.path))
+        .map(|key| {
+            key.enum_values()
+                .filter_map(std::result::Result::ok)
+                .map(|(name, _)| name)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read the effective logging policy of one PowerShell edition
+#[must_use]
+pub fn get_edition_logging(edition: PowerShellEdition) -> EditionLogging {
+    let (script_block, _) = resolve_setting(edition, SCRIPT_BLOCK_KEY, ENABLE_SCRIPT_BLOCK_LOGGING);
+    let (module_logging, module_location) =
+        resolve_setting(edition, MODULE_LOGGING_KEY, ENABLE_MODULE_LOGGING);
+    let (transcription, transcription_location) =
+        resolve_setting(edition, TRANSCRIPTION_KEY, ENABLE_TRANSCRIPTING);
+
+    let (transcript_directory, invocation_header) =
+        transcription_location.map_or((None, false), |location| {
+            (
+                utils::read_reg_string(location.hkey, &location.path, OUTPUT_DIRECTORY)
+                    .filter(|d| !d.is_empty()),
+                utils::read_reg_dword(location.hkey, &location.path, ENABLE_INVOCATION_HEADER)
+                    .unwrap_or(0)
+                    != 0,
+            )
+        });
+
+    EditionLogging {
+        edition,
+        script_block,
+        module_logging,
+        module_names: module_location
+            .as_ref()
+            .map(read_module_names)
+            .unwrap_or_default(),
+        transcription,
+        transcript_directory,
+        invocation_header,
+    }
+}
+
+/// Check if any PowerShell edition is missing part of the logging policy
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_powershell_logging_disabled() -> Result<bool> {
+    Ok(PowerShellEdition::ALL
+        .into_iter()
+        .any(|edition| !get_edition_logging(edition).is_fully_enabled()))
+}
+
+/// Enable script block, all-module and transcription logging for both editions
+///
+/// Policies are written to HKLM so they apply to every user.
+///
+/// # Errors
+///
+/// Returns error if the policy keys cannot be written
+pub fn enable_powershell_logging(output_directory: &Path) -> Result {
+    let enable_failed =
+        |e: RecentEnablerError| RecentEnablerError::PowerShellLoggingEnableFailed(e.to_string());
+    let output_directory = output_directory.display().to_string();
+
+    for edition in PowerShellEdition::ALL {
+        let root = edition.policy_root();
+        let script_block = format!(r"{root}\{SCRIPT_BLOCK_KEY}");
+        let module_logging = format!(r"{root}\{MODULE_LOGGING_KEY}");
+        let transcription = format!(r"{root}\{TRANSCRIPTION_KEY}");
+
+        utils::write_reg_dword(
+            HKEY_LOCAL_MACHINE,
+            &script_block,
+            ENABLE_SCRIPT_BLOCK_LOGGING,
+            1,
+        )
+        .map_err(enable_failed)?;
+
+        utils::write_reg_dword(
+            HKEY_LOCAL_MACHINE,
+            &module_logging,
+            ENABLE_MODULE_LOGGING,
+            1,
+        )
+        .map_err(enable_failed)?;
+        utils::write_reg_string(
+            HKEY_LOCAL_MACHINE,
+            &format!(r"{module_logging}\ModuleNames"),
+            ALL_MODULES,
+            ALL_MODULES,
+        )
+        .map_err(enable_failed)?;
+
+        utils::write_reg_dword(HKEY_LOCAL_MACHINE, &transcription, ENABLE_TRANSCRIPTING, 1)
+            .map_err(enable_failed)?;
+        utils::write_reg_string(
+            HKEY_LOCAL_MACHINE,
+            &transcription,
+            OUTPUT_DIRECTORY,
+            &output_directory,
+        )
+        .map_err(enable_failed)?;
+        utils::write_reg_dword(
+            HKEY_LOCAL_MACHINE,
+            &transcription,
+            ENABLE_INVOCATION_HEADER,
+            1,
+        )
+        .map_err(enable_failed)?;
+    }
+    Ok(())
+}
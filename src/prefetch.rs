@@ -0,0 +1,189 @@
+use crate::{
+    binary,
+    error::{RecentEnablerError, Result},
+    utils, xpress,
+};
+use std::io::Read;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// `SCCA` signature at offset 4 of an uncompressed Prefetch file
+const SCCA_SIGNATURE: &[u8; 4] = b"SCCA";
+/// `MAM` signature of a Windows 10+ compressed Prefetch file
+const MAM_SIGNATURE: &[u8; 3] = b"MAM";
+/// Compression format byte for Xpress Huffman
+const MAM_XPRESS_HUFFMAN: u8 = 0x04;
+/// Flag in the format byte indicating a CRC32 follows the header
+const MAM_CRC_FLAG: u8 = 0x80;
+
+const EXECUTABLE_NAME_OFFSET: usize = 0x10;
+const EXECUTABLE_NAME_SIZE: usize = 60;
+const LAST_RUN_OFFSET: usize = 0x80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefetchFile {
+    /// Format version: 17 (XP), 23 (Vista/7), 26 (8.1), 30/31 (10/11)
+    pub version: u32,
+    pub executable: String,
+    pub run_count: Option<u32>,
+    /// Last run times, most recent first (up to 8 on Windows 8+)
+    pub last_run_times: Vec<SystemTime>,
+}
+
+fn parse_error(msg: &str) -> RecentEnablerError {
+    RecentEnablerError::PrefetchParseFailed(msg.to_string())
+}
+
+/// Decompress a `MAM` container, returning other data unchanged
+///
+/// # Errors
+///
+/// Returns error if the container uses an unknown format or is corrupt
+pub fn decompress_mam(data: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>> {
+    if data.get(..3) != Some(MAM_SIGNATURE) {
+        return Ok(std::borrow::Cow::Borrowed(data));
+    }
+
+    let format = binary::read_u8(data, 3).ok_or_else(|| parse_error("truncated MAM header"))?;
+    if format & !MAM_CRC_FLAG != MAM_XPRESS_HUFFMAN {
+        return Err(parse_error(&format!(
+            "unsupported MAM compression {format:#04x}"
+        )));
+    }
+    let size = binary::read_u32(data, 4).ok_or_else(|| parse_error("truncated MAM header"))?;
+    if size as usize > xpress::MAX_UNCOMPRESSED_SIZE {
+        return Err(parse_error(&format!(
+            "MAM size {size} is implausibly large"
+        )));
+    }
+    let payload = if format & MAM_CRC_FLAG == 0 { 8 } else { 12 };
+
+    xpress::decompress(data.get(payload..).unwrap_or_default(), size as usize)
+        .map(std::borrow::Cow::Owned)
+        .ok_or_else(|| parse_error("corrupt Xpress Huffman stream"))
+}
+
+/// Offset of the run counter, which moved between format variants
+fn run_count_offset(data: &[u8], version: u32) -> Option<usize> {
+    match version {
+        17 => Some(0x90),
+        23 => Some(0x98),
+        26 => Some(0xD0),
+        // The shorter Windows 10 file information block drops 8 bytes before the counter
+        30 | 31 => match binary::read_u32(data, 0x54)? {
+            0x130 => Some(0xD0),
+            _ => Some(0xC8),
+        },
+        _ => None,
+    }
+}
+
+/// Parse a Prefetch file, decompressing it first if needed
+///
+/// # Errors
+///
+/// Returns error if the data is not a supported Prefetch file
+pub fn parse_prefetch(data: &[u8]) -> Result<PrefetchFile> {
+    let data = decompress_mam(data)?;
+    if data.get(4..8) != Some(SCCA_SIGNATURE) {
+        return Err(parse_error("missing SCCA signature"));
+    }
+
+    let version = binary::read_u32(&data, 0).ok_or_else(|| parse_error("truncated header"))?;
+    let run_slots = match version {
+        17 | 23 => 1,
+        26 | 30 | 31 => 8,
+        _ => return Err(parse_error(&format!("unsupported version {version}"))),
+    };
+    let last_run_offset = if version == 17 { 0x78 } else { LAST_RUN_OFFSET };
+
+    let name = data
+        .get(EXECUTABLE_NAME_OFFSET..EXECUTABLE_NAME_OFFSET + EXECUTABLE_NAME_SIZE)
+        .ok_or_else(|| parse_error("truncated header"))?;
+    let executable = binary::read_utf16_z(name, 0).unwrap_or_default();
+
+    let last_run_times = (0..run_slots)
+        .filter_map(|slot| binary::read_u64(&data, last_run_offset + slot * 8))
+        .filter_map(utils::filetime_to_system_time)
+        .collect();
+
+    Ok(PrefetchFile {
+        version,
+        executable,
+        run_count: run_count_offset(&data, version).and_then(|o| binary::read_u32(&data, o)),
+        last_run_times,
+    })
+}
+
+/// Read and parse a Prefetch file
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or parsed
+pub fn read_prefetch(path: &Path) -> Result<PrefetchFile> {
+    let mut data = Vec::new();
+    utils::open_for_backup(path)
+        .and_then(|mut file| file.read_to_end(&mut data))
+        .map_err(|e| RecentEnablerError::PrefetchParseFailed(format!("{}: {e}", path.display())))?;
+    parse_prefetch(&data)
+        .map_err(|e| RecentEnablerError::PrefetchParseFailed(format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const FILETIME_2023: u64 = 0x01D9_F3A2_B7C4_E5D6;
+
+    fn scca(version: u32, executable: &str, runs: &[u64], run_count: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 0x130];
+        data[0..4].copy_from_slice(&version.to_le_bytes());
+        data[4..8].copy_from_slice(SCCA_SIGNATURE);
+        for (i, unit) in executable.encode_utf16().enumerate() {
+            let offset = EXECUTABLE_NAME_OFFSET + i * 2;
+            data[offset..offset + 2].copy_from_slice(&unit.to_le_bytes());
+        }
+        data[0x54..0x58].copy_from_slice(&0x130u32.to_le_bytes());
+        for (i, run) in runs.iter().enumerate() {
+            let offset = LAST_RUN_OFFSET + i * 8;
+            data[offset..offset + 8].copy_from_slice(&run.to_le_bytes());
+        }
+        data[0xD0..0xD4].copy_from_slice(&run_count.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_prefetch_v30() {
+        let earlier = FILETIME_2023 - 36_000_000_000;
+        let data = scca(30, "CMD.EXE", &[FILETIME_2023, earlier], 42);
+        let prefetch = parse_prefetch(&data).unwrap();
+
+        assert_eq!(prefetch.version, 30);
+        assert_eq!(prefetch.executable, "CMD.EXE");
+        assert_eq!(prefetch.run_count, Some(42));
+        assert_eq!(prefetch.last_run_times.len(), 2);
+        assert_eq!(
+            prefetch.last_run_times[0]
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            1_696_080_817
+        );
+        assert_eq!(
+            prefetch.last_run_times[0]
+                .duration_since(prefetch.last_run_times[1])
+                .unwrap(),
+            Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn test_parse_prefetch_rejects_unknown() {
+        assert!(parse_prefetch(b"not a prefetch file").is_err());
+        assert!(parse_prefetch(&scca(99, "X.EXE", &[], 0)).is_err());
+        assert!(parse_prefetch(b"MAM\x05\x10\x00\x00\x00").is_err());
+        // A declared size of 4 GiB is refused before anything is allocated
+        assert!(decompress_mam(b"MAM\x04\xFF\xFF\xFF\xFF").is_err());
+    }
+}
@@ -2,92 +2,186 @@ use crate::{
     error::{RecentEnablerError, Result},
     utils,
 };
-use std::path::PathBuf;
-use winreg::enums::HKEY_CURRENT_USER;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Files counted in the Recent folder: shortcuts and both jump list kinds
+pub const RECENT_PATTERNS: &[&str] = &[
+    "*.lnk",
+    "*.automaticDestinations-ms",
+    "*.customDestinations-ms",
+];
 
 pub struct RecentInfo {
     pub lnk_count: usize,
+    /// Files in `AutomaticDestinations` and `CustomDestinations`
+    pub jumplist_count: usize,
+    pub total_size: u64,
+    /// Modification times of the `.lnk` files
     pub oldest_time: Option<std::time::SystemTime>,
     pub newest_time: Option<std::time::SystemTime>,
+    pub unreadable: Vec<utils::UnreadableEntry>,
 }
 
-/// Get the path to the Recent folder
-///
-/// # Errors
-///
-/// Returns error if APPDATA environment variable is not set
-pub fn get_recent_folder() -> Result<PathBuf> {
-    let appdata = std::env::var("APPDATA").map_err(|e| {
-        RecentEnablerError::RecentFolderNotFound(format!("APPDATA variable not found: {e}"))
-    })?;
-    Ok(PathBuf::from(appdata)
-        .join("Microsoft")
-        .join("Windows")
-        .join("Recent"))
+/// Where the Recent folder location was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentFolderSource {
+    /// `User Shell Folders\Recent`, the value Explorer itself uses
+    UserShellFolders,
+    /// Legacy `Shell Folders\Recent` cache
+    ShellFolders,
+    /// `%APPDATA%\Microsoft\Windows\Recent`
+    AppData,
 }
 
-/// Get statistics about files in Recent folder
-///
-/// # Errors
+impl RecentFolderSource {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UserShellFolders => "User Shell Folders",
+            Self::ShellFolders => "Shell Folders",
+            Self::AppData => "APPDATA",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFolder {
+    pub path: PathBuf,
+    pub source: RecentFolderSource,
+    /// The folder is redirected to a UNC path or a mapped network drive
+    pub is_network: bool,
+}
+
+/// Pick the Recent folder from the registry values and `APPDATA`, in order of precedence
 ///
-/// Returns error if folder doesn't exist or cannot be read
-pub fn get_recent_info() -> Result<RecentInfo> {
-    let recent_path = get_recent_folder()?;
-    let stats = utils::get_directory_stats(&recent_path, "lnk")
-        .map_err(|e| RecentEnablerError::RecentInfoFailed(e.to_string()))?;
+/// `user_shell_folder` may contain unexpanded `%VAR%` references.
+#[must_use]
+pub fn select_recent_folder(
+    user_shell_folder: Option<&str>,
+    shell_folder: Option<&str>,
+    appdata: Option<&str>,
+) -> Option<(PathBuf, RecentFolderSource)> {
+    fn non_empty(value: Option<&str>) -> Option<&str> {
+        value.map(str::trim).filter(|v| !v.is_empty())
+    }
 
-    Ok(RecentInfo {
-        lnk_count: stats.count,
-        oldest_time: stats.oldest,
-        newest_time: stats.newest,
+    if let Some(value) = non_empty(user_shell_folder) {
+        let expanded = utils::expand_env_vars(value);
+        if !expanded.contains('%') {
+            return Some((
+                PathBuf::from(expanded),
+                RecentFolderSource::UserShellFolders,
+            ));
+        }
+    }
+    if let Some(value) = non_empty(shell_folder) {
+        return Some((PathBuf::from(value), RecentFolderSource::ShellFolders));
+    }
+    non_empty(appdata).map(|appdata| {
+        (
+            PathBuf::from(appdata)
+                .join("Microsoft")
+                .join("Windows")
+                .join("Recent"),
+            RecentFolderSource::AppData,
+        )
     })
 }
 
-/// Check if Recent tracking is disabled in registry
-///
-/// # Errors
+/// Check if `path` is a UNC path or on a drive mapped under `HKCU\Network`
 ///
-/// Returns error if registry keys cannot be read
-pub fn is_recent_disabled() -> Result<bool> {
-    let adv_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced";
-    let exp_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer";
-
-    let track_docs =
-        utils::read_reg_dword(HKEY_CURRENT_USER, adv_path, "Start_TrackDocs").unwrap_or(0) == 0;
-    let show_recent =
-        utils::read_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowRecent").unwrap_or(1) == 0;
-    let show_frequent =
-        utils::read_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowFrequent").unwrap_or(1) == 0;
-
-    Ok(track_docs || show_recent || show_frequent)
+/// Mapped drives are only recognized with the `live` feature.
+#[must_use]
+pub fn is_network_path(path: &Path) -> bool {
+    let raw = path.to_string_lossy();
+    if raw.starts_with(r"\\") && !raw.starts_with(r"\\?\") && !raw.starts_with(r"\\.\") {
+        return true;
+    }
+    if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        return !unc.is_empty();
+    }
+
+    #[cfg(feature = "live")]
+    {
+        live::is_mapped_drive(path)
+    }
+
+    #[cfg(not(feature = "live"))]
+    {
+        false
+    }
 }
 
-/// Enable Recent tracking in Windows registry
+/// Get statistics about a Recent folder at `recent_path`
+///
+/// Used for copies of the folder, such as inside a shadow copy.
 ///
 /// # Errors
 ///
-/// Returns error if registry keys cannot be written
-pub fn enable_recent() -> Result {
-    let adv_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced";
-    let exp_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer";
-
-    utils::write_reg_dword(HKEY_CURRENT_USER, adv_path, "Start_TrackDocs", 1)
-        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
-    utils::write_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowRecent", 1)
-        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
-    utils::write_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowFrequent", 1)
-        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
-
-    Ok(())
+/// Returns error if the folder exists but cannot be read
+pub fn get_recent_info_at(recent_path: &Path) -> Result<RecentInfo> {
+    let walker = utils::walk_directory(recent_path, &utils::ScanOptions::new(RECENT_PATTERNS, 1))
+        .map_err(|e| RecentEnablerError::RecentInfoFailed(e.to_string()))?;
+
+    let stats = utils::ExtensionStats::collect(walker, "lnk");
+    Ok(RecentInfo {
+        lnk_count: stats.matching.count,
+        jumplist_count: stats.other_count(),
+        total_size: stats.all.total_size,
+        oldest_time: stats.matching.modified.oldest,
+        newest_time: stats.matching.modified.newest,
+        unreadable: stats.all.unreadable,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "live")]
     #[test]
     fn test_get_recent_folder() {
         let path = get_recent_folder().unwrap();
         assert!(path.to_string_lossy().contains("Recent"));
     }
+
+    #[test]
+    fn test_select_recent_folder() {
+        // Set by cargo for the test process, unlike APPDATA on non-Windows hosts
+        let package = env!("CARGO_PKG_NAME");
+        let (path, source) = select_recent_folder(
+            Some(r"%CARGO_PKG_NAME%\Redirected\Recent"),
+            Some(r"C:\Users\user\AppData\Roaming\Microsoft\Windows\Recent"),
+            Some(r"C:\Users\user\AppData\Roaming"),
+        )
+        .unwrap();
+        assert_eq!(path, PathBuf::from(format!(r"{package}\Redirected\Recent")));
+        assert_eq!(source, RecentFolderSource::UserShellFolders);
+
+        // Unresolvable variables fall through to the next source
+        let (path, source) = select_recent_folder(
+            Some(r"%RECENT_ENABLER_UNSET%\Recent"),
+            None,
+            Some(r"C:\Users\user\AppData\Roaming"),
+        )
+        .unwrap();
+        assert_eq!(source, RecentFolderSource::AppData);
+        assert!(path.ends_with("Recent"));
+
+        assert_eq!(select_recent_folder(Some(" "), None, None), None);
+    }
+
+    #[test]
+    fn test_is_network_path() {
+        assert!(is_network_path(Path::new(
+            r"\\fileserver\profiles\user\Recent"
+        )));
+        assert!(is_network_path(Path::new(r"\\?\UNC\fileserver\profiles")));
+        assert!(!is_network_path(Path::new(r"\\?\C:\Users")));
+    }
 }
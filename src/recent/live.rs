@@ -0,0 +1,117 @@
+use super::*;
+
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+
+const USER_SHELL_FOLDERS_PATH: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\User Shell Folders";
+
+const SHELL_FOLDERS_PATH: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\Shell Folders";
+
+const NETWORK_DRIVES_PATH: &str = "Network";
+
+/// Drive letter of a `X:` path, if any
+fn drive_letter(path: &Path) -> Option<char> {
+    let path = path.to_str()?;
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    (chars.next() == Some(':')).then_some(letter.to_ascii_uppercase())
+}
+
+/// Check if `path` is on a drive mapped under `HKCU\Network`
+pub(super) fn is_mapped_drive(path: &Path) -> bool {
+    drive_letter(path).is_some_and(|letter| {
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey(format!(r"{NETWORK_DRIVES_PATH}\{letter}"))
+            .is_ok()
+    })
+}
+
+/// Resolve the effective Recent folder
+///
+/// Honors folder redirection configured in `User Shell Folders`, then the
+/// `Shell Folders` cache, and falls back to `%APPDATA%`.
+///
+/// # Errors
+///
+/// Returns error if no source provides a location
+pub fn resolve_recent_folder() -> Result<RecentFolder> {
+    let user_shell_folder =
+        utils::read_reg_string(HKEY_CURRENT_USER, USER_SHELL_FOLDERS_PATH, "Recent");
+    let shell_folder = utils::read_reg_string(HKEY_CURRENT_USER, SHELL_FOLDERS_PATH, "Recent");
+    let appdata = std::env::var("APPDATA").ok();
+
+    let (path, source) = select_recent_folder(
+        user_shell_folder.as_deref(),
+        shell_folder.as_deref(),
+        appdata.as_deref(),
+    )
+    .ok_or_else(|| {
+        RecentEnablerError::RecentFolderNotFound(
+            "Recent is not set in User Shell Folders and APPDATA variable not found".to_string(),
+        )
+    })?;
+
+    Ok(RecentFolder {
+        is_network: is_network_path(&path),
+        path,
+        source,
+    })
+}
+
+/// Get the path to the Recent folder
+///
+/// # Errors
+///
+/// Returns error if the folder location cannot be resolved
+pub fn get_recent_folder() -> Result<PathBuf> {
+    resolve_recent_folder().map(|folder| folder.path)
+}
+
+/// Get statistics about files in Recent folder
+///
+/// # Errors
+///
+/// Returns error if folder doesn't exist or cannot be read
+pub fn get_recent_info() -> Result<RecentInfo> {
+    get_recent_info_at(&get_recent_folder()?)
+}
+
+/// Check if Recent tracking is disabled in registry
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_recent_disabled() -> Result<bool> {
+    let adv_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced";
+    let exp_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer";
+
+    let track_docs =
+        utils::read_reg_dword(HKEY_CURRENT_USER, adv_path, "Start_TrackDocs").unwrap_or(0) == 0;
+    let show_recent =
+        utils::read_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowRecent").unwrap_or(1) == 0;
+    let show_frequent =
+        utils::read_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowFrequent").unwrap_or(1) == 0;
+
+    Ok(track_docs || show_recent || show_frequent)
+}
+
+/// Enable Recent tracking in Windows registry
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be written
+pub fn enable_recent() -> Result {
+    let adv_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced";
+    let exp_path = r"Software\Microsoft\Windows\CurrentVersion\Explorer";
+
+    utils::write_reg_dword(HKEY_CURRENT_USER, adv_path, "Start_TrackDocs", 1)
+        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
+    utils::write_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowRecent", 1)
+        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
+    utils::write_reg_dword(HKEY_CURRENT_USER, exp_path, "ShowFrequent", 1)
+        .map_err(|e| RecentEnablerError::RecentEnableFailed(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,646 @@
+//! Self-contained HTML forensic-readiness report
+//!
+//! Each status type implements [`ReportSection`] to describe whether it passes,
+//! what to do when it doesn't and which counts and time ranges to show. New
+//! checks join the report by implementing the trait and being added in
+//! [`build_report`].
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    status,
+};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const STYLE: &str = "body{font-family:Segoe UI,Arial,sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{border:1px solid #ccc;padding:6px 10px;text-align:left;vertical-align:top}\
+th{background:#f0f0f0}.pass{color:#1a7f37;font-weight:bold}\
+.fail{color:#cf222e;font-weight:bold}pre{background:#f6f8fa;padding:1em;overflow:auto}";
+
+/// A status that contributes a row to the readiness report
+pub trait ReportSection: Serialize {
+    /// Whether the artifact is being recorded as expected
+    fn passed(&self) -> bool;
+
+    /// What to do when the check fails
+    fn recommendation(&self) -> &'static str;
+
+    /// Counts and time ranges worth showing next to the result
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HostInfo {
+    pub computer_name: String,
+    pub user_name: String,
+    pub os_version: String,
+    pub is_admin: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportCheck {
+    pub title: String,
+    pub passed: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+
+    pub facts: Vec<(String, String)>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// The status as returned by the check
+    pub raw: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    pub host: HostInfo,
+    /// RFC 3339 generation time
+    pub generated_at: String,
+    pub checks: Vec<ReportCheck>,
+}
+
+impl ReadinessReport {
+    #[must_use]
+    pub fn new(host: HostInfo, generated: SystemTime) -> Self {
+        Self {
+            host,
+            generated_at: chrono::DateTime::<chrono::Local>::from(generated).to_rfc3339(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Add the result of a check; a failed check is listed as not passed
+    pub fn add<S: ReportSection>(&mut self, title: &'static str, result: &Result<S>) -> &mut Self {
+        let check = match result {
+            Ok(status) => ReportCheck {
+                title: title.to_string(),
+                passed: status.passed(),
+                recommendation: (!status.passed()).then(|| status.recommendation().to_string()),
+                facts: status
+                    .facts()
+                    .into_iter()
+                    .map(|(label, value)| (label.to_string(), value))
+                    .collect(),
+                error: None,
+                raw: serde_json::to_value(status).unwrap_or_default(),
+            },
+            Err(e) => ReportCheck {
+                title: title.to_string(),
+                passed: false,
+                recommendation: None,
+                facts: Vec::new(),
+                error: Some(e.to_russian()),
+                raw: serde_json::json!({ "error": e.to_string() }),
+            },
+        };
+        self.checks.push(check);
+        self
+    }
+
+    #[must_use]
+    pub fn passed_count(&self) -> usize {
+        self.checks.iter().filter(|check| check.passed).count()
+    }
+
+    /// Render a single HTML page with no external resources
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"ru\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Отчёт о готовности: {}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+            escape_html(&self.host.computer_name)
+        );
+
+        let _ = write!(
+            html,
+            "<h1>Отчёт о готовности к расследованию</h1>\n<table>\n\
+             <tr><th>Компьютер</th><td>{}</td></tr>\n\
+             <tr><th>Пользователь</th><td>{}</td></tr>\n\
+             <tr><th>Система</th><td>{}</td></tr>\n\
+             <tr><th>Права администратора</th><td>{}</td></tr>\n\
+             <tr><th>Сформирован</th><td>{}</td></tr>\n\
+             <tr><th>Пройдено проверок</th><td>{} из {}</td></tr>\n</table>\n",
+            escape_html(&self.host.computer_name),
+            escape_html(&self.host.user_name),
+            escape_html(&self.host.os_version),
+            if self.host.is_admin { "да" } else { "нет" },
+            escape_html(&self.generated_at),
+            self.passed_count(),
+            self.checks.len(),
+        );
+
+        html.push_str(
+            "<h2>Результаты</h2>\n<table>\n<tr><th>Проверка</th><th>Результат</th><th>Данные</th></tr>\n",
+        );
+        for check in &self.checks {
+            let (class, label) = if check.passed {
+                ("pass", "ПРОЙДЕНА")
+            } else {
+                ("fail", "НЕ ПРОЙДЕНА")
+            };
+            let details = check.error.as_ref().map_or_else(
+                || {
+                    check
+                        .facts
+                        .iter()
+                        .map(|(label, value)| {
+                            format!("{}: {}", escape_html(label), escape_html(value))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("<br>")
+                },
+                |error| format!("Ошибка проверки: {}", escape_html(error)),
+            );
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"{class}\">{label}</td><td>{details}</td></tr>",
+                escape_html(&check.title)
+            );
+        }
+        html.push_str("</table>\n");
+
+        let recommendations: Vec<_> = self
+            .checks
+            .iter()
+            .filter_map(|check| Some((&check.title, check.recommendation.as_ref()?)))
+            .collect();
+        if !recommendations.is_empty() {
+            html.push_str("<h2>Рекомендации</h2>\n<ul>\n");
+            for (title, recommendation) in recommendations {
+                let _ = writeln!(
+                    html,
+                    "<li><b>{}</b>: {}</li>",
+                    escape_html(title),
+                    escape_html(recommendation)
+                );
+            }
+            html.push_str("</ul>\n");
+        }
+
+        let json = serde_json::to_string_pretty(self).unwrap_or_default();
+        let _ = write!(
+            html,
+            "<h2>Исходные данные</h2>\n<details><summary>JSON</summary>\n<pre>{}</pre>\n</details>\n\
+             <script type=\"application/json\" id=\"report-data\">{}</script>\n</body>\n</html>\n",
+            escape_html(&json),
+            json.replace("</", "<\\/")
+        );
+        html
+    }
+
+    /// Write the HTML report to `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be written
+    pub fn write_html(&self, path: &Path) -> Result {
+        std::fs::write(path, self.to_html())
+            .map_err(|e| RecentEnablerError::ReportExportFailed(format!("{}: {e}", path.display())))
+    }
+}
+
+/// Escape text for HTML element content and attribute values
+#[must_use]
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time)
+        .format("%d.%m.%Y %H:%M")
+        .to_string()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["Б", "КБ", "МБ", "ГБ"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} Б")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Oldest/newest facts for whichever bounds are known
+fn time_range(
+    oldest: Option<SystemTime>,
+    newest: Option<SystemTime>,
+) -> Vec<(&'static str, String)> {
+    [
+        ("Самая старая запись", oldest),
+        ("Самая новая запись", newest),
+    ]
+    .into_iter()
+    .filter_map(|(label, time)| Some((label, format_time(time?))))
+    .collect()
+}
+
+/// Count of files that couldn't be read, with the first few reasons
+fn unreadable_fact(count: usize, examples: &[String]) -> Option<(&'static str, String)> {
+    if count == 0 {
+        return None;
+    }
+    let mut value = count.to_string();
+    if !examples.is_empty() {
+        let _ = write!(value, " ({}", examples.join("; "));
+        if count > examples.len() {
+            value.push_str("; …");
+        }
+        value.push(')');
+    }
+    Some(("Недоступных файлов", value))
+}
+
+impl ReportSection for status::RecentStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите запись недавних документов (Start_TrackDocs) и снимите политику NoRecentDocsHistory"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            ("Ярлыков", self.files_count.to_string()),
+            ("Списков переходов", self.jumplist_count.to_string()),
+            ("Размер", format_size(self.total_size)),
+            ("Документов Office", self.office_mru.len().to_string()),
+        ];
+        facts.extend(unreadable_fact(
+            self.unreadable_count,
+            &self.unreadable_examples,
+        ));
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::ActivitiesStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Снимите политики, отключающие журнал действий Windows (EnableActivityFeed, PublishUserActivities)"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            ("Баз данных", self.databases_count.to_string()),
+            ("Записей", self.entries_count.to_string()),
+        ];
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::UserAssistStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите отслеживание запуска программ (Start_TrackProgs)"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![("Записей", self.entries_count.to_string())];
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::ShellBagsStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Снимите политику NoSaveSettings и верните ненулевой BagMRU Size"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![("Папок", self.entries_count.to_string())];
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::SysMainStatus {
+    fn passed(&self) -> bool {
+        self.is_running && self.is_auto
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Запустите службу SysMain и установите автоматический тип запуска"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            ("Тип запуска", self.startup_type.clone()),
+            ("Файлов .pf", self.prefetch_count.to_string()),
+            ("Layout.ini и .db", self.prefetch_other_count.to_string()),
+            ("Размер", format_size(self.prefetch_total_size)),
+            ("Записей ShimCache", self.shimcache_count.to_string()),
+        ];
+        facts.extend(unreadable_fact(
+            self.prefetch_unreadable_count,
+            &self.prefetch_unreadable_examples,
+        ));
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::LastAccessStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled && !self.reboot_required
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите обновление времени последнего доступа (NtfsDisableLastAccessUpdate = 0x80000000) и перезагрузите компьютер"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![("Режим", self.mode.clone())];
+        if self.reboot_required {
+            facts.push(("Перезагрузка", "требуется".to_string()));
+        }
+        facts
+    }
+}
+
+impl ReportSection for status::AmcacheStatus {
+    fn passed(&self) -> bool {
+        self.amcache_error.is_none()
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Проверьте доступность Amcache.hve; для чтения нужны права администратора"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            ("Файлов", self.files_count.to_string()),
+            ("Приложений", self.applications_count.to_string()),
+        ];
+        if self.skipped_entries > 0 {
+            facts.push(("Повреждённых записей", self.skipped_entries.to_string()));
+        }
+        facts.extend(time_range(None, self.newest_time));
+        if let Some(time) = self.snapshot_time {
+            facts.push(("Прочитано из теневой копии от", format_time(time)));
+        }
+        facts
+    }
+}
+
+impl ReportSection for status::SrumStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled && self.dps_running
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Запустите службу политики диагностики (DPS) с автоматическим типом запуска"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            (
+                "Записей об использовании приложений",
+                self.app_usage_count.to_string(),
+            ),
+            (
+                "Записей об использовании сети",
+                self.network_usage_count.to_string(),
+            ),
+        ];
+        if self.unreadable_names > 0 {
+            facts.push(("Нераспознанных имён", self.unreadable_names.to_string()));
+        }
+        facts.extend(time_range(None, self.newest_time));
+        if let Some(time) = self.snapshot_time {
+            facts.push(("Прочитано из теневой копии от", format_time(time)));
+        }
+        facts
+    }
+}
+
+impl ReportSection for status::BamStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите драйвер bam (Start = 1)"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![
+            ("Пользователей", self.users_count.to_string()),
+            ("Записей", self.entries_count.to_string()),
+        ];
+        facts.extend(time_range(self.oldest_time, self.newest_time));
+        facts
+    }
+}
+
+impl ReportSection for status::EventLogStatus {
+    fn passed(&self) -> bool {
+        self.needs_change_count == 0
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите нужные каналы журналов событий и увеличьте их размер"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Каналов", self.channels.len().to_string()),
+            ("Требуют настройки", self.needs_change_count.to_string()),
+        ]
+    }
+}
+
+impl ReportSection for status::PowerShellLoggingStatus {
+    fn passed(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите журналирование блоков скриптов, модулей и транскрипцию PowerShell"
+    }
+}
+
+impl ReportSection for status::UsnJournalStatus {
+    fn passed(&self) -> bool {
+        self.is_enabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Создайте или увеличьте журнал изменений USN на всех томах NTFS"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Томов NTFS", self.volumes.len().to_string()),
+            ("Целевой размер", format_size(self.target_size)),
+        ]
+    }
+}
+
+impl ReportSection for status::AuditPolicyStatus {
+    fn passed(&self) -> bool {
+        self.cmdline_enabled && self.needs_change_count == 0
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите аудит создания процессов с командной строкой и рекомендуемые подкатегории"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Подкатегорий", self.subcategories.len().to_string()),
+            ("Требуют настройки", self.needs_change_count.to_string()),
+        ]
+    }
+}
+
+impl ReportSection for status::ShadowCopyStatus {
+    fn passed(&self) -> bool {
+        !self.snapshots.is_empty()
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите защиту системы, чтобы создавались теневые копии со старыми версиями Recent и Prefetch"
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let mut facts = vec![("Теневых копий", self.snapshots.len().to_string())];
+        facts.extend(time_range(
+            self.snapshots.first().and_then(|s| s.created),
+            self.snapshots.last().and_then(|s| s.created),
+        ));
+        facts
+    }
+}
+
+impl ReportSection for status::SystemRestoreStatus {
+    fn passed(&self) -> bool {
+        self.is_enabled
+    }
+
+    fn recommendation(&self) -> &'static str {
+        "Включите защиту системы (System Restore) на диске C:"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> HostInfo {
+        HostInfo {
+            computer_name: "WS01".to_string(),
+            user_name: "analyst".to_string(),
+            os_version: "Windows 11 Pro 23H2".to_string(),
+            is_admin: false,
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_unreadable_fact() {
+        assert_eq!(unreadable_fact(0, &[]), None);
+        let examples = vec![r"C:\a.lnk: Access is denied".to_string()];
+        assert_eq!(
+            unreadable_fact(2, &examples).unwrap().1,
+            r"2 (C:\a.lnk: Access is denied; …)"
+        );
+    }
+
+    #[test]
+    fn test_report_html() {
+        let mut report = ReadinessReport::new(host(), SystemTime::now());
+        report
+            .add(
+                "System Restore",
+                &Ok(status::SystemRestoreStatus { is_enabled: true }),
+            )
+            .add(
+                "Журнал USN",
+                &Err::<status::UsnJournalStatus, _>(RecentEnablerError::UsnJournalReadFailed(
+                    "</script>".to_string(),
+                )),
+            )
+            .add(
+                "Время последнего доступа NTFS",
+                &Ok(status::LastAccessStatus {
+                    is_disabled: true,
+                    mode: "Отключено системой".to_string(),
+                    system_managed: true,
+                    value: Some(0x8000_0003),
+                    reboot_required: false,
+                }),
+            );
+
+        assert_eq!(report.passed_count(), 1);
+        assert_eq!(report.checks[1].title, "Журнал USN");
+        assert!(report.checks[1].recommendation.is_none());
+        assert!(report.checks[2].recommendation.is_some());
+
+        let html = report.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td>WS01</td>"));
+        assert!(html.contains("<li><b>Время последнего доступа NTFS</b>"));
+        assert!(html.contains("Режим: Отключено системой"));
+        // The embedded JSON must not close its own script element
+        assert_eq!(html.matches("</script>").count(), 1);
+
+        let start = html.find("id=\"report-data\">").unwrap() + "id=\"report-data\">".len();
+        let end = html.rfind("</script>").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&html[start..end]).unwrap();
+        assert_eq!(json["checks"][0]["raw"]["is_enabled"], true);
+        assert_eq!(json["host"]["computer_name"], "WS01");
+    }
+}
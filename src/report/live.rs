@@ -0,0 +1,85 @@
+use super::*;
+
+use crate::{service, utils};
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+const CURRENT_VERSION_PATH: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion";
+
+const USER_SHELL_FOLDERS_PATH: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\User Shell Folders";
+
+impl HostInfo {
+    /// Describe the machine the report is generated on
+    #[must_use]
+    pub fn current() -> Self {
+        let read = |value| utils::read_reg_string(HKEY_LOCAL_MACHINE, CURRENT_VERSION_PATH, value);
+        let os_version = [read("ProductName"), read("DisplayVersion")]
+            .into_iter()
+            .flatten()
+            .chain(read("CurrentBuild").map(|build| format!("(сборка {build})")))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            computer_name: std::env::var("COMPUTERNAME").unwrap_or_default(),
+            user_name: std::env::var("USERNAME").unwrap_or_default(),
+            os_version,
+            is_admin: utils::is_admin(),
+        }
+    }
+}
+
+/// The user's desktop, following User Shell Folders redirection
+#[must_use]
+pub fn desktop_folder() -> std::path::PathBuf {
+    let desktop = utils::read_reg_string(HKEY_CURRENT_USER, USER_SHELL_FOLDERS_PATH, "Desktop")
+        .unwrap_or_else(|| r"%USERPROFILE%\Desktop".to_string());
+    std::path::PathBuf::from(utils::expand_env_vars(&desktop))
+}
+
+/// Default location for an exported report: the user's desktop
+///
+/// The file name carries the computer name and generation time so repeated
+/// exports don't overwrite each other.
+#[must_use]
+pub fn default_report_path(report: &ReadinessReport) -> std::path::PathBuf {
+    let stamp: String = report
+        .generated_at
+        .chars()
+        .take(19)
+        .filter(char::is_ascii_digit)
+        .collect();
+    desktop_folder().join(format!(
+        "readiness-{}-{stamp}.html",
+        report.host.computer_name
+    ))
+}
+
+/// Run every check and collect the results into a report
+#[must_use]
+pub fn build_report() -> ReadinessReport {
+    let mut report = ReadinessReport::new(HostInfo::current(), SystemTime::now());
+    report
+        .add("Recent", &service::check_recent())
+        .add("Журнал действий", &service::check_activities())
+        .add("UserAssist", &service::check_userassist())
+        .add("ShellBags", &service::check_shellbags())
+        .add("Prefetch (SysMain)", &service::check_sysmain())
+        .add(
+            "Время последнего доступа NTFS",
+            &service::check_last_access(),
+        )
+        .add("Amcache", &service::check_amcache())
+        .add("SRUM", &service::check_srum())
+        .add("BAM", &service::check_bam())
+        .add("Журналы событий", &service::check_eventlog())
+        .add(
+            "Журналирование PowerShell",
+            &service::check_powershell_logging(),
+        )
+        .add("Журнал USN", &service::check_usn_journal())
+        .add("Политика аудита", &service::check_audit_policy())
+        .add("System Restore", &service::check_system_restore())
+        .add("Теневые копии", &service::check_shadow_copies());
+    report
+}
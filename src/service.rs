@@ -1,6 +1,8 @@
 use crate::{
+    activities, amcache, audit_policy, bam,
     error::{RecentEnablerError, Result},
-    recent, status, sysmain, system_restore, utils,
+    eventlog, last_access, office_mru, powershell_logging, recent, shellbags, shimcache, srum,
+    status, sysmain, system_restore, token, userassist, usn_journal, utils, vss,
 };
 
 /// Check Recent folder status
@@ -9,21 +11,152 @@ use crate::{
 ///
 /// Returns error if Recent folder cannot be accessed or read
 pub fn check_recent() -> Result<status::RecentStatus> {
-    let path = recent::get_recent_folder()?;
+    let folder = recent::resolve_recent_folder()?;
     let is_disabled = recent::is_recent_disabled()?;
     let info = recent::get_recent_info()?;
 
+    let (office_mru, office_mru_error) = match office_mru::read_office_mru() {
+        Ok(entries) => (
+            entries
+                .into_iter()
+                .map(|entry| status::OfficeMruStatus {
+                    version: entry.version,
+                    app: entry.app,
+                    identity: entry.identity,
+                    path: entry.path,
+                    last_opened: entry.last_opened,
+                })
+                .collect(),
+            None,
+        ),
+        Err(e) => (Vec::new(), Some(e.to_russian())),
+    };
+
     Ok(status::RecentStatus {
-        path: path.display().to_string(),
+        path: folder.path.display().to_string(),
+        path_source: folder.source.as_str().to_string(),
+        is_network: folder.is_network,
         is_disabled,
         files_count: info.lnk_count,
+        jumplist_count: info.jumplist_count,
+        total_size: info.total_size,
+        unreadable_count: info.unreadable.len(),
+        unreadable_examples: utils::unreadable_examples(&info.unreadable),
         oldest_time: info.oldest_time,
         newest_time: info.newest_time,
+        office_mru,
+        office_mru_error,
+    })
+}
+
+/// Check Windows Timeline activity history status
+///
+/// Unreadable databases are reported in `activities_error` rather than failing.
+///
+/// # Errors
+///
+/// Returns error if the `ConnectedDevicesPlatform` folder cannot be listed
+pub fn check_activities() -> Result<status::ActivitiesStatus> {
+    let policies = activities::get_activity_policies();
+    let databases = activities::get_activities_databases()?;
+
+    let mut entries = Vec::new();
+    let mut activities_error = None;
+    for path in &databases {
+        match activities::read_activities(path) {
+            Ok(found) => entries.extend(found),
+            Err(e) => activities_error = Some(e.to_russian()),
+        }
+    }
+
+    let start_times = entries.iter().filter_map(|e| e.start_time);
+    let last_app = entries
+        .iter()
+        .filter(|e| e.start_time.is_some())
+        .max_by_key(|e| e.start_time)
+        .map(|e| e.app.clone());
+
+    Ok(status::ActivitiesStatus {
+        is_disabled: policies.is_disabled(),
+        upload_enabled: policies.is_upload_enabled(),
+        databases_count: databases.len(),
+        entries_count: entries.len(),
+        last_app,
+        oldest_time: start_times.clone().min(),
+        newest_time: start_times.max(),
+        activities_error,
+    })
+}
+
+/// Check UserAssist program tracking status
+///
+/// # Errors
+///
+/// Returns error if UserAssist registry data cannot be read
+pub fn check_userassist() -> Result<status::UserAssistStatus> {
+    let is_disabled = userassist::is_userassist_disabled()?;
+    let entries = userassist::get_userassist_entries()?;
+
+    let last_run_times = entries.iter().filter_map(|e| e.counters.last_run);
+    let oldest_time = last_run_times.clone().min();
+    let newest_time = last_run_times.max();
+    let last_program = entries
+        .iter()
+        .filter(|e| e.counters.last_run.is_some())
+        .max_by_key(|e| e.counters.last_run)
+        .map(|e| e.path.clone());
+
+    Ok(status::UserAssistStatus {
+        is_disabled,
+        entries_count: entries.len(),
+        last_program,
+        oldest_time,
+        newest_time,
+    })
+}
+
+/// Check ShellBags saving status
+///
+/// # Errors
+///
+/// Returns error if BagMRU cannot be read
+pub fn check_shellbags() -> Result<status::ShellBagsStatus> {
+    let is_disabled = shellbags::is_shellbags_disabled()?;
+    let entries = shellbags::get_shellbags()?;
+
+    let write_times = entries.iter().filter_map(|e| e.key_last_write);
+
+    Ok(status::ShellBagsStatus {
+        path: shellbags::get_bagmru_path(),
+        is_disabled,
+        entries_count: entries.len(),
+        oldest_time: write_times.clone().min(),
+        newest_time: write_times.max(),
+    })
+}
+
+/// Check NTFS last-access timestamp updates
+///
+/// # Errors
+///
+/// Returns error if registry cannot be read
+pub fn check_last_access() -> Result<status::LastAccessStatus> {
+    let setting = last_access::get_last_access_setting();
+
+    Ok(status::LastAccessStatus {
+        is_disabled: !setting.mode.is_enabled(),
+        mode: setting.mode.as_str().to_string(),
+        system_managed: setting.mode.is_system_managed(),
+        value: setting.value,
+        reboot_required: setting.reboot_required,
     })
 }
 
 /// Check `SysMain` service and Prefetch folder status
 ///
+/// ShimCache contents are included here since they complement Prefetch as
+/// execution evidence; a read failure is reported in `shimcache_error`.
+///
 /// # Errors
 ///
 /// Returns error if service or Prefetch folder cannot be queried
@@ -32,10 +165,21 @@ pub fn check_sysmain() -> Result<status::SysMainStatus> {
     let startup_type = sysmain::get_sysmain_startup_type()?;
     let prefetch_path = sysmain::get_prefetch_folder()?;
 
-    let (prefetch_count, oldest_time, newest_time, prefetch_error) =
-        match sysmain::get_prefetch_info() {
-            Ok(info) => (info.pf_count, info.oldest_time, info.newest_time, None),
-            Err(e) => (0, None, None, Some(e.to_russian())),
+    // Backup Operators can read Prefetch without being administrators
+    let (prefetch, prefetch_error) = match token::with_backup_semantics(sysmain::get_prefetch_info)
+    {
+        Ok(info) => (Some(info), None),
+        Err(e) => (None, Some(with_access_hint(e.to_russian()))),
+    };
+
+    let (shimcache_count, shimcache_newest_time, shimcache_error) =
+        match shimcache::read_shimcache() {
+            Ok(cache) => (
+                cache.entries.len(),
+                cache.entries.iter().filter_map(|e| e.last_modified).max(),
+                None,
+            ),
+            Err(e) => (0, None, Some(e.to_russian())),
         };
 
     Ok(status::SysMainStatus {
@@ -43,13 +187,349 @@ pub fn check_sysmain() -> Result<status::SysMainStatus> {
         is_auto: startup_type == sysmain::StartupType::Automatic,
         startup_type: startup_type.as_str().to_string(),
         prefetch_path: prefetch_path.display().to_string(),
-        prefetch_count,
-        oldest_time,
-        newest_time,
+        prefetch_count: prefetch.as_ref().map_or(0, |p| p.pf_count),
+        prefetch_other_count: prefetch.as_ref().map_or(0, |p| p.other_count),
+        prefetch_total_size: prefetch.as_ref().map_or(0, |p| p.total_size),
+        prefetch_unreadable_count: prefetch.as_ref().map_or(0, |p| p.unreadable.len()),
+        prefetch_unreadable_examples: prefetch
+            .as_ref()
+            .map(|p| utils::unreadable_examples(&p.unreadable))
+            .unwrap_or_default(),
+        oldest_time: prefetch.as_ref().and_then(|p| p.oldest_time),
+        newest_time: prefetch.as_ref().and_then(|p| p.newest_time),
         prefetch_error,
+        shimcache_count,
+        shimcache_newest_time,
+        shimcache_error,
     })
 }
 
+/// Append what the current token lacks to an access error
+fn with_access_hint(message: String) -> String {
+    match token::TokenInfo::current().map(|info| info.access_level()) {
+        Ok(token::AccessLevel::Elevated) | Err(_) => message,
+        Ok(level) => format!("{message} {}", level.hint_russian()),
+    }
+}
+
+/// Check Amcache inventory contents
+///
+/// A locked or missing hive is reported in `amcache_error` rather than failing.
+///
+/// # Errors
+///
+/// Returns error if the Amcache path cannot be determined
+pub fn check_amcache() -> Result<status::AmcacheStatus> {
+    let path = amcache::get_amcache_path()?;
+
+    // The system keeps the live hive open, so it is usually read from a shadow copy
+    let (inventory, snapshot_time, amcache_error) =
+        match vss::read_locked_file(&path, RecentEnablerError::AmcacheReadFailed)
+            .and_then(|file| Ok((amcache::decode_amcache(file.data)?, file.snapshot)))
+        {
+            Ok((inventory, snapshot)) => (inventory, snapshot.and_then(|s| s.created), None),
+            Err(e) => (
+                amcache::AmcacheInventory::default(),
+                None,
+                Some(e.to_russian()),
+            ),
+        };
+    let newest = inventory.files.first();
+
+    Ok(status::AmcacheStatus {
+        path: path.display().to_string(),
+        files_count: inventory.files.len(),
+        applications_count: inventory.applications.len(),
+        skipped_entries: inventory.skipped,
+        newest_entry: newest.map(|e| e.path.clone()),
+        newest_time: newest.and_then(|e| e.last_write),
+        snapshot_time,
+        amcache_error,
+    })
+}
+
+/// Check Background Activity Moderator status and entries
+///
+/// # Errors
+///
+/// Returns error if BAM registry data cannot be read
+pub fn check_bam() -> Result<status::BamStatus> {
+    let is_disabled = bam::is_bam_disabled()?;
+    let entries = bam::get_bam_entries()?;
+
+    let mut sids: Vec<&str> = entries.iter().map(|e| e.sid.as_str()).collect();
+    sids.sort_unstable();
+    sids.dedup();
+
+    let times = entries.iter().filter_map(|e| e.last_execution);
+    let last_executable = entries
+        .iter()
+        .filter(|e| e.last_execution.is_some())
+        .max_by_key(|e| e.last_execution)
+        .map(|e| e.path.clone());
+
+    Ok(status::BamStatus {
+        is_disabled,
+        users_count: sids.len(),
+        entries_count: entries.len(),
+        last_executable,
+        oldest_time: times.clone().min(),
+        newest_time: times.max(),
+    })
+}
+
+/// Check SRUM prerequisites and database contents
+///
+/// A locked or unreadable database is reported in `srum_error` rather than failing.
+///
+/// # Errors
+///
+/// Returns error if DPS cannot be queried or the database path cannot be determined
+pub fn check_srum() -> Result<status::SrumStatus> {
+    let service_status = srum::get_dps_status()?;
+    let startup_type = srum::get_dps_startup_type()?;
+    let parameters = srum::get_srum_parameters();
+    let path = srum::get_srum_path()?;
+
+    // DPS keeps the live database open, so it is usually read from a shadow copy
+    let (data, snapshot_time, srum_error) =
+        match vss::read_locked_file(&path, RecentEnablerError::SrumReadFailed)
+            .and_then(|file| Ok((srum::decode_srum(file.data)?, file.snapshot)))
+        {
+            Ok((data, snapshot)) => (data, snapshot.and_then(|s| s.created), None),
+            Err(e) => (srum::SrumData::default(), None, Some(e.to_russian())),
+        };
+
+    let dps_running = service_status == sysmain::ServiceStatus::Running;
+    let dps_auto = startup_type == sysmain::StartupType::Automatic;
+
+    Ok(status::SrumStatus {
+        path: path.display().to_string(),
+        is_disabled: srum::is_srum_disabled()?,
+        dps_running,
+        dps_auto,
+        startup_type: startup_type.as_str().to_string(),
+        tier1_period: parameters.tier1_period,
+        tier2_period: parameters.tier2_period,
+        app_usage_count: data.app_usage.len(),
+        network_usage_count: data.network_usage.len(),
+        unreadable_names: data.unreadable_names,
+        newest_time: data.newest_time(),
+        snapshot_time,
+        srum_error,
+    })
+}
+
+/// Check the default forensic event log channels
+///
+/// # Errors
+///
+/// Never fails; returns `Result` for consistency with other checks
+pub fn check_eventlog() -> Result<status::EventLogStatus> {
+    check_eventlog_channels(&eventlog::default_channels())
+}
+
+/// Check enablement and size of the given event log channels
+///
+/// # Errors
+///
+/// Never fails; returns `Result` for consistency with other checks
+pub fn check_eventlog_channels(
+    targets: &[eventlog::ChannelTarget],
+) -> Result<status::EventLogStatus> {
+    let channels: Vec<status::EventLogChannelStatus> = targets
+        .iter()
+        .zip(eventlog::get_channel_configs(targets))
+        .map(|(target, config)| status::EventLogChannelStatus {
+            needs_change: config.needs_change(target),
+            name: config.name,
+            exists: config.exists,
+            enabled: config.enabled,
+            max_size: config.max_size,
+            target_size: target.min_size,
+            retention: config.retention,
+        })
+        .collect();
+
+    Ok(status::EventLogStatus {
+        needs_change_count: channels.iter().filter(|c| c.needs_change).count(),
+        channels,
+    })
+}
+
+fn edition_status(logging: &powershell_logging::EditionLogging) -> status::PowerShellEditionStatus {
+    let setting = |s: powershell_logging::PolicySetting| status::PowerShellSettingStatus {
+        enabled: s.enabled,
+        source: s.source.as_str().to_string(),
+    };
+
+    status::PowerShellEditionStatus {
+        edition: logging.edition.as_str().to_string(),
+        script_block_logging: setting(logging.script_block),
+        module_logging: setting(logging.module_logging),
+        module_names: logging.module_names.clone(),
+        transcription: setting(logging.transcription),
+        transcript_directory: logging.transcript_directory.clone(),
+        invocation_header: logging.invocation_header,
+    }
+}
+
+/// Check PowerShell script block, module and transcription logging policies
+///
+/// # Errors
+///
+/// Returns error if policy keys cannot be read
+pub fn check_powershell_logging() -> Result<status::PowerShellLoggingStatus> {
+    let windows_powershell = powershell_logging::get_edition_logging(
+        powershell_logging::PowerShellEdition::WindowsPowerShell,
+    );
+    let powershell_core = powershell_logging::get_edition_logging(
+        powershell_logging::PowerShellEdition::PowerShellCore,
+    );
+
+    Ok(status::PowerShellLoggingStatus {
+        is_disabled: !windows_powershell.is_fully_enabled() || !powershell_core.is_fully_enabled(),
+        windows_powershell: edition_status(&windows_powershell),
+        powershell_core: edition_status(&powershell_core),
+    })
+}
+
+/// Check the default audit subcategories and process command line auditing
+///
+/// # Errors
+///
+/// Never fails; `auditpol` errors are reported in `audit_error`
+pub fn check_audit_policy() -> Result<status::AuditPolicyStatus> {
+    check_audit_policy_subcategories(&audit_policy::default_subcategories())
+}
+
+/// Check the given audit subcategories and process command line auditing
+///
+/// # Errors
+///
+/// Never fails; `auditpol` errors are reported in `audit_error`
+pub fn check_audit_policy_subcategories(
+    targets: &[audit_policy::AuditTarget],
+) -> Result<status::AuditPolicyStatus> {
+    let cmdline_enabled = audit_policy::is_cmdline_enabled();
+
+    let (policies, audit_error) = match audit_policy::get_audit_policy() {
+        Ok(policies) => (policies, None),
+        Err(e) => (Vec::new(), Some(e.to_russian())),
+    };
+    let pending = audit_policy::pending_changes(&policies, targets);
+
+    let subcategories: Vec<status::AuditSubcategoryStatus> = targets
+        .iter()
+        .map(|target| {
+            let current = policies
+                .iter()
+                .find(|p| p.guid.eq_ignore_ascii_case(&target.guid))
+                .map(|p| p.setting)
+                .unwrap_or_default();
+            status::AuditSubcategoryStatus {
+                name: target.name.clone(),
+                guid: target.guid.clone(),
+                success: current.success,
+                failure: current.failure,
+                target_success: target.setting.success,
+                target_failure: target.setting.failure,
+                needs_change: pending.contains(&target),
+            }
+        })
+        .collect();
+
+    Ok(status::AuditPolicyStatus {
+        cmdline_enabled,
+        needs_change_count: subcategories.iter().filter(|s| s.needs_change).count(),
+        subcategories,
+        audit_error,
+    })
+}
+
+/// Check the change journal of every NTFS volume against the default size
+///
+/// # Errors
+///
+/// Never fails; per-volume errors are reported in each volume's `error`
+pub fn check_usn_journal() -> Result<status::UsnJournalStatus> {
+    check_usn_journal_sized(usn_journal::JournalSize::default())
+}
+
+/// Check the change journal of every NTFS volume against `size`
+///
+/// # Errors
+///
+/// Never fails; per-volume errors are reported in each volume's `error`
+pub fn check_usn_journal_sized(size: usn_journal::JournalSize) -> Result<status::UsnJournalStatus> {
+    let volumes: Vec<status::UsnVolumeStatus> = usn_journal::get_volume_journals()
+        .into_iter()
+        .map(|volume| status::UsnVolumeStatus {
+            needs_change: volume.needs_change(size),
+            is_active: volume.journal.is_some(),
+            journal_id: volume.journal.map(|j| j.journal_id),
+            first_usn: volume.journal.map(|j| j.first_usn),
+            next_usn: volume.journal.map(|j| j.next_usn),
+            max_size: volume.journal.map(|j| j.max_size),
+            allocation_delta: volume.journal.map(|j| j.allocation_delta),
+            volume: volume.volume,
+            error: volume.error,
+        })
+        .collect();
+
+    Ok(status::UsnJournalStatus {
+        is_enabled: volumes.iter().all(|v| !v.needs_change),
+        target_size: size.max_size,
+        volumes,
+    })
+}
+
+/// Check Recent and Prefetch contents of every shadow copy
+///
+/// Per-snapshot read failures are reported in `recent_error` and `prefetch_error`.
+///
+/// # Errors
+///
+/// Returns error if shadow copies cannot be listed
+pub fn check_shadow_copies() -> Result<status::ShadowCopyStatus> {
+    let snapshots = vss::read_snapshots()?
+        .into_iter()
+        .map(|artifacts| {
+            let snapshot = artifacts.snapshot;
+            let (recent, recent_error) = match artifacts.recent {
+                Ok(info) => (Some(info), None),
+                Err(e) => (None, Some(e.to_russian())),
+            };
+            let (prefetch, prefetch_error) = match artifacts.prefetch {
+                Ok(info) => (Some(info), None),
+                Err(e) => (None, Some(e.to_russian())),
+            };
+
+            status::ShadowCopySnapshotStatus {
+                volume: snapshot
+                    .drive_letter
+                    .unwrap_or_else(|| snapshot.volume_name.clone()),
+                id: snapshot.id,
+                device_object: snapshot.device_object,
+                created: snapshot.created,
+                recent_files_count: recent.as_ref().map_or(0, |r| r.lnk_count),
+                jumplist_count: recent.as_ref().map_or(0, |r| r.jumplist_count),
+                prefetch_count: prefetch.as_ref().map_or(0, |p| p.pf_count),
+                prefetch_parsed_count: artifacts.prefetch_files.len(),
+                prefetch_newest_run: artifacts
+                    .prefetch_files
+                    .iter()
+                    .filter_map(|file| file.last_run_times.first().copied())
+                    .max(),
+                recent_error,
+                prefetch_error,
+            }
+        })
+        .collect();
+
+    Ok(status::ShadowCopyStatus { snapshots })
+}
+
 /// Check System Restore status
 ///
 /// # Errors
@@ -73,6 +553,50 @@ pub fn enable_recent() -> Result {
     Ok(())
 }
 
+/// Enable local activity history (cloud upload stays off)
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or registry cannot be written
+pub fn enable_activities() -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::ActivitiesRequiresAdmin);
+    }
+
+    if !activities::is_activities_disabled()? {
+        return Err(RecentEnablerError::ActivitiesAlreadyEnabled);
+    }
+
+    activities::enable_activities()?;
+    Ok(())
+}
+
+/// Enable UserAssist program tracking
+///
+/// # Errors
+///
+/// Returns error if UserAssist is already enabled or registry cannot be written
+pub fn enable_userassist() -> Result {
+    if !userassist::is_userassist_disabled()? {
+        return Err(RecentEnablerError::UserAssistAlreadyEnabled);
+    }
+    userassist::enable_userassist()?;
+    Ok(())
+}
+
+/// Re-enable ShellBag saving
+///
+/// # Errors
+///
+/// Returns error if ShellBags are already enabled or registry cannot be written
+pub fn enable_shellbags() -> Result {
+    if !shellbags::is_shellbags_disabled()? {
+        return Err(RecentEnablerError::ShellBagsAlreadyEnabled);
+    }
+    shellbags::enable_shellbags()?;
+    Ok(())
+}
+
 /// Enable and start `SysMain` service
 ///
 /// # Errors
@@ -94,6 +618,175 @@ pub fn enable_sysmain() -> Result {
     Ok(())
 }
 
+/// Re-enable the `bam` driver (takes effect after reboot)
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or registry cannot be written
+pub fn enable_bam() -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::BamRequiresAdmin);
+    }
+
+    if !bam::is_bam_disabled()? {
+        return Err(RecentEnablerError::BamAlreadyEnabled);
+    }
+
+    bam::enable_bam()?;
+    Ok(())
+}
+
+/// Start DPS and clear zero SRUM flush period overrides
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or service/registry cannot be changed
+pub fn enable_srum() -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::SrumRequiresAdmin);
+    }
+
+    if !srum::is_srum_disabled()? {
+        return Err(RecentEnablerError::SrumAlreadyEnabled);
+    }
+
+    srum::enable_srum()?;
+    Ok(())
+}
+
+/// Enable and resize the default forensic event log channels
+///
+/// # Errors
+///
+/// Returns error if not admin, nothing to change, or `wevtutil` fails
+pub fn enable_eventlog() -> Result {
+    enable_eventlog_channels(&eventlog::default_channels())
+}
+
+/// Enable and resize the given event log channels
+///
+/// # Errors
+///
+/// Returns error if not admin, nothing to change, or `wevtutil` fails
+pub fn enable_eventlog_channels(targets: &[eventlog::ChannelTarget]) -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::EventLogRequiresAdmin);
+    }
+
+    if check_eventlog_channels(targets)?.needs_change_count == 0 {
+        return Err(RecentEnablerError::EventLogAlreadyEnabled);
+    }
+
+    eventlog::enable_channels(targets)?;
+    Ok(())
+}
+
+/// Enable PowerShell logging with transcripts in the default folder
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or registry cannot be written
+pub fn enable_powershell_logging() -> Result {
+    enable_powershell_logging_to(&powershell_logging::default_transcript_directory())
+}
+
+/// Enable PowerShell logging with transcripts written to `output_directory`
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or registry cannot be written
+pub fn enable_powershell_logging_to(output_directory: &std::path::Path) -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::PowerShellLoggingRequiresAdmin);
+    }
+
+    if !powershell_logging::is_powershell_logging_disabled()? {
+        return Err(RecentEnablerError::PowerShellLoggingAlreadyEnabled);
+    }
+
+    powershell_logging::enable_powershell_logging(output_directory)?;
+    Ok(())
+}
+
+/// Enable the default audit subcategories and process command line auditing
+///
+/// # Errors
+///
+/// Returns error if not admin, already configured, or `auditpol` fails
+pub fn enable_audit_policy() -> Result {
+    enable_audit_policy_subcategories(&audit_policy::default_subcategories())
+}
+
+/// Enable the given audit subcategories and process command line auditing
+///
+/// # Errors
+///
+/// Returns error if not admin, already configured, or `auditpol` fails
+pub fn enable_audit_policy_subcategories(targets: &[audit_policy::AuditTarget]) -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::AuditPolicyRequiresAdmin);
+    }
+
+    let policies = audit_policy::get_audit_policy()?;
+    let cmdline_enabled = audit_policy::is_cmdline_enabled();
+    if cmdline_enabled && audit_policy::pending_changes(&policies, targets).is_empty() {
+        return Err(RecentEnablerError::AuditPolicyAlreadyEnabled);
+    }
+
+    audit_policy::apply_audit_policy(targets)?;
+    if !cmdline_enabled {
+        audit_policy::enable_cmdline()?;
+    }
+    Ok(())
+}
+
+/// Enable user-managed NTFS last-access timestamp updates
+///
+/// The change takes effect after a reboot.
+///
+/// # Errors
+///
+/// Returns error if not admin, already enabled, or registry cannot be written
+pub fn enable_last_access() -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::LastAccessRequiresAdmin);
+    }
+
+    if !last_access::is_last_access_disabled()? {
+        return Err(RecentEnablerError::LastAccessAlreadyEnabled);
+    }
+
+    last_access::enable_last_access()?;
+    Ok(())
+}
+
+/// Create or grow the change journal on every NTFS volume to the default size
+///
+/// # Errors
+///
+/// Returns error if not admin, nothing to change, or `fsutil` fails
+pub fn enable_usn_journal() -> Result {
+    enable_usn_journal_sized(usn_journal::JournalSize::default())
+}
+
+/// Create or grow the change journal on every NTFS volume to `size`
+///
+/// # Errors
+///
+/// Returns error if not admin, nothing to change, or `fsutil` fails
+pub fn enable_usn_journal_sized(size: usn_journal::JournalSize) -> Result {
+    if !utils::is_admin() {
+        return Err(RecentEnablerError::UsnJournalRequiresAdmin);
+    }
+
+    if check_usn_journal_sized(size)?.is_enabled {
+        return Err(RecentEnablerError::UsnJournalAlreadyEnabled);
+    }
+
+    usn_journal::enable_usn_journals(size)?;
+    Ok(())
+}
+
 /// Enable System Restore on C: drive
 ///
 /// # Errors
@@ -0,0 +1,373 @@
+use crate::{binary, known_folders, utils};
+use std::time::SystemTime;
+
+/// Signature of the file entry extension block carrying the long name and times
+const BEEF0004_SIGNATURE: [u8; 4] = [0x04, 0x00, 0xEF, 0xBE];
+
+/// Signature of a delegate item wrapping a file entry (`CFSF`)
+const DELEGATE_SIGNATURE: &[u8] = b"CFSF";
+
+/// Shell folder CLSIDs that appear as root items in ID lists
+const ROOT_FOLDERS: &[(&str, &str)] = &[
+    ("{20D04FE0-3AEA-1069-A2D8-08002B30309D}", "My Computer"),
+    ("{59031A47-3F72-44A7-89C5-5595FE6B30EE}", "Users Files"),
+    ("{F02C1A0D-BE21-4350-88B0-7367FC96EF3C}", "Network"),
+    (
+        "{208D2C60-3AEA-1069-A2D7-08002B30309D}",
+        "My Network Places",
+    ),
+    ("{645FF040-5081-101B-9F08-00AA002F954E}", "Recycle Bin"),
+    ("{031E4825-7B94-4DC3-B131-E946B44C8DD5}", "Libraries"),
+    ("{679F85CB-0220-4080-B29B-5540CC05AAB6}", "Quick Access"),
+    ("{26EE0668-A00A-44D7-9371-BEB064C98683}", "Control Panel"),
+    (
+        "{21EC2020-3AEA-1069-A2DD-08002B30309D}",
+        "Control Panel Items",
+    ),
+    ("{450D8FBA-AD25-11D0-98A8-0800361B1103}", "My Documents"),
+    ("{018D5C66-4533-4307-9B53-224DE2ED1FE6}", "OneDrive"),
+    ("{B4BFCC3A-DB2C-424C-B029-7FE99A87C641}", "Desktop"),
+    ("{088E3905-0323-4B02-9826-5D99428E115F}", "Downloads"),
+    ("{D3162B92-9365-467A-956B-92703ACA08AF}", "Documents"),
+    ("{24AD3AD4-A569-4530-98E1-AB02F9417AA8}", "Pictures"),
+    ("{3DFDF296-DBEC-4FB4-81D1-6A3438BCF4DE}", "Music"),
+    ("{F86FA3AB-70D2-4FC7-9C99-FCBF05467F3A}", "Videos"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellItemKind {
+    RootFolder,
+    Volume,
+    File,
+    Network,
+    ControlPanel,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShellItem {
+    pub kind: ShellItemKind,
+    /// Raw class type indicator byte
+    pub class_type: u8,
+    pub name: String,
+    /// Modification time stored in the item header
+    pub modified: Option<SystemTime>,
+    /// Creation time from the `0xBEEF0004` extension block
+    pub created: Option<SystemTime>,
+    /// Last access time from the `0xBEEF0004` extension block
+    pub accessed: Option<SystemTime>,
+    /// NTFS file reference from the extension block (Vista and later)
+    pub mft_reference: Option<u64>,
+}
+
+impl ShellItem {
+    fn new(kind: ShellItemKind, class_type: u8, name: String) -> Self {
+        Self {
+            kind,
+            class_type,
+            name,
+            modified: None,
+            created: None,
+            accessed: None,
+            mft_reference: None,
+        }
+    }
+}
+
+fn root_folder_name(guid: &str) -> String {
+    ROOT_FOLDERS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(guid))
+        .map(|(_, name)| (*name).to_string())
+        .or_else(|| known_folders::known_folder_template(guid).map(utils::expand_env_vars))
+        .unwrap_or_else(|| guid.to_string())
+}
+
+fn read_dos_time(data: &[u8], offset: usize) -> Option<SystemTime> {
+    let date = binary::read_u16(data, offset)?;
+    let time = binary::read_u16(data, offset + 2)?;
+    utils::dos_datetime_to_system_time(date, time)
+}
+
+/// Apply the `0xBEEF0004` extension block (long name, times, file reference)
+fn apply_file_extension(item: &mut ShellItem, data: &[u8]) {
+    let Some(sig) = binary::find_bytes(data, &BEEF0004_SIGNATURE, 0) else {
+        return;
+    };
+    let Some(start) = sig.checked_sub(4) else {
+        return;
+    };
+    let block = &data[start..];
+    let (Some(size), Some(version)) = (binary::read_u16(block, 0), binary::read_u16(block, 2))
+    else {
+        return;
+    };
+    let block = &block[..usize::from(size).min(block.len())];
+
+    item.created = read_dos_time(block, 8);
+    item.accessed = read_dos_time(block, 12);
+
+    // The long name's offset is stored at 16: 0x14 in version 3, 0x26 in
+    // version 7 (which added the file reference), growing as later versions
+    // add fields
+    let min_offset = if version >= 7 {
+        item.mft_reference = binary::read_u64(block, 20).map(|r| r & 0x0000_FFFF_FFFF_FFFF);
+        28
+    } else {
+        20
+    };
+    let name_offset = match binary::read_u16(block, 16).map(usize::from) {
+        Some(offset) if offset >= min_offset => offset,
+        _ => return,
+    };
+    if let Some(long_name) = binary::read_utf16_z(block, name_offset) {
+        if !long_name.is_empty() {
+            item.name = long_name;
+        }
+    }
+}
+
+/// Parse a file entry item whose header starts at `header` inside `data`
+fn parse_file_entry(data: &[u8], header: usize, class_type: u8) -> ShellItem {
+    let primary_name = if class_type & 0x04 != 0 {
+        binary::read_utf16_z(data, header + 14)
+    } else {
+        binary::read_ascii_z(data, header + 14)
+    };
+
+    let mut item = ShellItem::new(
+        ShellItemKind::File,
+        class_type,
+        primary_name.unwrap_or_default(),
+    );
+    item.modified = read_dos_time(data, header + 8);
+    apply_file_extension(&mut item, data);
+    item
+}
+
+/// Parse a single shell item (including its leading size field)
+///
+/// Returns `None` for empty or truncated items.
+#[must_use]
+pub fn parse_shell_item(data: &[u8]) -> Option<ShellItem> {
+    let size = usize::from(binary::read_u16(data, 0)?);
+    if size < 3 {
+        return None;
+    }
+    let data = data.get(..size)?;
+    let class_type = binary::read_u8(data, 2)?;
+
+    let item = match class_type {
+        0x1F => ShellItem::new(
+            ShellItemKind::RootFolder,
+            class_type,
+            root_folder_name(&binary::format_guid(data, 4)?),
+        ),
+        0x2E if size >= 20 => ShellItem::new(
+            ShellItemKind::RootFolder,
+            class_type,
+            root_folder_name(&binary::format_guid(data, 4)?),
+        ),
+        0x20..=0x2F => ShellItem::new(
+            ShellItemKind::Volume,
+            class_type,
+            binary::read_ascii_z(data, 3)?,
+        ),
+        0x30..=0x3F => parse_file_entry(data, 0, class_type),
+        0x40..=0x4F => ShellItem::new(
+            ShellItemKind::Network,
+            class_type,
+            binary::read_ascii_z(data, 5)?,
+        ),
+        0x71 => ShellItem::new(
+            ShellItemKind::ControlPanel,
+            class_type,
+            root_folder_name(&binary::format_guid(data, 14)?),
+        ),
+        0x74 if data.get(6..10) == Some(DELEGATE_SIGNATURE) => {
+            let inner_type = binary::read_u8(data, 12)?;
+            let mut item = parse_file_entry(data, 10, inner_type);
+            item.class_type = class_type;
+            item
+        }
+        _ => ShellItem::new(
+            ShellItemKind::Unknown,
+            class_type,
+            format!("<0x{class_type:02X}>"),
+        ),
+    };
+
+    Some(item)
+}
+
+/// Parse a sequence of shell items terminated by a zero size field
+#[must_use]
+pub fn parse_id_list(data: &[u8]) -> Vec<ShellItem> {
+    let mut items = Vec::new();
+    let mut offset = 0;
+
+    while let Some(size) = binary::read_u16(data, offset) {
+        if size == 0 {
+            break;
+        }
+        let Some(item) = parse_shell_item(&data[offset..]) else {
+            break;
+        };
+        items.push(item);
+        offset += usize::from(size);
+    }
+
+    items
+}
+
+/// Append a shell item name to a parent path the way Explorer displays it
+#[must_use]
+pub fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else if parent.ends_with('\\') {
+        format!("{parent}{name}")
+    } else {
+        format!("{parent}\\{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root_item(guid: [u8; 16]) -> Vec<u8> {
+        let mut data = vec![0x14, 0x00, 0x1F, 0x50];
+        data.extend_from_slice(&guid);
+        data
+    }
+
+    /// File entry with a `0xBEEF0004` block of the given version
+    fn file_item(short_name: &str, long_name: &str, version: u16) -> Vec<u8> {
+        let mut data = vec![0u8, 0, 0x31, 0];
+        data.extend_from_slice(&0u32.to_le_bytes());
+        // 2020-06-15 12:30:20
+        data.extend_from_slice(&0x50CFu16.to_le_bytes());
+        data.extend_from_slice(&0x63CAu16.to_le_bytes());
+        data.extend_from_slice(&0x10u16.to_le_bytes());
+        data.extend_from_slice(short_name.as_bytes());
+        data.push(0);
+        if data.len() % 2 != 0 {
+            data.push(0);
+        }
+
+        let name_offset: u16 = match version {
+            0..=6 => 0x14,
+            7 => 0x26,
+            8 => 0x2A,
+            _ => 0x2E,
+        };
+        let mut ext = Vec::new();
+        ext.extend_from_slice(&0u16.to_le_bytes());
+        ext.extend_from_slice(&version.to_le_bytes());
+        ext.extend_from_slice(&BEEF0004_SIGNATURE);
+        ext.extend_from_slice(&0x50CFu16.to_le_bytes());
+        ext.extend_from_slice(&0x6000u16.to_le_bytes());
+        ext.extend_from_slice(&0x50D0u16.to_le_bytes());
+        ext.extend_from_slice(&0x7000u16.to_le_bytes());
+        ext.extend_from_slice(&name_offset.to_le_bytes());
+        if version >= 7 {
+            ext.extend_from_slice(&0u16.to_le_bytes());
+            ext.extend_from_slice(&0x0005_0000_0000_1234u64.to_le_bytes());
+        } else {
+            // Version 3 stores the long string size before the name
+            let size = u16::try_from(2 * (long_name.encode_utf16().count() + 1)).unwrap();
+            ext.extend_from_slice(&size.to_le_bytes());
+        }
+        ext.resize(usize::from(name_offset), 0);
+        for unit in long_name.encode_utf16().chain(Some(0)) {
+            ext.extend_from_slice(&unit.to_le_bytes());
+        }
+        let ext_len = u16::try_from(ext.len()).unwrap();
+        ext[0..2].copy_from_slice(&ext_len.to_le_bytes());
+
+        data.extend_from_slice(&ext);
+        let len = u16::try_from(data.len()).unwrap();
+        data[0..2].copy_from_slice(&len.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_root_folder() {
+        let guid = [
+            0xE0, 0x4F, 0xD0, 0x20, 0xEA, 0x3A, 0x69, 0x10, 0xA2, 0xD8, 0x08, 0x00, 0x2B, 0x30,
+            0x30, 0x9D,
+        ];
+        let item = parse_shell_item(&root_item(guid)).unwrap();
+        assert_eq!(item.kind, ShellItemKind::RootFolder);
+        assert_eq!(item.name, "My Computer");
+    }
+
+    #[test]
+    fn test_parse_file_entry_with_extension() {
+        let data = file_item("PROGRA~1", "Program Files", 9);
+        let item = parse_shell_item(&data).unwrap();
+        assert_eq!(item.kind, ShellItemKind::File);
+        assert_eq!(item.name, "Program Files");
+        assert_eq!(item.mft_reference, Some(0x1234));
+
+        let modified = item
+            .modified
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        // 2020-06-15 12:30:20 UTC
+        assert_eq!(modified.as_secs(), 1_592_224_220);
+        assert!(item.created.is_some());
+        assert!(item.accessed > item.created);
+    }
+
+    #[test]
+    fn test_parse_file_entry_extension_versions() {
+        for version in [3, 7, 8, 9] {
+            let item = parse_shell_item(&file_item("DOCUME~1", "Documents", version)).unwrap();
+            assert_eq!(item.name, "Documents", "version {version}");
+            assert_eq!(
+                item.mft_reference,
+                (version >= 7).then_some(0x1234),
+                "version {version}"
+            );
+        }
+
+        // A later version with more fields before the name still finds it
+        let mut data = file_item("DOCUME~1", "Documents", 9);
+        let block = binary::find_bytes(&data, &BEEF0004_SIGNATURE, 0).unwrap() - 4;
+        data[block + 2..block + 4].copy_from_slice(&10u16.to_le_bytes());
+        data[block + 16..block + 18].copy_from_slice(&0x32u16.to_le_bytes());
+        data.splice(block + 0x2E..block + 0x2E, [0u8; 4]);
+        let size = binary::read_u16(&data, block).unwrap() + 4;
+        data[block..block + 2].copy_from_slice(&size.to_le_bytes());
+        let item_size = binary::read_u16(&data, 0).unwrap() + 4;
+        data[0..2].copy_from_slice(&item_size.to_le_bytes());
+        assert_eq!(parse_shell_item(&data).unwrap().name, "Documents");
+
+        // An offset pointing back into the fixed fields is ignored
+        let mut data = file_item("DOCUME~1", "Documents", 3);
+        let block = binary::find_bytes(&data, &BEEF0004_SIGNATURE, 0).unwrap() - 4;
+        data[block + 16..block + 18].copy_from_slice(&18u16.to_le_bytes());
+        assert_eq!(parse_shell_item(&data).unwrap().name, "DOCUME~1");
+    }
+
+    #[test]
+    fn test_parse_id_list() {
+        let mut data = vec![0x19, 0x00, 0x2F];
+        data.extend_from_slice(b"C:\\");
+        data.resize(0x19, 0);
+        data.extend_from_slice(&file_item("Users", "Users", 9));
+        data.extend_from_slice(&[0, 0]);
+
+        let items = parse_id_list(&data);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].kind, ShellItemKind::Volume);
+
+        let path = items
+            .iter()
+            .fold(String::new(), |path, item| join_path(&path, &item.name));
+        assert_eq!(path, r"C:\Users");
+    }
+}
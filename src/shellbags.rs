@@ -0,0 +1,30 @@
+use crate::shell_item;
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Shell settings key backed by `UsrClass.dat`
+const SHELL_PATH: &str = r"Software\Classes\Local Settings\Software\Microsoft\Windows\Shell";
+#[derive(Debug, Clone)]
+pub struct ShellBagEntry {
+    /// Full path rebuilt from the chain of shell items
+    pub path: String,
+    /// BagMRU key path relative to `BagMRU`, e.g. `0\1\3`
+    pub mru_path: String,
+    pub kind: shell_item::ShellItemKind,
+    /// Last write time of the item's BagMRU key
+    pub key_last_write: Option<SystemTime>,
+    /// Folder creation time from the extension block (when first explored)
+    pub first_explored: Option<SystemTime>,
+    /// Folder last access time from the extension block (when last explored)
+    pub last_explored: Option<SystemTime>,
+}
+
+/// Get the registry path of the BagMRU root
+#[must_use]
+pub fn get_bagmru_path() -> String {
+    format!(r"HKCU\{SHELL_PATH}\BagMRU")
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+use crate::{
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+
+const POLICIES_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Policies\Explorer";
+
+/// Windows default for `BagMRU Size`
+const DEFAULT_BAGMRU_SIZE: u32 = 5000;
+
+/// Guard against malformed, self-referencing hives
+const MAX_DEPTH: usize = 64;
+
+/// Check if ShellBag saving is disabled in registry
+///
+/// Saving is off when the `NoSaveSettings` policy is set or `BagMRU Size` is zero.
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_shellbags_disabled() -> Result<bool> {
+    let no_save =
+        utils::read_reg_dword(HKEY_CURRENT_USER, POLICIES_PATH, "NoSaveSettings").unwrap_or(0) == 1;
+    let bagmru_size = utils::read_reg_dword(HKEY_CURRENT_USER, SHELL_PATH, "BagMRU Size")
+        .unwrap_or(DEFAULT_BAGMRU_SIZE);
+
+    Ok(no_save || bagmru_size == 0)
+}
+
+/// Re-enable ShellBag saving in Windows registry
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be written
+pub fn enable_shellbags() -> Result {
+    utils::write_reg_dword(HKEY_CURRENT_USER, POLICIES_PATH, "NoSaveSettings", 0)
+        .map_err(|e| RecentEnablerError::ShellBagsEnableFailed(e.to_string()))?;
+    utils::write_reg_dword(
+        HKEY_CURRENT_USER,
+        SHELL_PATH,
+        "BagMRU Size",
+        DEFAULT_BAGMRU_SIZE,
+    )
+    .map_err(|e| RecentEnablerError::ShellBagsEnableFailed(e.to_string()))?;
+
+    Ok(())
+}
+
+fn walk_bagmru(
+    key: &RegKey,
+    parent_path: &str,
+    mru_path: &str,
+    depth: usize,
+    entries: &mut Vec<ShellBagEntry>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    for (name, value) in key.enum_values().filter_map(std::result::Result::ok) {
+        if name.parse::<u32>().is_err() {
+            continue;
+        }
+        let Some(item) = shell_item::parse_shell_item(&value.bytes) else {
+            continue;
+        };
+
+        let path = shell_item::join_path(parent_path, &item.name);
+        let child_mru = shell_item::join_path(mru_path, &name);
+        let child_key = key.open_subkey(&name).ok();
+
+        entries.push(ShellBagEntry {
+            path: path.clone(),
+            mru_path: child_mru.clone(),
+            kind: item.kind,
+            key_last_write: child_key.as_ref().and_then(utils::reg_key_last_write),
+            first_explored: item.created,
+            last_explored: item.accessed,
+        });
+
+        if let Some(child_key) = child_key {
+            walk_bagmru(&child_key, &path, &child_mru, depth + 1, entries);
+        }
+    }
+}
+
+/// Walk BagMRU recursively and rebuild the explored folder paths
+///
+/// # Errors
+///
+/// Returns error if the BagMRU key exists but cannot be opened
+pub fn get_shellbags() -> Result<Vec<ShellBagEntry>> {
+    let root = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(format!(r"{SHELL_PATH}\BagMRU"))
+    {
+        Ok(key) => key,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(RecentEnablerError::ShellBagsReadFailed(e.to_string())),
+    };
+
+    let mut entries = Vec::new();
+    walk_bagmru(&root, "", "", 0, &mut entries);
+    Ok(entries)
+}
@@ -0,0 +1,329 @@
+use crate::{
+    binary,
+    error::{RecentEnablerError, Result},
+    utils,
+};
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const WIN7_SIGNATURE: u32 = 0xBADC_0FEE;
+const WIN7_HEADER_SIZE: usize = 0x80;
+const WIN7_X64_ENTRY_SIZE: usize = 48;
+const WIN7_X86_ENTRY_SIZE: usize = 32;
+const WIN8_HEADER_SIZE: usize = 0x80;
+const WIN10_HEADER_SIZE: usize = 0x30;
+const WIN10_CREATORS_HEADER_SIZE: usize = 0x34;
+const WIN8_MAGIC: &[u8] = b"00ts";
+const WIN81_MAGIC: &[u8] = b"10ts";
+
+/// Insert flag set by CSRSS when the process was executed
+const INSERT_FLAG_EXECUTED: u32 = 0x0000_0002;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShimCacheFormat {
+    Windows7X86,
+    Windows7X64,
+    Windows8,
+    Windows81,
+    /// Windows 10 before 1703
+    Windows10,
+    /// Windows 10 1703+ and Windows 11
+    Windows10Creators,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShimCacheEntry {
+    /// Position in the cache, 0 being the most recently inserted
+    pub position: usize,
+    pub path: String,
+    /// Last modification time of the file when it was cached
+    pub last_modified: Option<SystemTime>,
+    /// Raw insert flags (Windows 7 and 8 only)
+    pub insert_flags: Option<u32>,
+    /// Shim flags (Windows 7 and 8 only)
+    pub shim_flags: Option<u32>,
+    /// Execution flag, `None` where the format doesn't record it
+    pub executed: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShimCache {
+    pub format: ShimCacheFormat,
+    pub entries: Vec<ShimCacheEntry>,
+}
+
+fn parse_error(msg: &str) -> RecentEnablerError {
+    RecentEnablerError::ShimCacheParseFailed(msg.to_string())
+}
+
+fn normalize_path(path: &str) -> String {
+    path.strip_prefix(r"\??\").unwrap_or(path).to_string()
+}
+
+/// Detect the cache format from its header
+///
+/// # Errors
+///
+/// Returns error for unknown or unsupported (XP, Vista) formats
+pub fn detect_format(data: &[u8]) -> Result<ShimCacheFormat> {
+    let header = binary::read_u32(data, 0).ok_or_else(|| parse_error("empty cache"))?;
+
+    if header == WIN7_SIGNATURE {
+        // x64 entries pad the path length to 8 bytes, so bytes 4..8 are zero
+        let padding = binary::read_u32(data, WIN7_HEADER_SIZE + 4).unwrap_or(0);
+        return Ok(if padding == 0 {
+            ShimCacheFormat::Windows7X64
+        } else {
+            ShimCacheFormat::Windows7X86
+        });
+    }
+
+    let header_size = header as usize;
+    let magic = data.get(header_size..header_size + 4);
+    match (header_size, magic) {
+        (WIN8_HEADER_SIZE, Some(WIN8_MAGIC)) => Ok(ShimCacheFormat::Windows8),
+        (WIN8_HEADER_SIZE, Some(WIN81_MAGIC)) => Ok(ShimCacheFormat::Windows81),
+        (WIN10_HEADER_SIZE, Some(WIN81_MAGIC)) => Ok(ShimCacheFormat::Windows10),
+        (WIN10_CREATORS_HEADER_SIZE, Some(WIN81_MAGIC)) => Ok(ShimCacheFormat::Windows10Creators),
+        _ => Err(parse_error(&format!(
+            "unsupported cache signature 0x{header:08X}"
+        ))),
+    }
+}
+
+fn parse_win7(data: &[u8], x64: bool) -> Result<Vec<ShimCacheEntry>> {
+    let count = binary::read_u32(data, 4).ok_or_else(|| parse_error("truncated header"))? as usize;
+    let entry_size = if x64 {
+        WIN7_X64_ENTRY_SIZE
+    } else {
+        WIN7_X86_ENTRY_SIZE
+    };
+
+    let mut entries = Vec::new();
+    for position in 0..count {
+        let entry = WIN7_HEADER_SIZE + position * entry_size;
+        let truncated = || parse_error("truncated Windows 7 entry");
+
+        let path_len = usize::from(binary::read_u16(data, entry).ok_or_else(truncated)?);
+        let (path_offset, rest) = if x64 {
+            (
+                binary::read_u64(data, entry + 8).ok_or_else(truncated)? as usize,
+                entry + 16,
+            )
+        } else {
+            (
+                binary::read_u32(data, entry + 4).ok_or_else(truncated)? as usize,
+                entry + 8,
+            )
+        };
+        let filetime = binary::read_u64(data, rest).ok_or_else(truncated)?;
+        let insert_flags = binary::read_u32(data, rest + 8).ok_or_else(truncated)?;
+        let shim_flags = binary::read_u32(data, rest + 12).ok_or_else(truncated)?;
+        let path_bytes = path_offset
+            .checked_add(path_len)
+            .and_then(|end| data.get(path_offset..end))
+            .ok_or_else(truncated)?;
+
+        entries.push(ShimCacheEntry {
+            position,
+            path: normalize_path(&binary::read_utf16_z(path_bytes, 0).unwrap_or_default()),
+            last_modified: utils::filetime_to_system_time(filetime),
+            insert_flags: Some(insert_flags),
+            shim_flags: Some(shim_flags),
+            executed: Some(insert_flags & INSERT_FLAG_EXECUTED != 0),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parse the `XXts` records used by Windows 8 and later
+fn parse_ts_entries(
+    data: &[u8],
+    header_size: usize,
+    format: ShimCacheFormat,
+) -> Result<Vec<ShimCacheEntry>> {
+    let magic = if format == ShimCacheFormat::Windows8 {
+        WIN8_MAGIC
+    } else {
+        WIN81_MAGIC
+    };
+
+    let mut entries = Vec::new();
+    let mut offset = header_size;
+    while data.get(offset..offset + 4) == Some(magic) {
+        let truncated = || parse_error("truncated cache entry");
+        let entry_len = binary::read_u32(data, offset + 8).ok_or_else(truncated)? as usize;
+        let body = data
+            .get(offset + 12..offset + 12 + entry_len)
+            .ok_or_else(truncated)?;
+        offset += 12 + entry_len;
+
+        let path_len = usize::from(binary::read_u16(body, 0).ok_or_else(truncated)?);
+        let path_bytes = body.get(2..2 + path_len).ok_or_else(truncated)?;
+        let mut cursor = 2 + path_len;
+
+        if format == ShimCacheFormat::Windows81 {
+            let package_len = usize::from(binary::read_u16(body, cursor).ok_or_else(truncated)?);
+            cursor += 2 + package_len;
+        }
+
+        let (insert_flags, shim_flags, executed) = match format {
+            ShimCacheFormat::Windows8 | ShimCacheFormat::Windows81 => {
+                let insert = binary::read_u32(body, cursor).ok_or_else(truncated)?;
+                let shim = binary::read_u32(body, cursor + 4).ok_or_else(truncated)?;
+                cursor += 8;
+                (
+                    Some(insert),
+                    Some(shim),
+                    Some(insert & INSERT_FLAG_EXECUTED != 0),
+                )
+            }
+            _ => (None, None, None),
+        };
+
+        let filetime = binary::read_u64(body, cursor).ok_or_else(truncated)?;
+        let data_len = binary::read_u32(body, cursor + 8).ok_or_else(truncated)? as usize;
+        let executed = if format == ShimCacheFormat::Windows10Creators && data_len >= 4 {
+            // The trailing DWORD of the shim data records execution on 1703+
+            let flag_offset = cursor + 12 + data_len - 4;
+            binary::read_u32(body, flag_offset).map(|flag| flag == 1)
+        } else {
+            executed
+        };
+
+        entries.push(ShimCacheEntry {
+            position: entries.len(),
+            path: normalize_path(&binary::read_utf16_z(path_bytes, 0).unwrap_or_default()),
+            last_modified: utils::filetime_to_system_time(filetime),
+            insert_flags,
+            shim_flags,
+            executed,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Decode an `AppCompatCache` value
+///
+/// # Errors
+///
+/// Returns error if the format is unsupported or the data is truncated
+pub fn parse_shimcache(data: &[u8]) -> Result<ShimCache> {
+    let format = detect_format(data)?;
+    let entries = match format {
+        ShimCacheFormat::Windows7X64 => parse_win7(data, true)?,
+        ShimCacheFormat::Windows7X86 => parse_win7(data, false)?,
+        ShimCacheFormat::Windows8 | ShimCacheFormat::Windows81 => {
+            parse_ts_entries(data, WIN8_HEADER_SIZE, format)?
+        }
+        ShimCacheFormat::Windows10 => parse_ts_entries(data, WIN10_HEADER_SIZE, format)?,
+        ShimCacheFormat::Windows10Creators => {
+            parse_ts_entries(data, WIN10_CREATORS_HEADER_SIZE, format)?
+        }
+    };
+
+    Ok(ShimCache { format, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_entries(cache: &ShimCache, flags_recorded: bool) {
+        assert_eq!(cache.entries.len(), 3);
+
+        let first = &cache.entries[0];
+        assert_eq!(first.path, r"C:\Windows\System32\cmd.exe");
+        let secs = first
+            .last_modified
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(secs, 1_609_459_200);
+
+        assert_eq!(cache.entries[1].path, r"C:\Tools\procdump64.exe");
+        assert_eq!(cache.entries[2].path, r"C:\Program Files\7-Zip\7z.exe");
+        assert!(cache.entries[2].last_modified.is_none());
+
+        if flags_recorded {
+            assert_eq!(first.executed, Some(true));
+            assert_eq!(cache.entries[1].executed, Some(false));
+        }
+    }
+
+    #[test]
+    fn test_windows7_x64() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win7_x64.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows7X64);
+        assert_entries(&cache, true);
+        assert_eq!(cache.entries[0].insert_flags, Some(2));
+    }
+
+    #[test]
+    fn test_windows7_x86() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win7_x86.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows7X86);
+        assert_entries(&cache, true);
+    }
+
+    #[test]
+    fn test_windows8() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win8.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows8);
+        assert_entries(&cache, true);
+    }
+
+    #[test]
+    fn test_windows81() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win81.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows81);
+        assert_entries(&cache, true);
+    }
+
+    #[test]
+    fn test_windows10() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win10.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows10);
+        assert_entries(&cache, false);
+        assert!(cache.entries.iter().all(|e| e.executed.is_none()));
+    }
+
+    #[test]
+    fn test_windows11() {
+        let cache =
+            parse_shimcache(include_bytes!("../tests/fixtures/shimcache/win11.bin")).unwrap();
+        assert_eq!(cache.format, ShimCacheFormat::Windows10Creators);
+        assert_entries(&cache, true);
+    }
+
+    #[test]
+    fn test_windows7_malformed_path_offset() {
+        let mut data = include_bytes!("../tests/fixtures/shimcache/win7_x64.bin").to_vec();
+        let offset = WIN7_HEADER_SIZE + 8;
+        data[offset..offset + 8].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+        assert!(parse_shimcache(&data).is_err());
+
+        let mut data = include_bytes!("../tests/fixtures/shimcache/win7_x86.bin").to_vec();
+        let offset = WIN7_HEADER_SIZE + 4;
+        data[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_shimcache(&data).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(parse_shimcache(&0xDEAD_BEEFu32.to_le_bytes()).is_err());
+        assert!(parse_shimcache(&[]).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+const APPCOMPATCACHE_PATH: &str =
+    r"SYSTEM\CurrentControlSet\Control\Session Manager\AppCompatCache";
+
+/// Read and decode the live `AppCompatCache` value
+///
+/// The cache is only flushed to the registry on shutdown, so entries from the
+/// current boot are not visible here.
+///
+/// # Errors
+///
+/// Returns error if the value cannot be read or decoded
+pub fn read_shimcache() -> Result<ShimCache> {
+    let value = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(APPCOMPATCACHE_PATH)
+        .and_then(|key| key.get_raw_value("AppCompatCache"))
+        .map_err(|e| RecentEnablerError::ShimCacheReadFailed(e.to_string()))?;
+    parse_shimcache(&value.bytes)
+}
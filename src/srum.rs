@@ -0,0 +1,361 @@
+use crate::{
+    binary,
+    error::{RecentEnablerError, Result},
+    ese::{EseDatabase, EseRecord, EseValue},
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const ID_MAP_TABLE: &str = "SruDbIdMapTable";
+const APP_RESOURCE_USAGE_TABLE: &str = "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA89}";
+const NETWORK_USAGE_TABLE: &str = "{973F5D5C-1D90-4944-BE8E-24B94231A174}";
+/// `IdType` of `SruDbIdMapTable` rows holding a binary user SID
+const ID_TYPE_USER_SID: i64 = 3;
+
+#[derive(Debug, Clone, Default)]
+pub struct SrumParameters {
+    pub tier1_period: Option<u32>,
+    pub tier2_period: Option<u32>,
+}
+
+impl SrumParameters {
+    /// Whether a flush period was overridden with zero
+    #[must_use]
+    pub const fn has_zero_period(&self) -> bool {
+        matches!(self.tier1_period, Some(0)) || matches!(self.tier2_period, Some(0))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SrumAppUsage {
+    pub timestamp: Option<SystemTime>,
+    pub app: String,
+    pub user: Option<String>,
+    pub foreground_cycle_time: u64,
+    pub background_cycle_time: u64,
+    pub face_time: u64,
+    pub foreground_bytes_read: u64,
+    pub foreground_bytes_written: u64,
+    pub background_bytes_read: u64,
+    pub background_bytes_written: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SrumNetworkUsage {
+    pub timestamp: Option<SystemTime>,
+    pub app: String,
+    pub user: Option<String>,
+    pub interface_luid: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SrumData {
+    pub app_usage: Vec<SrumAppUsage>,
+    pub network_usage: Vec<SrumNetworkUsage>,
+    /// Id map entries whose name the ESE reader can't decode; rows that refer
+    /// to them show `#id`
+    pub unreadable_names: usize,
+}
+
+impl SrumData {
+    /// Latest timestamp across both tables
+    #[must_use]
+    pub fn newest_time(&self) -> Option<SystemTime> {
+        let app = self.app_usage.iter().filter_map(|e| e.timestamp);
+        let network = self.network_usage.iter().filter_map(|e| e.timestamp);
+        app.chain(network).max()
+    }
+}
+
+/// `SruDbIdMapTable` decoded into names by index
+#[derive(Debug, Clone, Default)]
+pub struct SrumIdMap {
+    pub names: HashMap<i64, String>,
+    /// Entries whose `IdBlob` is a long value or uses unsupported compression
+    pub unreadable: usize,
+}
+
+/// Map `SruDbIdMapTable` indexes to application names and user SIDs
+///
+/// # Errors
+///
+/// Returns error if the table cannot be decoded
+pub fn read_id_map(db: &EseDatabase) -> Result<SrumIdMap> {
+    let Some(table) = db.table(ID_MAP_TABLE) else {
+        return Ok(SrumIdMap::default());
+    };
+
+    let mut map = SrumIdMap::default();
+    for record in db.records(table)? {
+        if record.is_unreadable("IdBlob") {
+            map.unreadable += 1;
+            continue;
+        }
+        let (Some(index), Some(blob)) = (
+            record.get("IdIndex").and_then(EseValue::as_i64),
+            record.get("IdBlob").and_then(EseValue::as_bytes),
+        ) else {
+            continue;
+        };
+        let id_type = record.get("IdType").and_then(EseValue::as_i64);
+        let name = if id_type == Some(ID_TYPE_USER_SID) {
+            binary::format_sid(blob)
+        } else {
+            binary::read_utf16_z(blob, 0)
+        };
+        if let Some(name) = name {
+            map.names.insert(index, name);
+        }
+    }
+    Ok(map)
+}
+
+fn counter(record: &EseRecord, name: &str) -> u64 {
+    record
+        .get(name)
+        .and_then(EseValue::as_i64)
+        .map_or(0, i64::cast_unsigned)
+}
+
+/// Resolve an `AppId`/`UserId` column through the id map
+fn resolve(record: &EseRecord, name: &str, id_map: &HashMap<i64, String>) -> Option<String> {
+    let id = record.get(name).and_then(EseValue::as_i64)?;
+    Some(id_map.get(&id).cloned().unwrap_or_else(|| format!("#{id}")))
+}
+
+/// Decode application resource and network usage from a SRUM database
+///
+/// # Errors
+///
+/// Returns error if a SRUM table is corrupted
+pub fn parse_srum(db: &EseDatabase) -> Result<SrumData> {
+    let id_map = read_id_map(db)?;
+    let mut data = SrumData {
+        unreadable_names: id_map.unreadable,
+        ..SrumData::default()
+    };
+    let id_map = id_map.names;
+
+    if let Some(table) = db.table(APP_RESOURCE_USAGE_TABLE) {
+        for record in db.records(table)? {
+            data.app_usage.push(SrumAppUsage {
+                timestamp: record.get("TimeStamp").and_then(EseValue::as_system_time),
+                app: resolve(&record, "AppId", &id_map).unwrap_or_default(),
+                user: resolve(&record, "UserId", &id_map),
+                foreground_cycle_time: counter(&record, "ForegroundCycleTime"),
+                background_cycle_time: counter(&record, "BackgroundCycleTime"),
+                face_time: counter(&record, "FaceTime"),
+                foreground_bytes_read: counter(&record, "ForegroundBytesRead"),
+                foreground_bytes_written: counter(&record, "ForegroundBytesWritten"),
+                background_bytes_read: counter(&record, "BackgroundBytesRead"),
+                background_bytes_written: counter(&record, "BackgroundBytesWritten"),
+            });
+        }
+    }
+
+    if let Some(table) = db.table(NETWORK_USAGE_TABLE) {
+        for record in db.records(table)? {
+            data.network_usage.push(SrumNetworkUsage {
+                timestamp: record.get("TimeStamp").and_then(EseValue::as_system_time),
+                app: resolve(&record, "AppId", &id_map).unwrap_or_default(),
+                user: resolve(&record, "UserId", &id_map),
+                interface_luid: counter(&record, "InterfaceLuid"),
+                bytes_sent: counter(&record, "BytesSent"),
+                bytes_received: counter(&record, "BytesRecvd"),
+            });
+        }
+    }
+
+    Ok(data)
+}
+
+/// Read and decode a `SRUDB.dat` file
+///
+/// The live database is held open by DPS, so this usually needs a copy
+/// (for example from a shadow copy or a triage collection); see
+/// [`crate::vss::read_locked_file`].
+///
+/// # Errors
+///
+/// Returns error if the file cannot be read or decoded
+pub fn read_srum(path: &Path) -> Result<SrumData> {
+    let db =
+        EseDatabase::open(path).map_err(|e| RecentEnablerError::SrumReadFailed(e.to_string()))?;
+    parse_srum(&db).map_err(|e| RecentEnablerError::SrumReadFailed(e.to_string()))
+}
+
+/// Decode the raw bytes of a `SRUDB.dat` file
+///
+/// # Errors
+///
+/// Returns error if the data is not a readable SRUM database
+pub fn decode_srum(data: Vec<u8>) -> Result<SrumData> {
+    let db = EseDatabase::from_bytes(data)
+        .map_err(|e| RecentEnablerError::SrumReadFailed(e.to_string()))?;
+    parse_srum(&db).map_err(|e| RecentEnablerError::SrumReadFailed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ese::test_support::EseBuilder;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain(Some(0))
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    fn id_row(id_type: u8, index: i32, blob: Vec<u8>) -> Vec<(u32, Vec<u8>)> {
+        vec![
+            (1, vec![id_type]),
+            (2, index.to_le_bytes().to_vec()),
+            (256, blob),
+        ]
+    }
+
+    /// Large-page copy of [`build_srum`], read by `tests/portable.rs`
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/srum/SRUDB.dat");
+
+    /// A SRUM database with one app, one user and both usage tables
+    ///
+    /// With large pages the app name is 7-bit compressed, as in real databases.
+    fn build_srum(page_size: usize) -> Vec<u8> {
+        let mut builder = EseBuilder::with_page_size(page_size);
+        let app = r"\device\harddiskvolume3\tools\nc.exe";
+        let app_blob = if page_size >= 16384 {
+            builder.compressed_text(app)
+        } else {
+            builder.tagged(&utf16(app))
+        };
+
+        // S-1-5-21-1-2-3-1001
+        let mut sid = vec![1, 5, 0, 0, 0, 0, 0, 5];
+        for sub in [21u32, 1, 2, 3, 1001] {
+            sid.extend_from_slice(&sub.to_le_bytes());
+        }
+        builder.table(
+            ID_MAP_TABLE,
+            &[(1, "IdType", 2), (2, "IdIndex", 4), (256, "IdBlob", 11)],
+            &[id_row(0, 10, app_blob), id_row(3, 20, builder.tagged(&sid))],
+        );
+
+        let timestamp = 44_197.5f64.to_le_bytes().to_vec();
+        builder.table(
+            APP_RESOURCE_USAGE_TABLE,
+            &[
+                (1, "AutoIncId", 4),
+                (2, "TimeStamp", 8),
+                (3, "AppId", 4),
+                (4, "UserId", 4),
+                (5, "ForegroundCycleTime", 15),
+                (6, "BackgroundCycleTime", 15),
+                (7, "FaceTime", 15),
+            ],
+            &[vec![
+                (1, 1i32.to_le_bytes().to_vec()),
+                (2, timestamp.clone()),
+                (3, 10i32.to_le_bytes().to_vec()),
+                (4, 20i32.to_le_bytes().to_vec()),
+                (5, 5000i64.to_le_bytes().to_vec()),
+                (7, 60i64.to_le_bytes().to_vec()),
+            ]],
+        );
+        builder.table(
+            NETWORK_USAGE_TABLE,
+            &[
+                (1, "AutoIncId", 4),
+                (2, "TimeStamp", 8),
+                (3, "AppId", 4),
+                (4, "UserId", 4),
+                (5, "InterfaceLuid", 15),
+                (6, "BytesSent", 15),
+                (7, "BytesRecvd", 15),
+            ],
+            &[
+                vec![
+                    (1, 1i32.to_le_bytes().to_vec()),
+                    (2, timestamp.clone()),
+                    (3, 10i32.to_le_bytes().to_vec()),
+                    (4, 20i32.to_le_bytes().to_vec()),
+                    (6, 1_048_576i64.to_le_bytes().to_vec()),
+                    (7, 2048i64.to_le_bytes().to_vec()),
+                ],
+                vec![
+                    (1, 2i32.to_le_bytes().to_vec()),
+                    (2, timestamp.clone()),
+                    (3, 99i32.to_le_bytes().to_vec()),
+                ],
+                vec![
+                    (1, 3i32.to_le_bytes().to_vec()),
+                    (2, timestamp),
+                    (3, 10i32.to_le_bytes().to_vec()),
+                ],
+            ],
+        );
+
+        builder.finish()
+    }
+
+    #[test]
+    fn test_parse_srum() {
+        let data = decode_srum(build_srum(4096)).unwrap();
+
+        assert_eq!(data.app_usage.len(), 1);
+        let app = &data.app_usage[0];
+        assert_eq!(app.app, r"\device\harddiskvolume3\tools\nc.exe");
+        assert_eq!(app.user.as_deref(), Some("S-1-5-21-1-2-3-1001"));
+        assert_eq!(app.foreground_cycle_time, 5000);
+        assert_eq!(app.background_cycle_time, 0);
+        assert_eq!(app.face_time, 60);
+
+        assert_eq!(data.network_usage.len(), 3);
+        let net = &data.network_usage[0];
+        assert_eq!(net.bytes_sent, 1_048_576);
+        assert_eq!(net.bytes_received, 2048);
+        assert_eq!(data.network_usage[1].app, "#99");
+        assert!(data.network_usage[1].user.is_none());
+
+        let secs = data
+            .newest_time()
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(secs, 1_609_502_400);
+    }
+
+    #[test]
+    fn test_srudb_fixture() {
+        let fixture = std::fs::read(FIXTURE).unwrap();
+        assert!(
+            fixture == build_srum(32768),
+            "regenerate with `cargo test write_srudb_fixture -- --ignored`"
+        );
+    }
+
+    #[test]
+    #[ignore = "rewrites tests/fixtures/srum/SRUDB.dat"]
+    fn write_srudb_fixture() {
+        std::fs::write(FIXTURE, build_srum(32768)).unwrap();
+    }
+
+    #[test]
+    fn test_zero_period() {
+        let parameters = SrumParameters {
+            tier1_period: Some(60),
+            tier2_period: Some(0),
+        };
+        assert!(parameters.has_zero_period());
+        assert!(!SrumParameters::default().has_zero_period());
+    }
+}
@@ -0,0 +1,110 @@
+use super::*;
+
+use crate::{
+    sysmain::{self, ServiceStatus, StartupType},
+    utils,
+};
+use std::path::PathBuf;
+use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_SET_VALUE};
+use winreg::RegKey;
+
+/// Diagnostic Policy Service, which hosts the SRUM provider
+const DPS_SERVICE_NAME: &str = "DPS";
+
+const SRUM_PARAMETERS_PATH: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\SRUM\Parameters";
+
+/// Flush intervals; a zero override stops the corresponding tier from being written
+const PERIOD_VALUES: &[&str] = &["Tier1Period", "Tier2Period"];
+
+/// Get the path to `SRUDB.dat`
+///
+/// # Errors
+///
+/// Returns error if SystemRoot/windir environment variables are not set
+pub fn get_srum_path() -> Result<PathBuf> {
+    let windows_dir = std::env::var("SystemRoot")
+        .or_else(|_| std::env::var("windir"))
+        .map_err(|e| {
+            RecentEnablerError::WindowsPathNotFound(format!("SystemRoot/windir not found: {e}"))
+        })?;
+    Ok(PathBuf::from(windows_dir)
+        .join("System32")
+        .join("sru")
+        .join("SRUDB.dat"))
+}
+
+/// Get Diagnostic Policy Service status
+///
+/// # Errors
+///
+/// Returns error if service cannot be queried
+pub fn get_dps_status() -> Result<ServiceStatus> {
+    sysmain::get_service_status(DPS_SERVICE_NAME)
+}
+
+/// Get Diagnostic Policy Service startup type
+///
+/// # Errors
+///
+/// Returns error if service configuration cannot be queried
+pub fn get_dps_startup_type() -> Result<StartupType> {
+    sysmain::get_service_startup_type(DPS_SERVICE_NAME)
+}
+
+/// Read the flush period overrides from `SRUM\Parameters`
+#[must_use]
+pub fn get_srum_parameters() -> SrumParameters {
+    SrumParameters {
+        tier1_period: utils::read_reg_dword(
+            HKEY_LOCAL_MACHINE,
+            SRUM_PARAMETERS_PATH,
+            PERIOD_VALUES[0],
+        ),
+        tier2_period: utils::read_reg_dword(
+            HKEY_LOCAL_MACHINE,
+            SRUM_PARAMETERS_PATH,
+            PERIOD_VALUES[1],
+        ),
+    }
+}
+
+/// Check if SRUM collection is disabled
+///
+/// SRUM is considered disabled when DPS is not running, is not set to start
+/// automatically, or a flush period is overridden with zero.
+///
+/// # Errors
+///
+/// Returns error if the service cannot be queried
+pub fn is_srum_disabled() -> Result<bool> {
+    let status = get_dps_status()?;
+    let startup = get_dps_startup_type()?;
+    Ok(status != ServiceStatus::Running
+        || startup != StartupType::Automatic
+        || get_srum_parameters().has_zero_period())
+}
+
+/// Start DPS automatically and remove zero flush period overrides
+///
+/// # Errors
+///
+/// Returns error if the service or `SRUM\Parameters` cannot be changed
+pub fn enable_srum() -> Result {
+    sysmain::enable_service(DPS_SERVICE_NAME, RecentEnablerError::SrumEnableFailed)?;
+
+    let parameters = match RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(SRUM_PARAMETERS_PATH, KEY_SET_VALUE)
+    {
+        Ok(key) => key,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(RecentEnablerError::SrumEnableFailed(e.to_string())),
+    };
+    for name in PERIOD_VALUES {
+        if utils::read_reg_dword(HKEY_LOCAL_MACHINE, SRUM_PARAMETERS_PATH, name) == Some(0) {
+            parameters
+                .delete_value(name)
+                .map_err(|e| RecentEnablerError::SrumEnableFailed(format!("{name}: {e}")))?;
+        }
+    }
+    Ok(())
+}
@@ -22,8 +22,95 @@ where
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentStatus {
     pub path: String,
+    /// Where `path` was resolved from (User Shell Folders, Shell Folders, APPDATA)
+    pub path_source: String,
+    /// `path` is on a UNC share or mapped network drive
+    pub is_network: bool,
     pub is_disabled: bool,
     pub files_count: usize,
+    /// Jump list files in `AutomaticDestinations` and `CustomDestinations`
+    pub jumplist_count: usize,
+    pub total_size: u64,
+    /// Files or folders that couldn't be read while counting
+    pub unreadable_count: usize,
+    /// `path: reason` of the first few of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unreadable_examples: Vec<String>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_time: Option<SystemTime>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+
+    /// Office File MRU entries, most recently opened first
+    pub office_mru: Vec<OfficeMruStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub office_mru_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeMruStatus {
+    pub version: String,
+    pub app: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+
+    pub path: String,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_opened: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitiesStatus {
+    pub is_disabled: bool,
+    pub upload_enabled: bool,
+    pub databases_count: usize,
+    pub entries_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_app: Option<String>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_time: Option<SystemTime>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activities_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAssistStatus {
+    pub is_disabled: bool,
+    pub entries_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_program: Option<String>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_time: Option<SystemTime>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellBagsStatus {
+    pub path: String,
+    pub is_disabled: bool,
+    pub entries_count: usize,
 
     #[serde(serialize_with = "serialize_system_time")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -41,6 +128,12 @@ pub struct SysMainStatus {
     pub startup_type: String,
     pub prefetch_path: String,
     pub prefetch_count: usize,
+    /// `Layout.ini` and `*.db` files next to the traces
+    pub prefetch_other_count: usize,
+    pub prefetch_total_size: u64,
+    pub prefetch_unreadable_count: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefetch_unreadable_examples: Vec<String>,
 
     #[serde(serialize_with = "serialize_system_time")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,6 +145,244 @@ pub struct SysMainStatus {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefetch_error: Option<String>,
+
+    /// Number of ShimCache (AppCompatCache) entries
+    pub shimcache_count: usize,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shimcache_newest_time: Option<SystemTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shimcache_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastAccessStatus {
+    pub is_disabled: bool,
+    pub mode: String,
+    pub system_managed: bool,
+
+    /// Raw `NtfsDisableLastAccessUpdate` value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+
+    pub reboot_required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmcacheStatus {
+    pub path: String,
+    pub files_count: usize,
+    pub applications_count: usize,
+    /// Inventory entries left out because their values couldn't be read
+    pub skipped_entries: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_entry: Option<String>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+
+    /// Creation time of the shadow copy read because the live hive was locked
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<SystemTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amcache_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SrumStatus {
+    pub path: String,
+    pub is_disabled: bool,
+    pub dps_running: bool,
+    pub dps_auto: bool,
+    pub startup_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier1_period: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tier2_period: Option<u32>,
+
+    pub app_usage_count: usize,
+    pub network_usage_count: usize,
+    /// Application or user names that couldn't be decoded and show as `#id`
+    pub unreadable_names: usize,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+
+    /// Creation time of the shadow copy read because the live database was locked
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_time: Option<SystemTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub srum_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BamStatus {
+    pub is_disabled: bool,
+    pub users_count: usize,
+    pub entries_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_executable: Option<String>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_time: Option<SystemTime>,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_time: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogChannelStatus {
+    pub name: String,
+    pub exists: bool,
+    pub enabled: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u32>,
+
+    pub target_size: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention: Option<u32>,
+
+    pub needs_change: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogStatus {
+    pub channels: Vec<EventLogChannelStatus>,
+    pub needs_change_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerShellSettingStatus {
+    pub enabled: bool,
+    /// Where the effective value comes from (HKLM, HKCU, inherited, not configured)
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerShellEditionStatus {
+    pub edition: String,
+    pub script_block_logging: PowerShellSettingStatus,
+    pub module_logging: PowerShellSettingStatus,
+    pub module_names: Vec<String>,
+    pub transcription: PowerShellSettingStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_directory: Option<String>,
+
+    pub invocation_header: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerShellLoggingStatus {
+    pub is_disabled: bool,
+    pub windows_powershell: PowerShellEditionStatus,
+    pub powershell_core: PowerShellEditionStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsnVolumeStatus {
+    pub volume: String,
+    pub is_active: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_id: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_usn: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_usn: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocation_delta: Option<u64>,
+
+    pub needs_change: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsnJournalStatus {
+    /// Every NTFS volume has an active journal of at least `target_size`
+    pub is_enabled: bool,
+    pub target_size: u64,
+    pub volumes: Vec<UsnVolumeStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditSubcategoryStatus {
+    pub name: String,
+    pub guid: String,
+    pub success: bool,
+    pub failure: bool,
+    pub target_success: bool,
+    pub target_failure: bool,
+    pub needs_change: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPolicyStatus {
+    /// `ProcessCreationIncludeCmdLine_Enabled` is set
+    pub cmdline_enabled: bool,
+    pub subcategories: Vec<AuditSubcategoryStatus>,
+    pub needs_change_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowCopySnapshotStatus {
+    pub id: String,
+    /// Drive letter of the originating volume, or its volume GUID path
+    pub volume: String,
+    pub device_object: String,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<SystemTime>,
+
+    pub recent_files_count: usize,
+    pub jumplist_count: usize,
+    pub prefetch_count: usize,
+    /// Prefetch files whose run history could be parsed
+    pub prefetch_parsed_count: usize,
+
+    #[serde(serialize_with = "serialize_system_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefetch_newest_run: Option<SystemTime>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefetch_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowCopyStatus {
+    /// Oldest snapshot first
+    pub snapshots: Vec<ShadowCopySnapshotStatus>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
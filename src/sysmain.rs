@@ -2,24 +2,25 @@ use crate::{
     error::{RecentEnablerError, Result},
     utils,
 };
-use std::path::PathBuf;
-use windows::core::PCWSTR;
-use windows::Win32::System::Services::{
-    ChangeServiceConfigW, CloseServiceHandle, OpenSCManagerW, OpenServiceW, QueryServiceConfigW,
-    QueryServiceStatus, StartServiceW, ENUM_SERVICE_TYPE, QUERY_SERVICE_CONFIGW, SC_HANDLE,
-    SC_MANAGER_ALL_ACCESS, SC_MANAGER_CONNECT, SERVICE_AUTO_START, SERVICE_CHANGE_CONFIG,
-    SERVICE_DEMAND_START, SERVICE_DISABLED, SERVICE_ERROR, SERVICE_NO_CHANGE, SERVICE_PAUSED,
-    SERVICE_QUERY_CONFIG, SERVICE_QUERY_STATUS, SERVICE_RUNNING, SERVICE_START, SERVICE_STATUS,
-    SERVICE_STOPPED,
-};
+use std::path::Path;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
 
-const SYSMAIN_SERVICE_NAME: &str = "SysMain";
-const ERROR_SERVICE_ALREADY_RUNNING: u32 = 1056;
+/// Prefetch trace files plus the boot layout and SuperFetch/ReadyBoot databases
+const PREFETCH_PATTERNS: &[&str] = &["*.pf", "Layout.ini", "*.db"];
 
 pub struct PrefetchInfo {
     pub pf_count: usize,
+    /// `Layout.ini` and `*.db` files
+    pub other_count: usize,
+    pub total_size: u64,
+    /// Modification times of the `.pf` files
     pub oldest_time: Option<std::time::SystemTime>,
     pub newest_time: Option<std::time::SystemTime>,
+    pub unreadable: Vec<utils::UnreadableEntry>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,170 +54,32 @@ impl StartupType {
 
 // === Path and folder operations ===
 
-/// Get the path to the Prefetch folder
-///
-/// # Errors
+/// Get statistics about a Prefetch folder at `prefetch_path`
 ///
-/// Returns error if SystemRoot/windir environment variables are not set
-pub fn get_prefetch_folder() -> Result<PathBuf> {
-    let windows_dir = std::env::var("SystemRoot")
-        .or_else(|_| std::env::var("windir"))
-        .map_err(|e| {
-            RecentEnablerError::WindowsPathNotFound(format!("SystemRoot/windir not found: {e}"))
-        })?;
-    Ok(PathBuf::from(windows_dir).join("Prefetch"))
-}
-
-/// Get statistics about files in Prefetch folder
+/// Used for copies of the folder, such as inside a shadow copy.
 ///
 /// # Errors
 ///
-/// Returns error if folder doesn't exist or cannot be read
-pub fn get_prefetch_info() -> Result<PrefetchInfo> {
-    let prefetch_path = get_prefetch_folder()?;
-    let stats = utils::get_directory_stats(&prefetch_path, "pf")
-        .map_err(|e| RecentEnablerError::PrefetchInfoFailed(e.to_string()))?;
+/// Returns error if the folder exists but cannot be read
+pub fn get_prefetch_info_at(prefetch_path: &Path) -> Result<PrefetchInfo> {
+    let walker = utils::walk_directory(
+        prefetch_path,
+        &utils::ScanOptions::new(PREFETCH_PATTERNS, 1),
+    )
+    .map_err(|e| RecentEnablerError::PrefetchInfoFailed(e.to_string()))?;
 
+    let stats = utils::ExtensionStats::collect(walker, "pf");
     Ok(PrefetchInfo {
-        pf_count: stats.count,
-        oldest_time: stats.oldest,
-        newest_time: stats.newest,
+        pf_count: stats.matching.count,
+        other_count: stats.other_count(),
+        total_size: stats.all.total_size,
+        oldest_time: stats.matching.modified.oldest,
+        newest_time: stats.matching.modified.newest,
+        unreadable: stats.all.unreadable,
     })
 }
 
-// === Service Control Manager operations ===
-
-struct ServiceHandle(SC_HANDLE);
-
-impl Drop for ServiceHandle {
-    fn drop(&mut self) {
-        if !self.0.is_invalid() {
-            unsafe {
-                let _ = CloseServiceHandle(self.0);
-            }
-        }
-    }
-}
-
-fn with_service<F, R>(access: u32, service_access: u32, f: F) -> Result<R>
-where
-    F: FnOnce(SC_HANDLE) -> Result<R>,
-{
-    unsafe {
-        let scm = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), access).map_err(|e| {
-            RecentEnablerError::ServiceManagerOpenFailed(format!("OpenSCManagerW failed: {e}"))
-        })?;
-        let scm_handle = ServiceHandle(scm);
-
-        let service_name: Vec<u16> = SYSMAIN_SERVICE_NAME.encode_utf16().chain(Some(0)).collect();
-        let service = OpenServiceW(scm_handle.0, PCWSTR(service_name.as_ptr()), service_access)
-            .map_err(|e| {
-                RecentEnablerError::SysMainServiceNotFound(format!("OpenServiceW failed: {e}"))
-            })?;
-        let service_handle = ServiceHandle(service);
-
-        f(service_handle.0)
-    }
-}
-
-/// Get `SysMain` service status
-///
-/// # Errors
-///
-/// Returns error if service cannot be queried
-pub fn get_sysmain_status() -> Result<ServiceStatus> {
-    let res = with_service(SC_MANAGER_CONNECT, SERVICE_QUERY_STATUS, |service| unsafe {
-        let mut status = SERVICE_STATUS::default();
-        QueryServiceStatus(service, &raw mut status).map_err(|e| {
-            RecentEnablerError::SysMainStatusQueryFailed(format!("QueryServiceStatus failed: {e}"))
-        })?;
-
-        Ok(match status.dwCurrentState {
-            SERVICE_RUNNING => ServiceStatus::Running,
-            SERVICE_STOPPED => ServiceStatus::Stopped,
-            SERVICE_PAUSED => ServiceStatus::Paused,
-            _ => ServiceStatus::Unknown,
-        })
-    });
-
-    res.or(Ok(ServiceStatus::NotFound))
-}
-
-/// Get `SysMain` service startup type
-///
-/// # Errors
-///
-/// Returns error if service configuration cannot be queried
-pub fn get_sysmain_startup_type() -> Result<StartupType> {
-    let res = with_service(SC_MANAGER_CONNECT, SERVICE_QUERY_CONFIG, |service| unsafe {
-        let mut bytes_needed = 0u32;
-        let _ = QueryServiceConfigW(service, None, 0, &raw mut bytes_needed);
-
-        let mut buffer: Vec<u8> = vec![0; bytes_needed as usize];
-        #[allow(clippy::cast_ptr_alignment)]
-        let config = buffer.as_mut_ptr().cast::<QUERY_SERVICE_CONFIGW>();
-
-        QueryServiceConfigW(service, Some(config), bytes_needed, &raw mut bytes_needed).map_err(
-            |e| {
-                RecentEnablerError::SysMainConfigQueryFailed(format!(
-                    "QueryServiceConfigW failed: {e}"
-                ))
-            },
-        )?;
-
-        Ok(match (*config).dwStartType {
-            SERVICE_AUTO_START => StartupType::Automatic,
-            SERVICE_DEMAND_START => StartupType::Manual,
-            SERVICE_DISABLED => StartupType::Disabled,
-            _ => StartupType::Unknown,
-        })
-    });
-
-    res.or(Ok(StartupType::Unknown))
-}
-
-/// Enable and start `SysMain` service
-///
-/// # Errors
-///
-/// Returns error if service cannot be configured or started
-pub fn enable_sysmain() -> Result {
-    with_service(
-        SC_MANAGER_ALL_ACCESS,
-        SERVICE_CHANGE_CONFIG | SERVICE_START,
-        |service| unsafe {
-            ChangeServiceConfigW(
-                service,
-                ENUM_SERVICE_TYPE(SERVICE_NO_CHANGE),
-                SERVICE_AUTO_START,
-                SERVICE_ERROR(SERVICE_NO_CHANGE),
-                PCWSTR::null(),
-                PCWSTR::null(),
-                None,
-                PCWSTR::null(),
-                PCWSTR::null(),
-                PCWSTR::null(),
-                PCWSTR::null(),
-            )
-            .map_err(|e| {
-                RecentEnablerError::SysMainEnableFailed(format!("ChangeServiceConfigW failed: {e}"))
-            })?;
-
-            let start_result = StartServiceW(service, None);
-            if start_result.is_err() {
-                let err = windows::core::Error::from_thread();
-                if err.code().0.cast_unsigned() != ERROR_SERVICE_ALREADY_RUNNING {
-                    return Err(RecentEnablerError::SysMainEnableFailed(format!(
-                        "StartServiceW failed: {err}"
-                    )));
-                }
-            }
-            Ok(())
-        },
-    )
-}
-
-#[cfg(test)]
+#[cfg(all(test, feature = "live"))]
 mod tests {
     use super::*;
 
@@ -0,0 +1,240 @@
+use super::*;
+
+use std::path::PathBuf;
+use windows::core::PCWSTR;
+use windows::Win32::System::Services::{
+    ChangeServiceConfigW, CloseServiceHandle, OpenSCManagerW, OpenServiceW, QueryServiceConfigW,
+    QueryServiceStatus, StartServiceW, ENUM_SERVICE_TYPE, QUERY_SERVICE_CONFIGW, SC_HANDLE,
+    SC_MANAGER_ALL_ACCESS, SC_MANAGER_CONNECT, SERVICE_AUTO_START, SERVICE_CHANGE_CONFIG,
+    SERVICE_DEMAND_START, SERVICE_DISABLED, SERVICE_ERROR, SERVICE_NO_CHANGE, SERVICE_PAUSED,
+    SERVICE_QUERY_CONFIG, SERVICE_QUERY_STATUS, SERVICE_RUNNING, SERVICE_START, SERVICE_STATUS,
+    SERVICE_STOPPED,
+};
+
+const SYSMAIN_SERVICE_NAME: &str = "SysMain";
+
+const ERROR_SERVICE_ALREADY_RUNNING: u32 = 1056;
+
+/// Get the path to the Prefetch folder
+///
+/// # Errors
+///
+/// Returns error if SystemRoot/windir environment variables are not set
+pub fn get_prefetch_folder() -> Result<PathBuf> {
+    let windows_dir = std::env::var("SystemRoot")
+        .or_else(|_| std::env::var("windir"))
+        .map_err(|e| {
+            RecentEnablerError::WindowsPathNotFound(format!("SystemRoot/windir not found: {e}"))
+        })?;
+    Ok(PathBuf::from(windows_dir).join("Prefetch"))
+}
+
+/// Get statistics about files in Prefetch folder
+///
+/// # Errors
+///
+/// Returns error if folder doesn't exist or cannot be read
+pub fn get_prefetch_info() -> Result<PrefetchInfo> {
+    get_prefetch_info_at(&get_prefetch_folder()?)
+}
+
+// === Service Control Manager operations ===
+
+struct ServiceHandle(SC_HANDLE);
+
+impl Drop for ServiceHandle {
+    fn drop(&mut self) {
+        if !self.0.is_invalid() {
+            unsafe {
+                let _ = CloseServiceHandle(self.0);
+            }
+        }
+    }
+}
+
+fn with_service<F, R>(
+    name: &str,
+    access: u32,
+    service_access: u32,
+    on_open_error: impl FnOnce(String) -> RecentEnablerError,
+    f: F,
+) -> Result<R>
+where
+    F: FnOnce(SC_HANDLE) -> Result<R>,
+{
+    unsafe {
+        let scm = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), access).map_err(|e| {
+            RecentEnablerError::ServiceManagerOpenFailed(format!("OpenSCManagerW failed: {e}"))
+        })?;
+        let scm_handle = ServiceHandle(scm);
+
+        let service_name: Vec<u16> = name.encode_utf16().chain(Some(0)).collect();
+        let service = OpenServiceW(scm_handle.0, PCWSTR(service_name.as_ptr()), service_access)
+            .map_err(|e| on_open_error(format!("OpenServiceW failed: {e}")))?;
+        let service_handle = ServiceHandle(service);
+
+        f(service_handle.0)
+    }
+}
+
+// SysMain keeps its own error variants; other services name themselves
+
+fn service_not_found(name: &str, e: String) -> RecentEnablerError {
+    if name == SYSMAIN_SERVICE_NAME {
+        RecentEnablerError::SysMainServiceNotFound(e)
+    } else {
+        RecentEnablerError::ServiceNotFound(name.to_string(), e)
+    }
+}
+
+fn status_query_failed(name: &str, e: String) -> RecentEnablerError {
+    if name == SYSMAIN_SERVICE_NAME {
+        RecentEnablerError::SysMainStatusQueryFailed(e)
+    } else {
+        RecentEnablerError::ServiceStatusQueryFailed(name.to_string(), e)
+    }
+}
+
+fn config_query_failed(name: &str, e: String) -> RecentEnablerError {
+    if name == SYSMAIN_SERVICE_NAME {
+        RecentEnablerError::SysMainConfigQueryFailed(e)
+    } else {
+        RecentEnablerError::ServiceConfigQueryFailed(name.to_string(), e)
+    }
+}
+
+/// Get the status of a service by name
+///
+/// # Errors
+///
+/// Returns error if service cannot be queried
+pub(crate) fn get_service_status(name: &str) -> Result<ServiceStatus> {
+    let res = with_service(
+        name,
+        SC_MANAGER_CONNECT,
+        SERVICE_QUERY_STATUS,
+        |e| service_not_found(name, e),
+        |service| unsafe {
+            let mut status = SERVICE_STATUS::default();
+            QueryServiceStatus(service, &raw mut status).map_err(|e| {
+                status_query_failed(name, format!("QueryServiceStatus failed: {e}"))
+            })?;
+
+            Ok(match status.dwCurrentState {
+                SERVICE_RUNNING => ServiceStatus::Running,
+                SERVICE_STOPPED => ServiceStatus::Stopped,
+                SERVICE_PAUSED => ServiceStatus::Paused,
+                _ => ServiceStatus::Unknown,
+            })
+        },
+    );
+
+    res.or(Ok(ServiceStatus::NotFound))
+}
+
+/// Get `SysMain` service status
+///
+/// # Errors
+///
+/// Returns error if service cannot be queried
+pub fn get_sysmain_status() -> Result<ServiceStatus> {
+    get_service_status(SYSMAIN_SERVICE_NAME)
+}
+
+/// Get the startup type of a service by name
+///
+/// # Errors
+///
+/// Returns error if service configuration cannot be queried
+pub(crate) fn get_service_startup_type(name: &str) -> Result<StartupType> {
+    let res = with_service(
+        name,
+        SC_MANAGER_CONNECT,
+        SERVICE_QUERY_CONFIG,
+        |e| service_not_found(name, e),
+        |service| unsafe {
+            let mut bytes_needed = 0u32;
+            let _ = QueryServiceConfigW(service, None, 0, &raw mut bytes_needed);
+
+            let mut buffer: Vec<u8> = vec![0; bytes_needed as usize];
+            #[allow(clippy::cast_ptr_alignment)]
+            let config = buffer.as_mut_ptr().cast::<QUERY_SERVICE_CONFIGW>();
+
+            QueryServiceConfigW(service, Some(config), bytes_needed, &raw mut bytes_needed)
+                .map_err(|e| {
+                    config_query_failed(name, format!("QueryServiceConfigW failed: {e}"))
+                })?;
+
+            Ok(match (*config).dwStartType {
+                SERVICE_AUTO_START => StartupType::Automatic,
+                SERVICE_DEMAND_START => StartupType::Manual,
+                SERVICE_DISABLED => StartupType::Disabled,
+                _ => StartupType::Unknown,
+            })
+        },
+    );
+
+    res.or(Ok(StartupType::Unknown))
+}
+
+/// Get `SysMain` service startup type
+///
+/// # Errors
+///
+/// Returns error if service configuration cannot be queried
+pub fn get_sysmain_startup_type() -> Result<StartupType> {
+    get_service_startup_type(SYSMAIN_SERVICE_NAME)
+}
+
+/// Set a service to automatic start and start it
+///
+/// `on_error` builds the error reported for any failure.
+///
+/// # Errors
+///
+/// Returns error if service cannot be configured or started
+pub(crate) fn enable_service(name: &str, on_error: fn(String) -> RecentEnablerError) -> Result {
+    with_service(
+        name,
+        SC_MANAGER_ALL_ACCESS,
+        SERVICE_CHANGE_CONFIG | SERVICE_START,
+        on_error,
+        |service| unsafe {
+            ChangeServiceConfigW(
+                service,
+                ENUM_SERVICE_TYPE(SERVICE_NO_CHANGE),
+                SERVICE_AUTO_START,
+                SERVICE_ERROR(SERVICE_NO_CHANGE),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                None,
+                PCWSTR::null(),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                PCWSTR::null(),
+            )
+            .map_err(|e| on_error(format!("ChangeServiceConfigW failed: {e}")))?;
+
+            let start_result = StartServiceW(service, None);
+            if start_result.is_err() {
+                let err = windows::core::Error::from_thread();
+                if err.code().0.cast_unsigned() != ERROR_SERVICE_ALREADY_RUNNING {
+                    return Err(on_error(format!("StartServiceW failed: {err}")));
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Enable and start `SysMain` service
+///
+/// # Errors
+///
+/// Returns error if service cannot be configured or started
+pub fn enable_sysmain() -> Result {
+    enable_service(
+        SYSMAIN_SERVICE_NAME,
+        RecentEnablerError::SysMainEnableFailed,
+    )
+}
@@ -2,68 +2,96 @@ use crate::{
     error::{RecentEnablerError, Result},
     utils,
 };
-use std::process::Command;
-use winreg::enums::HKEY_LOCAL_MACHINE;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// Check if System Restore is enabled for C: drive
-///
-/// # Errors
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestorePoint {
+    pub sequence_number: u32,
+    pub description: String,
+    /// `RestorePointType` from `SystemRestore` (0 = application install, 12 = modify settings, ...)
+    pub restore_point_type: u32,
+    pub created: Option<SystemTime>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawRestorePoint {
+    sequence_number: u32,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    creation_time: String,
+    #[serde(default)]
+    restore_point_type: u32,
+}
+
+/// Parse a WMI `CIM_DATETIME` string (`yyyyMMddHHmmss.ffffff+MMM`)
 ///
-/// Returns error if registry cannot be read
-pub fn is_system_restore_enabled() -> Result<bool> {
-    let path = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\SystemRestore";
-    Ok(utils::read_reg_dword(HKEY_LOCAL_MACHINE, path, "RPSessionInterval").unwrap_or(0) == 1)
+/// The trailing sign and minutes give the offset from UTC.
+#[must_use]
+pub fn parse_wmi_datetime(value: &str) -> Option<SystemTime> {
+    let value = value.trim();
+    let datetime = chrono::NaiveDateTime::parse_from_str(value.get(..14)?, "%Y%m%d%H%M%S").ok()?;
+    let micros: u64 = value.get(15..21).and_then(|m| m.parse().ok()).unwrap_or(0);
+    let offset_minutes: i64 = value.get(21..).and_then(|o| o.parse().ok()).unwrap_or(0);
+
+    let secs = datetime.and_utc().timestamp() - offset_minutes * 60;
+    UNIX_EPOCH
+        .checked_add(Duration::from_secs(u64::try_from(secs).ok()?))?
+        .checked_add(Duration::from_micros(micros))
 }
 
-/// Enable System Restore on C: drive
+/// Parse `Get-ComputerRestorePoint | ConvertTo-Json` output
 ///
 /// # Errors
 ///
-/// Returns error if `PowerShell` command fails
-pub fn enable_system_restore() -> Result {
-    #[cfg(windows)]
-    use std::os::windows::process::CommandExt;
+/// Returns error if the output is not valid JSON
+pub fn parse_restore_points(json: &str) -> Result<Vec<RestorePoint>> {
+    let raw: Vec<RawRestorePoint> = utils::parse_json_list(json)
+        .map_err(|e| RecentEnablerError::RestorePointsReadFailed(e.to_string()))?;
 
-    #[cfg(windows)]
-    const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-    let mut cmd = Command::new("powershell");
-    cmd.args([
-        "-NoProfile",
-        "-WindowStyle",
-        "Hidden",
-        "-Command",
-        "Enable-ComputerRestore -Drive 'C:'",
-    ]);
-
-    #[cfg(windows)]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    Ok(raw
+        .into_iter()
+        .map(|point| RestorePoint {
+            sequence_number: point.sequence_number,
+            description: point.description,
+            restore_point_type: point.restore_point_type,
+            created: parse_wmi_datetime(&point.creation_time),
+        })
+        .collect())
+}
 
-    let output = cmd.output().map_err(|e| {
-        RecentEnablerError::SystemRestoreEnableFailed(format!(
-            "Failed to execute PowerShell command: {e}"
-        ))
-    })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let essential = stderr
-            .lines()
-            .find(|line| !line.trim().is_empty() && !line.contains("ProgressPreference"))
-            .unwrap_or_else(|| stderr.as_ref());
-        return Err(RecentEnablerError::SystemRestoreEnableFailed(
-            essential.to_string(),
-        ));
+    #[test]
+    fn test_parse_wmi_datetime() {
+        let time = parse_wmi_datetime("20230930163337.123456+180").unwrap();
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(elapsed.as_secs(), 1_696_080_817);
+        assert_eq!(elapsed.subsec_micros(), 123_456);
+        assert_eq!(parse_wmi_datetime("garbage"), None);
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_parse_restore_points() {
+        let single = r#"{"SequenceNumber":7,"Description":"Windows Update","CreationTime":"20230930133337.000000-000","RestorePointType":18}"#;
+        let points = parse_restore_points(single).unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].sequence_number, 7);
+        assert_eq!(points[0].description, "Windows Update");
+        assert_eq!(points[0].restore_point_type, 18);
+        assert!(points[0].created.is_some());
 
-/// Get System Restore status for C: drive
-///
-/// # Errors
-///
-/// Returns error if status cannot be queried
-pub fn get_system_restore_info() -> Result<bool> {
-    is_system_restore_enabled()
+        let many = format!("[{single},{single}]");
+        assert_eq!(parse_restore_points(&many).unwrap().len(), 2);
+        assert!(parse_restore_points("").unwrap().is_empty());
+        assert!(parse_restore_points("not json").is_err());
+    }
 }
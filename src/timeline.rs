@@ -0,0 +1,429 @@
+//! Super-timeline of every timestamped artifact the crate can read
+//!
+//! Events from all sources are normalized into [`TimelineEvent`] and can be
+//! exported as a mactime bodyfile, log2timeline CSV or Timesketch JSONL.
+
+use crate::error::{RecentEnablerError, Result};
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// What a timestamp means for the artifact it was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimestampKind {
+    Created,
+    Modified,
+    Accessed,
+    LastRun,
+    LastWritten,
+    LastOpened,
+    FirstVisited,
+    LastVisited,
+}
+
+impl TimestampKind {
+    /// Description in the wording used by plaso and Timesketch
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "Creation Time",
+            Self::Modified => "Content Modification Time",
+            Self::Accessed => "Last Access Time",
+            Self::LastRun => "Last Time Executed",
+            Self::LastWritten => "Last Written Time",
+            Self::LastOpened => "Last Opened Time",
+            Self::FirstVisited => "First Visited Time",
+            Self::LastVisited => "Last Visited Time",
+        }
+    }
+
+    /// MACB notation; times without a file system meaning map to the closest column
+    #[must_use]
+    pub const fn macb(self) -> &'static str {
+        match self {
+            Self::Created | Self::FirstVisited => "...B",
+            Self::Modified | Self::LastWritten => "M...",
+            Self::Accessed | Self::LastRun | Self::LastOpened | Self::LastVisited => ".A..",
+        }
+    }
+}
+
+/// Artifact an event was collected from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventSource {
+    Lnk,
+    Prefetch,
+    RestorePoint,
+    Registry,
+    UserAssist,
+    Bam,
+    ShellBags,
+    OfficeMru,
+    ShimCache,
+}
+
+impl EventSource {
+    /// Short log2timeline `source` column
+    #[must_use]
+    pub const fn short_name(self) -> &'static str {
+        match self {
+            Self::Lnk => "LNK",
+            Self::Prefetch => "PREFETCH",
+            Self::RestorePoint => "RESTORE",
+            Self::Registry
+            | Self::UserAssist
+            | Self::Bam
+            | Self::ShellBags
+            | Self::OfficeMru
+            | Self::ShimCache => "REG",
+        }
+    }
+
+    /// Long log2timeline `sourcetype` column
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lnk => "Windows Shortcut",
+            Self::Prefetch => "Windows Prefetch",
+            Self::RestorePoint => "System Restore Point",
+            Self::Registry => "Registry Key",
+            Self::UserAssist => "UserAssist",
+            Self::Bam => "Background Activity Moderator",
+            Self::ShellBags => "ShellBags",
+            Self::OfficeMru => "Office File MRU",
+            Self::ShimCache => "AppCompatCache",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEvent {
+    pub timestamp: SystemTime,
+    pub kind: TimestampKind,
+    pub source: EventSource,
+    pub description: String,
+    /// File or registry path the event refers to
+    pub path: Option<String>,
+    pub size: Option<u64>,
+}
+
+impl TimelineEvent {
+    #[must_use]
+    pub fn new(
+        timestamp: SystemTime,
+        kind: TimestampKind,
+        source: EventSource,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp,
+            kind,
+            source,
+            description: description.into(),
+            path: None,
+            size: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+/// A source that failed while collecting the timeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError {
+    pub source: EventSource,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    /// Events in chronological order
+    pub events: Vec<TimelineEvent>,
+    pub errors: Vec<SourceError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineFormat {
+    /// TSK 3.x bodyfile for `mactime`
+    Bodyfile,
+    /// log2timeline/plaso `l2tcsv`
+    L2tCsv,
+    /// One JSON object per line for Timesketch import
+    TimesketchJsonl,
+}
+
+impl TimelineFormat {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Bodyfile => "body",
+            Self::L2tCsv => "csv",
+            Self::TimesketchJsonl => "jsonl",
+        }
+    }
+}
+
+/// Write events as a mactime bodyfile
+///
+/// Each event gets its own line with only the column matching its MACB slot set.
+///
+/// # Errors
+///
+/// Returns error if writing fails
+pub fn write_bodyfile<W: Write>(events: &[TimelineEvent], writer: &mut W) -> std::io::Result<()> {
+    for event in events {
+        let seconds = DateTime::<Utc>::from(event.timestamp).timestamp();
+        let time_at = |slot: char| {
+            if event.kind.macb().contains(slot) {
+                seconds
+            } else {
+                0
+            }
+        };
+        let name = format!(
+            "[{}] {}",
+            event.source.as_str(),
+            event.path.as_deref().unwrap_or(&event.description)
+        )
+        .replace('|', "_");
+
+        writeln!(
+            writer,
+            "0|{name}|0|0|0|0|{}|{}|{}|{}|{}",
+            event.size.unwrap_or(0),
+            time_at('A'),
+            time_at('M'),
+            time_at('C'),
+            time_at('B'),
+        )?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Write events as log2timeline CSV with times in UTC
+///
+/// # Errors
+///
+/// Returns error if writing fails
+pub fn write_l2t_csv<W: Write>(
+    events: &[TimelineEvent],
+    host: &str,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "date,time,timezone,MACB,source,sourcetype,type,user,host,short,desc,version,filename,inode,notes,format,extra"
+    )?;
+    for event in events {
+        let datetime = DateTime::<Utc>::from(event.timestamp);
+        let desc = match &event.path {
+            Some(path) => format!("{} [{path}]", event.description),
+            None => event.description.clone(),
+        };
+        let fields = [
+            datetime.format("%m/%d/%Y").to_string(),
+            datetime.format("%H:%M:%S").to_string(),
+            "UTC".to_string(),
+            event.kind.macb().to_string(),
+            event.source.short_name().to_string(),
+            event.source.as_str().to_string(),
+            event.kind.as_str().to_string(),
+            "-".to_string(),
+            host.to_string(),
+            event.description.clone(),
+            desc,
+            "2".to_string(),
+            event.path.clone().unwrap_or_else(|| "-".to_string()),
+            "-".to_string(),
+            "-".to_string(),
+            "recent-enabler".to_string(),
+            event
+                .size
+                .map_or_else(|| "-".to_string(), |size| format!("size: {size}")),
+        ];
+        let line: Vec<_> = fields.iter().map(|field| csv_field(field)).collect();
+        writeln!(writer, "{}", line.join(","))?;
+    }
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct TimesketchRecord<'a> {
+    message: &'a str,
+    datetime: String,
+    /// Microseconds since the Unix epoch
+    timestamp: i64,
+    timestamp_desc: &'a str,
+    data_type: &'a str,
+    source_short: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+}
+
+/// Write events as Timesketch JSONL
+///
+/// # Errors
+///
+/// Returns error if writing fails
+pub fn write_timesketch_jsonl<W: Write>(
+    events: &[TimelineEvent],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    for event in events {
+        let datetime = DateTime::<Utc>::from(event.timestamp);
+        let record = TimesketchRecord {
+            message: &event.description,
+            datetime: datetime.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            timestamp: datetime.timestamp_micros(),
+            timestamp_desc: event.kind.as_str(),
+            data_type: event.source.as_str(),
+            source_short: event.source.short_name(),
+            path: event.path.as_deref(),
+            size: event.size,
+        };
+        serde_json::to_writer(&mut *writer, &record)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Write events to `writer` in `format`
+///
+/// # Errors
+///
+/// Returns error if writing fails
+pub fn write_timeline<W: Write>(
+    events: &[TimelineEvent],
+    format: TimelineFormat,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        TimelineFormat::Bodyfile => write_bodyfile(events, writer),
+        TimelineFormat::L2tCsv => {
+            let host = std::env::var("COMPUTERNAME").unwrap_or_else(|_| "-".to_string());
+            write_l2t_csv(events, &host, writer)
+        }
+        TimelineFormat::TimesketchJsonl => write_timesketch_jsonl(events, writer),
+    }
+}
+
+/// Write events to a file in `format`
+///
+/// # Errors
+///
+/// Returns error if the file cannot be created or written
+pub fn export_timeline(events: &[TimelineEvent], format: TimelineFormat, path: &Path) -> Result {
+    let export_error = |e: std::io::Error| {
+        RecentEnablerError::TimelineExportFailed(format!("{}: {e}", path.display()))
+    };
+
+    let file = std::fs::File::create(path).map_err(export_error)?;
+    let mut writer = std::io::BufWriter::new(file);
+    write_timeline(events, format, &mut writer).map_err(export_error)?;
+    writer.flush().map_err(export_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn sample_events() -> Vec<TimelineEvent> {
+        // 2023-09-30 13:33:37.003829 UTC
+        let time = UNIX_EPOCH + Duration::from_micros(1_696_080_817_003_829);
+        vec![
+            TimelineEvent::new(
+                time,
+                TimestampKind::LastRun,
+                EventSource::Prefetch,
+                "CMD.EXE executed (run count 42)",
+            )
+            .with_path(r"C:\Windows\Prefetch\CMD.EXE-0BD30981.pf")
+            .with_size(4096),
+            TimelineEvent::new(
+                time + Duration::from_secs(60),
+                TimestampKind::Created,
+                EventSource::RestorePoint,
+                "Restore point #7: Installed \"App\", v2 (type 0)",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_write_bodyfile() {
+        let mut output = Vec::new();
+        write_bodyfile(&sample_events(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            r"0|[Windows Prefetch] C:\Windows\Prefetch\CMD.EXE-0BD30981.pf|0|0|0|0|4096|1696080817|0|0|0"
+        );
+        assert_eq!(
+            lines[1],
+            "0|[System Restore Point] Restore point #7: Installed \"App\", v2 (type 0)|0|0|0|0|0|0|0|0|1696080877"
+        );
+    }
+
+    #[test]
+    fn test_write_l2t_csv() {
+        let mut output = Vec::new();
+        write_l2t_csv(&sample_events(), "WS01", &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("date,time,timezone,MACB,source,"));
+        assert!(lines[1].starts_with(
+            "09/30/2023,13:33:37,UTC,.A..,PREFETCH,Windows Prefetch,Last Time Executed,-,WS01,"
+        ));
+        assert!(lines[1].ends_with(",recent-enabler,size: 4096"));
+        assert!(lines[2].contains(r#","Restore point #7: Installed ""App"", v2 (type 0)","#));
+    }
+
+    #[test]
+    fn test_write_timesketch_jsonl() {
+        let mut output = Vec::new();
+        write_timesketch_jsonl(&sample_events(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["datetime"], "2023-09-30T13:33:37.003829Z");
+        assert_eq!(records[0]["timestamp"], 1_696_080_817_003_829_i64);
+        assert_eq!(records[0]["timestamp_desc"], "Last Time Executed");
+        assert_eq!(records[0]["size"], 4096);
+        assert!(records[1].get("path").is_none());
+    }
+}
@@ -0,0 +1,360 @@
+use super::*;
+
+use crate::{
+    bam, office_mru, prefetch, recent, shellbags, shimcache, sysmain, system_restore, userassist,
+    utils,
+};
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+use winreg::{RegKey, HKEY};
+
+const PREFETCH_PATTERNS: &[&str] = &["*.pf"];
+
+/// Keys whose last-write time (and that of their direct subkeys) marks user activity
+const REGISTRY_KEYS: &[(HKEY, &str, &str)] = &[
+    (
+        HKEY_CURRENT_USER,
+        "HKCU",
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\RecentDocs",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        "HKCU",
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\ComDlg32",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        "HKCU",
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\RunMRU",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        "HKCU",
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\TypedPaths",
+    ),
+    (
+        HKEY_CURRENT_USER,
+        "HKCU",
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\UserAssist",
+    ),
+    (
+        HKEY_LOCAL_MACHINE,
+        "HKLM",
+        r"SYSTEM\CurrentControlSet\Control\Session Manager\AppCompatCache",
+    ),
+    (
+        HKEY_LOCAL_MACHINE,
+        "HKLM",
+        r"SYSTEM\CurrentControlSet\Services\bam\State\UserSettings",
+    ),
+];
+
+/// Push an event for each known timestamp
+fn push_times(
+    events: &mut Vec<TimelineEvent>,
+    times: &[(Option<SystemTime>, TimestampKind)],
+    make: impl Fn(SystemTime, TimestampKind) -> TimelineEvent,
+) {
+    events.extend(
+        times
+            .iter()
+            .filter_map(|&(time, kind)| time.map(|time| make(time, kind))),
+    );
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+/// MAC times of shortcuts and jump lists in the Recent folder
+///
+/// # Errors
+///
+/// Returns error if the Recent folder cannot be resolved or opened
+pub fn collect_lnk_events() -> Result<Vec<TimelineEvent>> {
+    let folder = recent::get_recent_folder()?;
+    let walker = utils::walk_directory(
+        &folder,
+        &utils::ScanOptions::new(recent::RECENT_PATTERNS, 1),
+    )?;
+
+    let mut events = Vec::new();
+    for entry in walker.filter_map(std::result::Result::ok) {
+        let name = file_name(&entry.path);
+        let description = if name.to_ascii_lowercase().ends_with(".lnk") {
+            format!("Shortcut {name}")
+        } else {
+            format!("Jump list {name}")
+        };
+        push_times(
+            &mut events,
+            &[
+                (entry.created, TimestampKind::Created),
+                (entry.modified, TimestampKind::Modified),
+                (entry.accessed, TimestampKind::Accessed),
+            ],
+            |time, kind| {
+                TimelineEvent::new(time, kind, EventSource::Lnk, description.as_str())
+                    .with_path(entry.path.display().to_string())
+                    .with_size(entry.size)
+            },
+        );
+    }
+    Ok(events)
+}
+
+/// Run times recorded inside Prefetch files
+///
+/// Files that fail to parse are skipped; the folder itself must be readable.
+///
+/// # Errors
+///
+/// Returns error if the Prefetch folder cannot be resolved or opened
+pub fn collect_prefetch_events() -> Result<Vec<TimelineEvent>> {
+    let folder = sysmain::get_prefetch_folder()?;
+    let walker = utils::walk_directory(&folder, &utils::ScanOptions::new(PREFETCH_PATTERNS, 0))?;
+
+    let mut events = Vec::new();
+    for entry in walker.filter_map(std::result::Result::ok) {
+        let path = entry.path.display().to_string();
+        if let Some(created) = entry.created {
+            events.push(
+                TimelineEvent::new(
+                    created,
+                    TimestampKind::Created,
+                    EventSource::Prefetch,
+                    format!("Prefetch file {} created", file_name(&entry.path)),
+                )
+                .with_path(path.as_str())
+                .with_size(entry.size),
+            );
+        }
+
+        let Ok(file) = prefetch::read_prefetch(&entry.path) else {
+            continue;
+        };
+        for (i, &time) in file.last_run_times.iter().enumerate() {
+            let description = match (i, file.run_count) {
+                (0, Some(count)) => format!("{} executed (run count {count})", file.executable),
+                (0, None) => format!("{} executed", file.executable),
+                _ => format!("{} executed (previous run {i})", file.executable),
+            };
+            events.push(
+                TimelineEvent::new(
+                    time,
+                    TimestampKind::LastRun,
+                    EventSource::Prefetch,
+                    description,
+                )
+                .with_path(path.as_str())
+                .with_size(entry.size),
+            );
+        }
+    }
+    Ok(events)
+}
+
+/// Creation times of System Restore points
+///
+/// # Errors
+///
+/// Returns error if restore points cannot be listed
+pub fn collect_restore_point_events() -> Result<Vec<TimelineEvent>> {
+    Ok(system_restore::get_restore_points()?
+        .into_iter()
+        .filter_map(|point| {
+            Some(TimelineEvent::new(
+                point.created?,
+                TimestampKind::Created,
+                EventSource::RestorePoint,
+                format!(
+                    "Restore point #{}: {} (type {})",
+                    point.sequence_number, point.description, point.restore_point_type
+                ),
+            ))
+        })
+        .collect())
+}
+
+/// Last-write times of activity keys and their direct subkeys
+#[must_use]
+pub fn collect_registry_events() -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+    let mut push_key = |key: &RegKey, path: String| {
+        if let Some(time) = utils::reg_key_last_write(key) {
+            events.push(
+                TimelineEvent::new(
+                    time,
+                    TimestampKind::LastWritten,
+                    EventSource::Registry,
+                    format!("Key {path} written"),
+                )
+                .with_path(path),
+            );
+        }
+    };
+
+    for &(hkey, root, path) in REGISTRY_KEYS {
+        let Ok(key) = RegKey::predef(hkey).open_subkey(path) else {
+            continue;
+        };
+        push_key(&key, format!(r"{root}\{path}"));
+
+        for name in key.enum_keys().filter_map(std::result::Result::ok) {
+            if let Ok(subkey) = key.open_subkey(&name) {
+                push_key(&subkey, format!(r"{root}\{path}\{name}"));
+            }
+        }
+    }
+    events
+}
+
+/// # Errors
+///
+/// Returns error if the UserAssist keys cannot be read
+pub fn collect_userassist_events() -> Result<Vec<TimelineEvent>> {
+    Ok(userassist::get_userassist_entries()?
+        .into_iter()
+        .filter_map(|entry| {
+            Some(
+                TimelineEvent::new(
+                    entry.counters.last_run?,
+                    TimestampKind::LastRun,
+                    EventSource::UserAssist,
+                    format!("{} run {} times", entry.path, entry.counters.run_count),
+                )
+                .with_path(entry.path),
+            )
+        })
+        .collect())
+}
+
+/// # Errors
+///
+/// Returns error if the BAM keys cannot be read
+pub fn collect_bam_events() -> Result<Vec<TimelineEvent>> {
+    Ok(bam::get_bam_entries()?
+        .into_iter()
+        .filter_map(|entry| {
+            Some(
+                TimelineEvent::new(
+                    entry.last_execution?,
+                    TimestampKind::LastRun,
+                    EventSource::Bam,
+                    format!("{} executed by {}", entry.path, entry.sid),
+                )
+                .with_path(entry.path),
+            )
+        })
+        .collect())
+}
+
+/// # Errors
+///
+/// Returns error if the BagMRU key cannot be read
+pub fn collect_shellbag_events() -> Result<Vec<TimelineEvent>> {
+    let mut events = Vec::new();
+    for entry in shellbags::get_shellbags()? {
+        push_times(
+            &mut events,
+            &[
+                (entry.first_explored, TimestampKind::FirstVisited),
+                (entry.last_explored, TimestampKind::LastVisited),
+                (entry.key_last_write, TimestampKind::LastWritten),
+            ],
+            |time, kind| {
+                TimelineEvent::new(
+                    time,
+                    kind,
+                    EventSource::ShellBags,
+                    format!("Folder {} (BagMRU {})", entry.path, entry.mru_path),
+                )
+                .with_path(entry.path.as_str())
+            },
+        );
+    }
+    Ok(events)
+}
+
+/// # Errors
+///
+/// Returns error if the Office key cannot be opened
+pub fn collect_office_mru_events() -> Result<Vec<TimelineEvent>> {
+    Ok(office_mru::read_office_mru()?
+        .into_iter()
+        .filter_map(|entry| {
+            Some(
+                TimelineEvent::new(
+                    entry.last_opened?,
+                    TimestampKind::LastOpened,
+                    EventSource::OfficeMru,
+                    format!("{} {} opened {}", entry.app, entry.version, entry.path),
+                )
+                .with_path(entry.path),
+            )
+        })
+        .collect())
+}
+
+/// # Errors
+///
+/// Returns error if the AppCompatCache value cannot be read or parsed
+pub fn collect_shimcache_events() -> Result<Vec<TimelineEvent>> {
+    Ok(shimcache::read_shimcache()?
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(
+                TimelineEvent::new(
+                    entry.last_modified?,
+                    TimestampKind::Modified,
+                    EventSource::ShimCache,
+                    format!("{} cached at position {}", entry.path, entry.position),
+                )
+                .with_path(entry.path),
+            )
+        })
+        .collect())
+}
+
+impl Timeline {
+    fn add(&mut self, source: EventSource, events: Result<Vec<TimelineEvent>>) {
+        match events {
+            Ok(events) => self.events.extend(events),
+            Err(e) => self.errors.push(SourceError {
+                source,
+                message: e.to_russian(),
+            }),
+        }
+    }
+
+    fn sort(&mut self) {
+        self.events.sort_by(|a, b| {
+            (a.timestamp, a.source, a.kind, &a.description).cmp(&(
+                b.timestamp,
+                b.source,
+                b.kind,
+                &b.description,
+            ))
+        });
+    }
+}
+
+/// Collect events from every source, in chronological order
+///
+/// A failing source is recorded in [`Timeline::errors`] and doesn't stop the others.
+#[must_use]
+pub fn collect_timeline() -> Timeline {
+    let mut timeline = Timeline::default();
+    timeline.add(EventSource::Lnk, collect_lnk_events());
+    timeline.add(EventSource::Prefetch, collect_prefetch_events());
+    timeline.add(EventSource::RestorePoint, collect_restore_point_events());
+    timeline.add(EventSource::Registry, Ok(collect_registry_events()));
+    timeline.add(EventSource::UserAssist, collect_userassist_events());
+    timeline.add(EventSource::Bam, collect_bam_events());
+    timeline.add(EventSource::ShellBags, collect_shellbag_events());
+    timeline.add(EventSource::OfficeMru, collect_office_mru_events());
+    timeline.add(EventSource::ShimCache, collect_shimcache_events());
+    timeline.sort();
+    timeline
+}
@@ -0,0 +1,320 @@
+use crate::error::Result;
+use serde::Serialize;
+
+#[cfg(feature = "live")]
+mod live;
+
+/// Well-known SID of the built-in Administrators group
+pub const ADMINISTRATORS_SID: &str = "S-1-5-32-544";
+
+/// Lets the holder read any file with backup semantics, regardless of its ACL
+pub const BACKUP_PRIVILEGE: &str = "SeBackupPrivilege";
+
+/// `SE_GROUP_*` attribute bits of a token group
+const SE_GROUP_ENABLED: u32 = 0x0000_0004;
+const SE_GROUP_USE_FOR_DENY_ONLY: u32 = 0x0000_0010;
+
+/// UAC elevation type of a token (`TOKEN_ELEVATION_TYPE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ElevationType {
+    /// UAC is off or the account is not an administrator
+    Default,
+    /// The elevated half of a split administrator token
+    Full,
+    /// The filtered half of a split administrator token
+    Limited,
+}
+
+impl ElevationType {
+    #[must_use]
+    pub fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(Self::Default),
+            2 => Some(Self::Full),
+            3 => Some(Self::Limited),
+            _ => None,
+        }
+    }
+}
+
+/// Mandatory integrity level, from the last sub-authority of the label SID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum IntegrityLevel {
+    Untrusted,
+    Low,
+    Medium,
+    MediumPlus,
+    High,
+    System,
+    Protected,
+    Unknown(u32),
+}
+
+impl IntegrityLevel {
+    #[must_use]
+    pub fn from_rid(rid: u32) -> Self {
+        match rid {
+            0x0000 => Self::Untrusted,
+            0x1000 => Self::Low,
+            0x2000 => Self::Medium,
+            0x2100 => Self::MediumPlus,
+            0x3000 => Self::High,
+            0x4000 => Self::System,
+            0x5000 => Self::Protected,
+            other => Self::Unknown(other),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Untrusted => "Untrusted",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::MediumPlus => "Medium Plus",
+            Self::High => "High",
+            Self::System => "System",
+            Self::Protected => "Protected",
+            Self::Unknown(_) => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenGroup {
+    pub sid: String,
+    pub enabled: bool,
+    /// Present only to deny access, as Administrators is in a UAC-filtered token
+    pub deny_only: bool,
+}
+
+impl TokenGroup {
+    #[must_use]
+    pub fn from_attributes(sid: String, attributes: u32) -> Self {
+        Self {
+            sid,
+            enabled: attributes & SE_GROUP_ENABLED != 0,
+            deny_only: attributes & SE_GROUP_USE_FOR_DENY_ONLY != 0,
+        }
+    }
+}
+
+/// A privilege held by the token; only enabled privileges take effect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenPrivilege {
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// What the current process may do, from most to least capable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AccessLevel {
+    Elevated,
+    /// Member of Administrators running with the UAC-filtered token
+    FilteredAdmin,
+    /// UAC-filtered token of another privileged group (e.g. Backup Operators),
+    /// whose privileges return only after elevation
+    FilteredToken,
+    /// Not an administrator, but holds `SeBackupPrivilege` (e.g. Backup Operators)
+    BackupOperator,
+    StandardUser,
+}
+
+impl AccessLevel {
+    /// Explanation for the user of what is and isn't possible
+    #[must_use]
+    pub fn hint_russian(self) -> &'static str {
+        match self {
+            Self::Elevated => "Программа запущена с правами администратора.",
+            Self::FilteredAdmin => {
+                "Учётная запись входит в группу Администраторы, но UAC ограничил права: \
+                 перезапустите программу с повышением прав."
+            }
+            Self::FilteredToken => {
+                "UAC ограничил права учётной записи (например, группы Операторы архива): \
+                 SeBackupPrivilege и другие права станут доступны после перезапуска \
+                 с повышением прав."
+            }
+            Self::BackupOperator => {
+                "Есть право SeBackupPrivilege: защищённые папки читаются, \
+                 но для изменения настроек нужны права администратора."
+            }
+            Self::StandardUser => {
+                "Учётная запись не входит в группу Администраторы: \
+                 для изменения настроек потребуется пароль администратора."
+            }
+        }
+    }
+}
+
+/// Identity and rights of the current process token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenInfo {
+    pub user_sid: String,
+    pub groups: Vec<TokenGroup>,
+    pub elevated: bool,
+    pub elevation_type: Option<ElevationType>,
+    pub integrity_level: Option<IntegrityLevel>,
+    /// Every privilege present in the token, enabled or not
+    pub privileges: Vec<TokenPrivilege>,
+}
+
+impl TokenInfo {
+    /// Query the token of the current process
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token cannot be opened or queried
+    pub fn current() -> Result<Self> {
+        #[cfg(feature = "live")]
+        {
+            live::current()
+        }
+
+        #[cfg(not(feature = "live"))]
+        {
+            Err(crate::error::RecentEnablerError::TokenQueryFailed(
+                "Token inspection needs the `live` feature".to_string(),
+            ))
+        }
+    }
+
+    #[must_use]
+    pub fn privilege(&self, name: &str) -> Option<&TokenPrivilege> {
+        self.privileges
+            .iter()
+            .find(|privilege| privilege.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the user belongs to Administrators, even if UAC filtered the group out
+    #[must_use]
+    pub fn is_administrators_member(&self) -> bool {
+        self.groups
+            .iter()
+            .any(|group| group.sid == ADMINISTRATORS_SID && (group.enabled || group.deny_only))
+    }
+
+    #[must_use]
+    pub fn access_level(&self) -> AccessLevel {
+        if self.elevated {
+            AccessLevel::Elevated
+        } else if self.is_administrators_member() {
+            AccessLevel::FilteredAdmin
+        } else if self.elevation_type == Some(ElevationType::Limited) {
+            AccessLevel::FilteredToken
+        } else if self.privilege(BACKUP_PRIVILEGE).is_some() {
+            AccessLevel::BackupOperator
+        } else {
+            AccessLevel::StandardUser
+        }
+    }
+}
+
+/// Enable a privilege the token already holds
+///
+/// Returns `false` if the token doesn't hold it.
+///
+/// # Errors
+///
+/// Returns error if the token cannot be opened or adjusted
+pub fn enable_privilege(name: &str) -> Result<bool> {
+    #[cfg(feature = "live")]
+    {
+        live::set_privilege(name, true).map(|previous| previous.is_some())
+    }
+
+    #[cfg(not(feature = "live"))]
+    {
+        Err(crate::error::RecentEnablerError::TokenQueryFailed(format!(
+            "{name}: privileges need the `live` feature"
+        )))
+    }
+}
+
+/// Run `read` with `SeBackupPrivilege` enabled if held, so reads through
+/// [`crate::utils::open_for_backup`] bypass folder ACLs
+///
+/// The privilege is disabled again once the last concurrent `read` returns,
+/// unless it was already enabled before the first one started.
+pub fn with_backup_semantics<T>(read: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "live")]
+    {
+        live::with_backup_semantics(read)
+    }
+
+    #[cfg(not(feature = "live"))]
+    {
+        read()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(elevated: bool, elevation_type: ElevationType, groups: &[(&str, u32)]) -> TokenInfo {
+        TokenInfo {
+            user_sid: "S-1-5-21-1-2-3-1001".to_string(),
+            groups: groups
+                .iter()
+                .map(|(sid, attributes)| {
+                    TokenGroup::from_attributes((*sid).to_string(), *attributes)
+                })
+                .collect(),
+            elevated,
+            elevation_type: Some(elevation_type),
+            integrity_level: Some(IntegrityLevel::Medium),
+            privileges: vec![TokenPrivilege {
+                name: "SeChangeNotifyPrivilege".to_string(),
+                enabled: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_access_level() {
+        let filtered = token(
+            false,
+            ElevationType::Limited,
+            &[(ADMINISTRATORS_SID, SE_GROUP_USE_FOR_DENY_ONLY)],
+        );
+        assert!(filtered.is_administrators_member());
+        assert_eq!(filtered.access_level(), AccessLevel::FilteredAdmin);
+
+        // Backup Operators also get a split token under UAC, without Administrators
+        let filtered_backup = token(
+            false,
+            ElevationType::Limited,
+            &[("S-1-5-32-551", 0x07), ("S-1-5-32-545", 0x07)],
+        );
+        assert!(!filtered_backup.is_administrators_member());
+        assert_eq!(filtered_backup.access_level(), AccessLevel::FilteredToken);
+
+        let elevated = token(true, ElevationType::Full, &[(ADMINISTRATORS_SID, 0x0f)]);
+        assert_eq!(elevated.access_level(), AccessLevel::Elevated);
+
+        let mut standard = token(false, ElevationType::Default, &[("S-1-5-32-545", 0x07)]);
+        assert!(!standard.is_administrators_member());
+        assert_eq!(standard.access_level(), AccessLevel::StandardUser);
+
+        standard.privileges.push(TokenPrivilege {
+            name: BACKUP_PRIVILEGE.to_string(),
+            enabled: false,
+        });
+        assert_eq!(standard.access_level(), AccessLevel::BackupOperator);
+        assert!(!standard.privilege("sebackupprivilege").unwrap().enabled);
+    }
+
+    #[test]
+    fn test_integrity_level() {
+        assert_eq!(IntegrityLevel::from_rid(0x2000), IntegrityLevel::Medium);
+        assert_eq!(IntegrityLevel::from_rid(0x3000), IntegrityLevel::High);
+        assert_eq!(
+            IntegrityLevel::from_rid(0x2010),
+            IntegrityLevel::Unknown(0x2010)
+        );
+        assert!(IntegrityLevel::High > IntegrityLevel::Medium);
+        assert_eq!(ElevationType::from_raw(3), Some(ElevationType::Limited));
+        assert_eq!(ElevationType::from_raw(0), None);
+    }
+}
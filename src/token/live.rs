@@ -0,0 +1,226 @@
+use super::{
+    ElevationType, IntegrityLevel, TokenGroup, TokenInfo, TokenPrivilege, BACKUP_PRIVILEGE,
+};
+use crate::error::{RecentEnablerError, Result};
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, LocalFree, ERROR_NOT_ALL_ASSIGNED, HANDLE, HLOCAL, LUID,
+};
+use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation,
+    LookupPrivilegeNameW, LookupPrivilegeValueW, TokenElevation, TokenElevationType, TokenGroups,
+    TokenIntegrityLevel, TokenPrivileges, TokenUser, LUID_AND_ATTRIBUTES, PSID,
+    SE_PRIVILEGE_ENABLED, TOKEN_ACCESS_MASK, TOKEN_ADJUST_PRIVILEGES, TOKEN_ELEVATION,
+    TOKEN_GROUPS, TOKEN_INFORMATION_CLASS, TOKEN_MANDATORY_LABEL, TOKEN_PRIVILEGES,
+    TOKEN_PRIVILEGES_ATTRIBUTES, TOKEN_QUERY, TOKEN_USER,
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+struct Token(HANDLE);
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+fn open(access: TOKEN_ACCESS_MASK) -> Result<Token> {
+    let mut handle = HANDLE::default();
+    unsafe { OpenProcessToken(GetCurrentProcess(), access, &raw mut handle) }
+        .map_err(|e| RecentEnablerError::TokenQueryFailed(e.to_string()))?;
+    Ok(Token(handle))
+}
+
+/// Variable-size token information in an 8-byte aligned buffer
+fn information(token: &Token, class: TOKEN_INFORMATION_CLASS) -> Result<Vec<u64>> {
+    let mut len = 0u32;
+    // The first call fails with ERROR_INSUFFICIENT_BUFFER and reports the size
+    let _ = unsafe { GetTokenInformation(token.0, class, None, 0, &raw mut len) };
+    let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+    unsafe {
+        GetTokenInformation(
+            token.0,
+            class,
+            Some(buffer.as_mut_ptr().cast()),
+            len,
+            &raw mut len,
+        )
+    }
+    .map_err(|e| RecentEnablerError::TokenQueryFailed(format!("{class:?}: {e}")))?;
+    Ok(buffer)
+}
+
+fn sid_string(sid: PSID) -> Option<String> {
+    let mut string = PWSTR::null();
+    unsafe { ConvertSidToStringSidW(sid, &raw mut string) }.ok()?;
+    let result = unsafe { string.to_string() }.ok();
+    unsafe {
+        LocalFree(Some(HLOCAL(string.0.cast())));
+    }
+    result
+}
+
+fn privilege_name(luid: &LUID) -> Option<String> {
+    let mut buffer = [0u16; 128];
+    #[allow(clippy::cast_possible_truncation)]
+    let mut len = buffer.len() as u32;
+    unsafe {
+        LookupPrivilegeNameW(
+            PCWSTR::null(),
+            luid,
+            Some(PWSTR(buffer.as_mut_ptr())),
+            &raw mut len,
+        )
+    }
+    .ok()?;
+    Some(String::from_utf16_lossy(&buffer[..len as usize]))
+}
+
+pub fn current() -> Result<TokenInfo> {
+    let token = open(TOKEN_QUERY)?;
+
+    let user = information(&token, TokenUser)?;
+    let user = unsafe { &*user.as_ptr().cast::<TOKEN_USER>() };
+    let user_sid = sid_string(user.User.Sid).unwrap_or_default();
+
+    let groups = information(&token, TokenGroups)?;
+    let groups = unsafe {
+        let header = &*groups.as_ptr().cast::<TOKEN_GROUPS>();
+        std::slice::from_raw_parts(header.Groups.as_ptr(), header.GroupCount as usize)
+    }
+    .iter()
+    .filter_map(|group| {
+        Some(TokenGroup::from_attributes(
+            sid_string(group.Sid)?,
+            group.Attributes,
+        ))
+    })
+    .collect();
+
+    let privileges = information(&token, TokenPrivileges)?;
+    let privileges = unsafe {
+        let header = &*privileges.as_ptr().cast::<TOKEN_PRIVILEGES>();
+        std::slice::from_raw_parts::<LUID_AND_ATTRIBUTES>(
+            header.Privileges.as_ptr(),
+            header.PrivilegeCount as usize,
+        )
+    }
+    .iter()
+    .filter_map(|privilege| {
+        Some(TokenPrivilege {
+            name: privilege_name(&privilege.Luid)?,
+            enabled: privilege.Attributes.contains(SE_PRIVILEGE_ENABLED),
+        })
+    })
+    .collect();
+
+    let elevated = information(&token, TokenElevation).is_ok_and(|buffer| {
+        unsafe { &*buffer.as_ptr().cast::<TOKEN_ELEVATION>() }.TokenIsElevated != 0
+    });
+
+    let elevation_type = information(&token, TokenElevationType)
+        .ok()
+        .and_then(|buffer| ElevationType::from_raw(unsafe { *buffer.as_ptr().cast::<i32>() }));
+
+    let integrity_level = information(&token, TokenIntegrityLevel)
+        .ok()
+        .and_then(|buffer| {
+            let label = unsafe { &*buffer.as_ptr().cast::<TOKEN_MANDATORY_LABEL>() };
+            let count = unsafe { *GetSidSubAuthorityCount(label.Label.Sid) };
+            let last = u32::from(count).checked_sub(1)?;
+            Some(IntegrityLevel::from_rid(unsafe {
+                *GetSidSubAuthority(label.Label.Sid, last)
+            }))
+        });
+
+    Ok(TokenInfo {
+        user_sid,
+        groups,
+        elevated,
+        elevation_type,
+        integrity_level,
+        privileges,
+    })
+}
+
+/// Enable or disable a privilege, returning its previous state or `None` if not held
+pub fn set_privilege(name: &str, enable: bool) -> Result<Option<bool>> {
+    let token = open(TOKEN_QUERY | TOKEN_ADJUST_PRIVILEGES)?;
+    let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut luid = LUID::default();
+    unsafe { LookupPrivilegeValueW(PCWSTR::null(), PCWSTR(wide.as_ptr()), &raw mut luid) }
+        .map_err(|e| RecentEnablerError::PrivilegeEnableFailed(format!("{name}: {e}")))?;
+
+    let privileges = TOKEN_PRIVILEGES {
+        PrivilegeCount: 1,
+        Privileges: [LUID_AND_ATTRIBUTES {
+            Luid: luid,
+            Attributes: if enable {
+                SE_PRIVILEGE_ENABLED
+            } else {
+                TOKEN_PRIVILEGES_ATTRIBUTES(0)
+            },
+        }],
+    };
+    let mut previous = TOKEN_PRIVILEGES::default();
+    let mut len = 0u32;
+    #[allow(clippy::cast_possible_truncation)]
+    unsafe {
+        AdjustTokenPrivileges(
+            token.0,
+            false,
+            Some(&raw const privileges),
+            std::mem::size_of::<TOKEN_PRIVILEGES>() as u32,
+            Some(&raw mut previous),
+            Some(&raw mut len),
+        )
+    }
+    .map_err(|e| RecentEnablerError::PrivilegeEnableFailed(format!("{name}: {e}")))?;
+
+    // Succeeds with ERROR_NOT_ALL_ASSIGNED when the token doesn't hold the privilege
+    #[allow(clippy::cast_possible_wrap)]
+    let not_assigned =
+        std::io::Error::last_os_error().raw_os_error() == Some(ERROR_NOT_ALL_ASSIGNED.0 as i32);
+    if not_assigned {
+        return Ok(None);
+    }
+    // Only privileges whose state actually changed are reported back
+    Ok(Some(if previous.PrivilegeCount == 0 {
+        enable
+    } else {
+        previous.Privileges[0]
+            .Attributes
+            .contains(SE_PRIVILEGE_ENABLED)
+    }))
+}
+
+/// Reference-count concurrent `read`s so the privilege is enabled once and disabled by the last
+pub fn with_backup_semantics<T>(read: impl FnOnce() -> T) -> T {
+    /// Reads in progress, and whether the last one out must disable the privilege
+    static SCOPES: std::sync::Mutex<(usize, bool)> = std::sync::Mutex::new((0, false));
+
+    {
+        let mut scopes = SCOPES
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if scopes.0 == 0 {
+            let previous = set_privilege(BACKUP_PRIVILEGE, true).ok().flatten();
+            scopes.1 = previous == Some(false);
+        }
+        scopes.0 += 1;
+    }
+
+    let result = read();
+
+    let mut scopes = SCOPES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    scopes.0 -= 1;
+    if scopes.0 == 0 && scopes.1 {
+        let _ = set_privilege(BACKUP_PRIVILEGE, false);
+    }
+    result
+}
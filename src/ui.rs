@@ -78,30 +78,6 @@ pub fn card_style(_theme: &iced::Theme, bg_color: Color, border_color: Color) ->
     }
 }
 
-pub fn warning_box<M: Clone + 'static>(
-    message: &str,
-    on_restart: M,
-) -> container::Container<'_, M> {
-    container(
-        row![
-            text(message).size(13).width(Fill),
-            restart_button(on_restart).padding([5, 10]),
-        ]
-        .spacing(8)
-        .align_y(iced::Alignment::Center),
-    )
-    .padding(10)
-    .style(|_theme| container::Style {
-        background: Some(iced::Background::Color(Color::from_rgb(0.25, 0.2, 0.15))),
-        border: iced::Border {
-            color: Color::from_rgb(0.6, 0.5, 0.3),
-            width: 1.0,
-            radius: 6.0.into(),
-        },
-        ..Default::default()
-    })
-}
-
 pub fn restart_button<M: Clone>(on_press: M) -> button::Button<'static, M> {
     button("Перезапустить")
         .on_press(on_press)
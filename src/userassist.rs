@@ -0,0 +1,138 @@
+use crate::{binary, known_folders, utils};
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Size of a version 5 (Windows 7+) UserAssist record
+const ENTRY_V5_SIZE: usize = 72;
+
+/// Counters decoded from a version 5 UserAssist record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAssistCounters {
+    pub run_count: u32,
+    pub focus_count: u32,
+    pub focus_time_ms: u32,
+    pub last_run: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserAssistEntry {
+    /// GUID of the `UserAssist\{GUID}` key the entry was found under
+    pub guid: String,
+    /// ROT13-decoded value name as stored by Explorer
+    pub name: String,
+    /// Name with a leading KNOWNFOLDERID replaced by its location
+    pub path: String,
+    pub counters: UserAssistCounters,
+}
+
+/// Decode a ROT13-encoded UserAssist value name
+#[must_use]
+pub fn rot13(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => (((c as u8 - b'a' + 13) % 26) + b'a') as char,
+            'A'..='Z' => (((c as u8 - b'A' + 13) % 26) + b'A') as char,
+            _ => c,
+        })
+        .collect()
+}
+
+/// Parse a version 5 UserAssist record
+///
+/// Returns `None` if the data is not a 72-byte version 5 record.
+#[must_use]
+pub fn parse_entry_data(data: &[u8]) -> Option<UserAssistCounters> {
+    if data.len() != ENTRY_V5_SIZE {
+        return None;
+    }
+
+    Some(UserAssistCounters {
+        run_count: binary::read_u32(data, 4)?,
+        focus_count: binary::read_u32(data, 8)?,
+        focus_time_ms: binary::read_u32(data, 12)?,
+        last_run: utils::filetime_to_system_time(binary::read_u64(data, 60)?),
+    })
+}
+
+/// Decode a single `Count` value into an entry
+///
+/// Session bookkeeping values (`UEME_*`) and unknown record formats are skipped.
+#[must_use]
+pub fn decode_entry(guid: &str, value_name: &str, data: &[u8]) -> Option<UserAssistEntry> {
+    let name = rot13(value_name);
+    if name.starts_with("UEME_") {
+        return None;
+    }
+
+    let counters = parse_entry_data(data)?;
+    Some(UserAssistEntry {
+        guid: guid.to_string(),
+        path: known_folders::substitute_known_folder(&name),
+        name,
+        counters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v5_record(run_count: u32, focus_count: u32, focus_ms: u32, filetime: u64) -> Vec<u8> {
+        let mut data = vec![0u8; ENTRY_V5_SIZE];
+        data[4..8].copy_from_slice(&run_count.to_le_bytes());
+        data[8..12].copy_from_slice(&focus_count.to_le_bytes());
+        data[12..16].copy_from_slice(&focus_ms.to_le_bytes());
+        data[60..68].copy_from_slice(&filetime.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_rot13() {
+        assert_eq!(rot13(r"P:\Jvaqbjf\abgrcnq.rkr"), r"C:\Windows\notepad.exe");
+        assert_eq!(rot13(&rot13("UEME_CTLSESSION")), "UEME_CTLSESSION");
+    }
+
+    #[test]
+    fn test_parse_entry_data() {
+        // 2021-01-01 00:00:00 UTC
+        let data = v5_record(7, 3, 65_000, 132_539_328_000_000_000);
+        let counters = parse_entry_data(&data).unwrap();
+        assert_eq!(counters.run_count, 7);
+        assert_eq!(counters.focus_count, 3);
+        assert_eq!(counters.focus_time_ms, 65_000);
+        let secs = counters
+            .last_run
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert_eq!(secs, 1_609_459_200);
+
+        assert!(parse_entry_data(&data[..16]).is_none());
+        assert!(parse_entry_data(&v5_record(1, 1, 0, 0))
+            .unwrap()
+            .last_run
+            .is_none());
+    }
+
+    #[test]
+    fn test_decode_entry() {
+        let data = v5_record(2, 0, 0, 0);
+        let entry = decode_entry(
+            "{CEBFF5CD-ACE2-4F4F-9178-9926F41749EA}",
+            &rot13(r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\cmd.exe"),
+            &data,
+        )
+        .unwrap();
+        assert!(entry.name.starts_with("{1AC14E77"));
+        assert!(entry.path.ends_with(r"\cmd.exe"));
+        assert!(!entry.path.starts_with('{'));
+
+        assert!(decode_entry("{GUID}", &rot13("UEME_CTLCUACount:ctor"), &data).is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use super::*;
+
+use crate::error::{RecentEnablerError, Result};
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+
+const ADVANCED_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\Advanced";
+
+const USERASSIST_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\UserAssist";
+
+/// Get all decoded UserAssist entries for the current user
+///
+/// # Errors
+///
+/// Returns error if the UserAssist key cannot be opened
+pub fn get_userassist_entries() -> Result<Vec<UserAssistEntry>> {
+    let root = match RegKey::predef(HKEY_CURRENT_USER).open_subkey(USERASSIST_PATH) {
+        Ok(key) => key,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(RecentEnablerError::UserAssistRegistryReadFailed(format!(
+                "{USERASSIST_PATH}: {e}"
+            )))
+        }
+    };
+
+    let mut entries = Vec::new();
+    for guid in root.enum_keys().filter_map(std::result::Result::ok) {
+        let Ok(count) = root.open_subkey(format!(r"{guid}\Count")) else {
+            continue;
+        };
+        for (value_name, value) in count.enum_values().filter_map(std::result::Result::ok) {
+            if let Some(entry) = decode_entry(&guid, &value_name, &value.bytes) {
+                entries.push(entry);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Check if program launch tracking (UserAssist) is disabled
+///
+/// # Errors
+///
+/// Returns error if registry keys cannot be read
+pub fn is_userassist_disabled() -> Result<bool> {
+    let track_progs = utils::read_reg_dword(HKEY_CURRENT_USER, ADVANCED_PATH, "Start_TrackProgs");
+    Ok(track_progs.unwrap_or(1) == 0)
+}
+
+/// Enable program launch tracking in Windows registry
+///
+/// # Errors
+///
+/// Returns error if registry key cannot be written
+pub fn enable_userassist() -> Result {
+    utils::write_reg_dword(HKEY_CURRENT_USER, ADVANCED_PATH, "Start_TrackProgs", 1)
+        .map_err(|e| RecentEnablerError::UserAssistEnableFailed(e.to_string()))
+}
@@ -0,0 +1,167 @@
+use crate::error::{RecentEnablerError, Result};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const MB: u64 = 1024 * 1024;
+
+/// Journal size applied by `fsutil usn createjournal`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalSize {
+    /// Maximum journal size in bytes
+    pub max_size: u64,
+    /// Bytes added or trimmed when the journal wraps
+    pub allocation_delta: u64,
+}
+
+impl Default for JournalSize {
+    /// 512 MB keeps weeks of activity on a typical workstation (Windows uses 32 MB)
+    fn default() -> Self {
+        Self {
+            max_size: 512 * MB,
+            allocation_delta: 64 * MB,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsnJournalInfo {
+    pub journal_id: u64,
+    pub first_usn: u64,
+    pub next_usn: u64,
+    pub lowest_valid_usn: u64,
+    pub max_usn: u64,
+    pub max_size: u64,
+    pub allocation_delta: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeJournal {
+    /// Drive letter with colon, e.g. `C:`
+    pub volume: String,
+    /// `None` if the journal is missing or couldn't be queried
+    pub journal: Option<UsnJournalInfo>,
+    pub error: Option<String>,
+}
+
+impl VolumeJournal {
+    /// Whether the journal must be created or grown to reach `target`
+    #[must_use]
+    pub fn needs_change(&self, target: JournalSize) -> bool {
+        self.journal
+            .is_none_or(|journal| journal.max_size < target.max_size)
+    }
+}
+
+/// Parse `fsutil usn queryjournal` output
+///
+/// Labels are localized (and printed in the OEM code page), so values are
+/// taken by position: the first seven hexadecimal fields are always the
+/// journal ID, first, next, lowest valid and max USN, maximum size and
+/// allocation delta.
+///
+/// # Errors
+///
+/// Returns error if fewer than seven hexadecimal fields are present
+pub fn parse_queryjournal(output: &str) -> Result<UsnJournalInfo> {
+    let values: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter_map(|(_, value)| value.split_whitespace().next())
+        .filter_map(|value| value.strip_prefix("0x"))
+        .filter_map(|hex| u64::from_str_radix(hex, 16).ok())
+        .collect();
+
+    let [journal_id, first_usn, next_usn, lowest_valid_usn, max_usn, max_size, allocation_delta, ..] =
+        values[..]
+    else {
+        return Err(RecentEnablerError::UsnJournalReadFailed(format!(
+            "unexpected fsutil output: {}",
+            output.trim()
+        )));
+    };
+
+    Ok(UsnJournalInfo {
+        journal_id,
+        first_usn,
+        next_usn,
+        lowest_valid_usn,
+        max_usn,
+        max_size,
+        allocation_delta,
+    })
+}
+
+/// Arguments for `fsutil usn createjournal` applying `size` to `volume`
+///
+/// `createjournal` on an existing journal resizes it without losing records.
+#[must_use]
+pub fn createjournal_args(volume: &str, size: JournalSize) -> Vec<String> {
+    vec![
+        "usn".to_string(),
+        "createjournal".to_string(),
+        format!("m={}", size.max_size),
+        format!("a={}", size.allocation_delta),
+        volume.to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_queryjournal() {
+        let output = include_str!("../tests/fixtures/fsutil/queryjournal_en.txt");
+        let info = parse_queryjournal(output).unwrap();
+        assert_eq!(info.journal_id, 0x01d9_f3a2_b7c4_e5d6);
+        assert_eq!(info.first_usn, 0x1280_0000);
+        assert_eq!(info.next_usn, 0x14b3_f2a8);
+        assert_eq!(info.max_usn, 0x7fff_ffff_ffff_0000);
+        assert_eq!(info.max_size, 32 * MB);
+        assert_eq!(info.allocation_delta, 8 * MB);
+    }
+
+    #[test]
+    fn test_parse_queryjournal_localized() {
+        // Windows 11 appends human-readable sizes; labels depend on the UI language
+        let output = "Код журнала USN             : 0x01d9f3a2b7c4e5d6\r\n\
+                      Первый USN                  : 0x0000000000000000\r\n\
+                      Следующий USN               : 0x0000000000a1b2c3\r\n\
+                      Наименьший допустимый USN   : 0x0000000000000000\r\n\
+                      Макс. USN                   : 0x7fffffffffff0000\r\n\
+                      Максимальный размер         : 0x0000000020000000 (512,0 МБ)\r\n\
+                      Разностное выделение        : 0x0000000004000000 (64,0 МБ)\r\n";
+        let info = parse_queryjournal(output).unwrap();
+        assert_eq!(info.next_usn, 0x00a1_b2c3);
+        assert_eq!(info.max_size, 512 * MB);
+        assert_eq!(info.allocation_delta, 64 * MB);
+
+        assert!(parse_queryjournal("Error:  The volume change journal is not active.").is_err());
+    }
+
+    #[test]
+    fn test_needs_change() {
+        let info = parse_queryjournal(include_str!("../tests/fixtures/fsutil/queryjournal_en.txt"))
+            .unwrap();
+        let mut volume = VolumeJournal {
+            volume: "C:".to_string(),
+            journal: Some(info),
+            error: None,
+        };
+        assert!(volume.needs_change(JournalSize::default()));
+        assert!(!volume.needs_change(JournalSize {
+            max_size: 32 * MB,
+            allocation_delta: 8 * MB
+        }));
+
+        volume.journal = None;
+        assert!(volume.needs_change(JournalSize::default()));
+        assert_eq!(
+            createjournal_args("D:", JournalSize::default()),
+            ["usn", "createjournal", "m=536870912", "a=67108864", "D:"]
+        );
+    }
+}
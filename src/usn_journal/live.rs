@@ -0,0 +1,118 @@
+use super::*;
+
+use crate::utils;
+
+/// List fixed local volumes formatted as NTFS
+///
+/// Removable, network and optical drives are left out: their journals come and
+/// go with the media and aren't part of the system's readiness.
+#[must_use]
+pub fn get_ntfs_volumes() -> Vec<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{GetDriveTypeW, GetVolumeInformationW};
+    const DRIVE_FIXED: u32 = 3;
+
+    let mut volumes = Vec::new();
+    for letter in 'A'..='Z' {
+        let root: Vec<u16> = format!(r"{letter}:\")
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+        if unsafe { GetDriveTypeW(PCWSTR(root.as_ptr())) } != DRIVE_FIXED {
+            continue;
+        }
+        let mut fs_name = [0u16; 32];
+
+        let queried = unsafe {
+            GetVolumeInformationW(
+                PCWSTR(root.as_ptr()),
+                None,
+                None,
+                None,
+                None,
+                Some(&mut fs_name),
+            )
+        };
+        if queried.is_err() {
+            continue;
+        }
+
+        let end = fs_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(fs_name.len());
+        if String::from_utf16_lossy(&fs_name[..end]) == "NTFS" {
+            volumes.push(format!("{letter}:"));
+        }
+    }
+    volumes
+}
+
+/// Query the change journal of `volume`
+///
+/// # Errors
+///
+/// Returns error if the journal is not active or `fsutil` fails
+pub fn query_journal(volume: &str) -> Result<UsnJournalInfo> {
+    let output = utils::hidden_command("fsutil")
+        .args(["usn", "queryjournal", volume])
+        .output()
+        .map_err(|e| {
+            RecentEnablerError::UsnJournalReadFailed(format!("Failed to run fsutil: {e}"))
+        })?;
+    if !output.status.success() {
+        return Err(RecentEnablerError::UsnJournalReadFailed(format!(
+            "{volume} {}",
+            utils::command_error(&output)
+        )));
+    }
+    parse_queryjournal(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Query the change journal of every fixed NTFS volume
+#[must_use]
+pub fn get_volume_journals() -> Vec<VolumeJournal> {
+    get_ntfs_volumes()
+        .into_iter()
+        .map(|volume| match query_journal(&volume) {
+            Ok(journal) => VolumeJournal {
+                volume,
+                journal: Some(journal),
+                error: None,
+            },
+            Err(e) => VolumeJournal {
+                volume,
+                journal: None,
+                error: Some(e.to_russian()),
+            },
+        })
+        .collect()
+}
+
+/// Create or grow the change journal on every fixed NTFS volume that needs it
+///
+/// # Errors
+///
+/// Returns error if `fsutil` fails for any volume
+pub fn enable_usn_journals(size: JournalSize) -> Result {
+    for volume in get_volume_journals() {
+        if !volume.needs_change(size) {
+            continue;
+        }
+
+        let output = utils::hidden_command("fsutil")
+            .args(createjournal_args(&volume.volume, size))
+            .output()
+            .map_err(|e| {
+                RecentEnablerError::UsnJournalEnableFailed(format!("Failed to run fsutil: {e}"))
+            })?;
+        if !output.status.success() {
+            return Err(RecentEnablerError::UsnJournalEnableFailed(format!(
+                "{}: {}",
+                volume.volume,
+                utils::command_error(&output)
+            )));
+        }
+    }
+    Ok(())
+}
@@ -1,54 +1,137 @@
 use crate::error::{RecentEnablerError, Result};
-use std::path::Path;
-use std::time::SystemTime;
-use winreg::{RegKey, HKEY};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
 
 /// Check if the current process is running with admin privileges
 #[must_use]
 pub fn is_admin() -> bool {
+    #[cfg(feature = "live")]
+    {
+        live::token_is_elevated()
+    }
+
+    #[cfg(not(feature = "live"))]
+    {
+        false
+    }
+}
+
+/// Build a command that runs without opening a console window
+#[must_use]
+pub fn hidden_command(program: &str) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut cmd = std::process::Command::new(program);
+
     #[cfg(windows)]
     {
-        use windows::Win32::Foundation::{CloseHandle, HANDLE};
-        use windows::Win32::Security::{
-            GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
-        };
-        use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
 
-        unsafe {
-            let mut token = HANDLE::default();
+    cmd
+}
 
-            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &raw mut token).is_err() {
-                return false;
-            }
+/// Open a file for reading with backup semantics
+///
+/// With `SeBackupPrivilege` enabled (see [`crate::token::with_backup_semantics`])
+/// this bypasses the file's ACL; without it, it behaves like [`std::fs::File::open`].
+///
+/// # Errors
+///
+/// Returns error if the file cannot be opened
+pub fn open_for_backup(path: &Path) -> std::io::Result<std::fs::File> {
+    #[allow(unused_mut)]
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
 
-            let mut elevation = TOKEN_ELEVATION::default();
-            let mut return_length = 0u32;
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x02000000;
+        options.custom_flags(FILE_FLAG_BACKUP_SEMANTICS);
+    }
 
-            #[allow(clippy::cast_possible_truncation)]
-            let result = GetTokenInformation(
-                token,
-                TokenElevation,
-                Some((&raw mut elevation).cast()),
-                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
-                &raw mut return_length,
-            );
+    options.open(path)
+}
 
-            let _ = CloseHandle(token);
+/// First non-empty line of a failed command's stderr (or stdout if stderr is empty)
+#[must_use]
+pub fn command_error(output: &std::process::Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stderr
+        .lines()
+        .chain(stdout.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("unknown error")
+        .to_string()
+}
 
-            result.is_ok() && elevation.TokenIsElevated != 0
-        }
+/// Time the system was last booted
+///
+/// Returns `None` without the `live` feature.
+#[must_use]
+pub fn boot_time() -> Option<SystemTime> {
+    #[cfg(feature = "live")]
+    {
+        live::query_boot_time()
     }
 
-    #[cfg(not(windows))]
+    #[cfg(not(feature = "live"))]
     {
-        false
+        None
     }
 }
 
+/// Quote one argument so `CommandLineToArgvW` splits it back unchanged
+#[must_use]
+pub fn quote_windows_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\u{b}', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing quote must be doubled
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Quote a string as a single-quoted `PowerShell` literal
+#[must_use]
+pub fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
 /// Restart the current application with administrator privileges
 ///
 /// This function spawns a new instance of the application with elevated privileges
 /// using PowerShell's Start-Process with the RunAs verb, then exits the current process.
+/// The new instance receives `args`, see
+/// [`crate::broker::restart_with_pending_action`] for resuming an operation.
 ///
 /// # Errors
 ///
@@ -58,7 +141,7 @@ pub fn is_admin() -> bool {
 /// # Platform Support
 ///
 /// This function only works on Windows. On other platforms, it returns an error.
-pub fn restart_as_admin() -> Result<()> {
+pub fn restart_as_admin(args: &[String]) -> Result<()> {
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
@@ -68,17 +151,23 @@ pub fn restart_as_admin() -> Result<()> {
             RecentEnablerError::WindowsPathNotFound(format!("Failed to get executable path: {}", e))
         })?;
 
+        let command_line = args
+            .iter()
+            .map(|arg| quote_windows_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut script = format!(
+            "Start-Process -FilePath {} -Verb RunAs",
+            powershell_quote(&exe_path.to_string_lossy())
+        );
+        if !command_line.is_empty() {
+            script.push_str(" -ArgumentList ");
+            script.push_str(&powershell_quote(&command_line));
+        }
+
         let mut cmd = std::process::Command::new("powershell");
-        cmd.args([
-            "-WindowStyle",
-            "Hidden",
-            "-Command",
-            &format!(
-                "Start-Process -FilePath '{}' -Verb RunAs",
-                exe_path.display()
-            ),
-        ])
-        .creation_flags(CREATE_NO_WINDOW);
+        cmd.args(["-WindowStyle", "Hidden", "-Command", &script])
+            .creation_flags(CREATE_NO_WINDOW);
 
         cmd.spawn().map_err(|e| {
             RecentEnablerError::SystemRestoreEnableFailed(format!(
@@ -92,81 +181,478 @@ pub fn restart_as_admin() -> Result<()> {
 
     #[cfg(not(windows))]
     {
+        let _ = args;
         Err(RecentEnablerError::WindowsPathNotFound(
             "Restart as admin is only supported on Windows".to_string(),
         ))
     }
 }
 
-pub struct DirectoryStats {
-    pub count: usize,
-    pub oldest: Option<SystemTime>,
-    pub newest: Option<SystemTime>,
+/// Number of 100ns FILETIME intervals between 1601-01-01 and 1970-01-01
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+
+/// Convert a Windows FILETIME (100ns intervals since 1601) to `SystemTime`
+///
+/// Returns `None` for zero and pre-1970 values, which Windows uses as "never".
+#[must_use]
+pub fn filetime_to_system_time(filetime: u64) -> Option<SystemTime> {
+    let ticks = filetime.checked_sub(FILETIME_UNIX_EPOCH)?;
+    #[allow(clippy::cast_possible_truncation)]
+    let nanos = (ticks % 10_000_000) as u32 * 100;
+    UNIX_EPOCH.checked_add(Duration::new(ticks / 10_000_000, nanos))
+}
+
+/// Days between the OLE Automation epoch (1899-12-30) and the Unix epoch
+const OLE_DATE_UNIX_EPOCH: f64 = 25_569.0;
+
+/// Convert an OLE Automation date (fractional days since 1899-12-30) to `SystemTime`
+///
+/// Returns `None` for pre-1970 and non-finite values.
+#[must_use]
+pub fn ole_date_to_system_time(days: f64) -> Option<SystemTime> {
+    let secs = (days - OLE_DATE_UNIX_EPOCH) * 86_400.0;
+    if !secs.is_finite() || secs < 0.0 {
+        return None;
+    }
+    UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(secs).ok()?)
+}
+
+/// Convert an MS-DOS date/time pair (as stored in shell items) to `SystemTime`
+///
+/// DOS timestamps have two-second resolution and are interpreted as UTC.
+#[must_use]
+pub fn dos_datetime_to_system_time(date: u16, time: u16) -> Option<SystemTime> {
+    if date == 0 {
+        return None;
+    }
+    let datetime = chrono::NaiveDate::from_ymd_opt(
+        1980 + i32::from(date >> 9),
+        u32::from((date >> 5) & 0x0F),
+        u32::from(date & 0x1F),
+    )?
+    .and_hms_opt(
+        u32::from(time >> 11),
+        u32::from((time >> 5) & 0x3F),
+        u32::from(time & 0x1F) * 2,
+    )?;
+    let secs = u64::try_from(datetime.and_utc().timestamp()).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
 }
 
-/// Get statistics about files in a directory
+/// Parse `ConvertTo-Json` output of a pipeline into a list
+///
+/// `ConvertTo-Json` emits a bare object for a single item and nothing at all
+/// for an empty pipeline.
 ///
 /// # Errors
 ///
-/// Returns error if directory cannot be read
-pub fn get_directory_stats(path: &Path, extension: &str) -> Result<DirectoryStats> {
-    if !path.exists() {
-        return Ok(DirectoryStats {
-            count: 0,
-            oldest: None,
-            newest: None,
-        });
+/// Returns error if the output is not valid JSON for `T`
+pub fn parse_json_list<T: serde::de::DeserializeOwned>(json: &str) -> serde_json::Result<Vec<T>> {
+    let json = json.trim();
+    if json.is_empty() {
+        Ok(Vec::new())
+    } else if json.starts_with('[') {
+        serde_json::from_str(json)
+    } else {
+        serde_json::from_str(json).map(|item| vec![item])
     }
+}
 
-    let entries = std::fs::read_dir(path).map_err(|e| {
-        RecentEnablerError::DirectoryReadFailed(format!("{}: {}", path.display(), e))
-    })?;
+/// Expand `%VAR%` references using the current environment
+///
+/// Unknown variables are left as-is, matching `ExpandEnvironmentStrings`.
+#[must_use]
+pub fn expand_env_vars(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
 
-    let mut count = 0;
-    let mut oldest: Option<SystemTime> = None;
-    let mut newest: Option<SystemTime> = None;
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => {
+                result.push('%');
+                result.push_str(name);
+                result.push('%');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Which files a directory walk reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// File name globs (`*` and `?`), matched case-insensitively, e.g. `*.pf` or `Layout.ini`
+    pub patterns: Vec<String>,
+    /// How many levels of subdirectories to descend into (0 = top level only)
+    pub max_depth: usize,
+}
+
+impl ScanOptions {
+    #[must_use]
+    pub fn new(patterns: &[&str], max_depth: usize) -> Self {
+        Self {
+            patterns: patterns.iter().map(|p| (*p).to_string()).collect(),
+            max_depth,
+        }
+    }
+
+    /// Whether `name` matches any of the patterns
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern, name))
+    }
+}
+
+/// Case-insensitive glob match supporting `*` and `?`
+#[must_use]
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
 
-    for entry in entries.filter_map(std::result::Result::ok) {
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A matching file found by [`walk_directory`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub created: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+}
+
+/// A file or directory that could not be read during a walk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreadableEntry {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Number of unreadable entries kept as examples in a status
+pub const UNREADABLE_EXAMPLES: usize = 3;
+
+/// `path: reason` for the first [`UNREADABLE_EXAMPLES`] unreadable entries
+#[must_use]
+pub fn unreadable_examples(entries: &[UnreadableEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .take(UNREADABLE_EXAMPLES)
+        .map(|entry| format!("{}: {}", entry.path.display(), entry.reason))
+        .collect()
+}
+
+/// Iterator over the matching files below a directory
+///
+/// Symbolic links and junctions are not followed. Entries that cannot be
+/// read are yielded as `Err` instead of being skipped.
+pub struct DirectoryWalker {
+    options: ScanOptions,
+    /// Directories still being read, with their depth
+    stack: Vec<(PathBuf, std::fs::ReadDir, usize)>,
+}
+
+impl DirectoryWalker {
+    fn read_entry(
+        &mut self,
+        entry: std::fs::DirEntry,
+        depth: usize,
+    ) -> Option<std::result::Result<FileEntry, UnreadableEntry>> {
         let path = entry.path();
-        if path.is_file()
-            && path
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
-        {
-            count += 1;
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    oldest = Some(oldest.map_or(modified, |t| t.min(modified)));
-                    newest = Some(newest.map_or(modified, |t| t.max(modified)));
+        let unreadable = |path: PathBuf, e: std::io::Error| UnreadableEntry {
+            path,
+            reason: e.to_string(),
+        };
+
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => return Some(Err(unreadable(path, e))),
+        };
+
+        if file_type.is_dir() {
+            if depth < self.options.max_depth {
+                match std::fs::read_dir(&path) {
+                    Ok(entries) => self.stack.push((path, entries, depth + 1)),
+                    Err(e) => return Some(Err(unreadable(path, e))),
                 }
             }
+            return None;
         }
+
+        if !file_type.is_file() || !self.options.matches(&entry.file_name().to_string_lossy()) {
+            return None;
+        }
+
+        Some(match entry.metadata() {
+            Ok(metadata) => Ok(FileEntry {
+                path,
+                size: metadata.len(),
+                created: metadata.created().ok(),
+                accessed: metadata.accessed().ok(),
+                modified: metadata.modified().ok(),
+            }),
+            Err(e) => Err(unreadable(path, e)),
+        })
     }
+}
+
+impl Iterator for DirectoryWalker {
+    type Item = std::result::Result<FileEntry, UnreadableEntry>;
 
-    Ok(DirectoryStats {
-        count,
-        oldest,
-        newest,
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((dir, entries, depth)) = self.stack.last_mut() {
+            let depth = *depth;
+            match entries.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some(Err(e)) => {
+                    return Some(Err(UnreadableEntry {
+                        path: dir.clone(),
+                        reason: e.to_string(),
+                    }))
+                }
+                Some(Ok(entry)) => {
+                    if let Some(item) = self.read_entry(entry, depth) {
+                        return Some(item);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// List the files below `path` matching `options`
+///
+/// A missing directory yields no entries.
+///
+/// # Errors
+///
+/// Returns error if `path` exists but cannot be read
+pub fn walk_directory(path: &Path, options: &ScanOptions) -> Result<DirectoryWalker> {
+    let mut stack = Vec::new();
+    if path.exists() {
+        let entries = std::fs::read_dir(path).map_err(|e| {
+            RecentEnablerError::DirectoryReadFailed(format!("{}: {}", path.display(), e))
+        })?;
+        stack.push((path.to_path_buf(), entries, 0));
+    }
+
+    Ok(DirectoryWalker {
+        options: options.clone(),
+        stack,
     })
 }
 
-pub fn read_reg_dword(hkey: HKEY, path: &str, value: &str) -> Option<u32> {
-    RegKey::predef(hkey)
-        .open_subkey(path)
-        .ok()
-        .and_then(|k| k.get_value(value).ok())
+/// Oldest and newest of a set of timestamps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub oldest: Option<SystemTime>,
+    pub newest: Option<SystemTime>,
+}
+
+impl TimeRange {
+    pub fn include(&mut self, time: Option<SystemTime>) {
+        if let Some(time) = time {
+            self.oldest = Some(self.oldest.map_or(time, |t| t.min(time)));
+            self.newest = Some(self.newest.map_or(time, |t| t.max(time)));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirectoryStats {
+    pub count: usize,
+    pub total_size: u64,
+    pub created: TimeRange,
+    pub accessed: TimeRange,
+    pub modified: TimeRange,
+    pub unreadable: Vec<UnreadableEntry>,
+}
+
+impl DirectoryStats {
+    pub fn add(&mut self, entry: &FileEntry) {
+        self.count += 1;
+        self.total_size += entry.size;
+        self.created.include(entry.created);
+        self.accessed.include(entry.accessed);
+        self.modified.include(entry.modified);
+    }
+}
+
+impl FromIterator<std::result::Result<FileEntry, UnreadableEntry>> for DirectoryStats {
+    fn from_iter<I: IntoIterator<Item = std::result::Result<FileEntry, UnreadableEntry>>>(
+        iter: I,
+    ) -> Self {
+        let mut stats = Self::default();
+        for item in iter {
+            match item {
+                Ok(entry) => stats.add(&entry),
+                Err(unreadable) => stats.unreadable.push(unreadable),
+            }
+        }
+        stats
+    }
+}
+
+/// Directory statistics with the files of one extension also counted apart
+///
+/// Used where one extension is the artifact itself (`.lnk`, `.pf`) and the
+/// rest are companion files; the artifact's times describe the activity.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtensionStats {
+    /// Files with the extension
+    pub matching: DirectoryStats,
+    /// Every file, including the matching ones, and the unreadable entries
+    pub all: DirectoryStats,
+}
+
+impl ExtensionStats {
+    pub fn collect(
+        items: impl IntoIterator<Item = std::result::Result<FileEntry, UnreadableEntry>>,
+        extension: &str,
+    ) -> Self {
+        let mut stats = Self::default();
+        for item in items {
+            match item {
+                Ok(entry) => {
+                    if entry
+                        .path
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+                    {
+                        stats.matching.add(&entry);
+                    }
+                    stats.all.add(&entry);
+                }
+                Err(unreadable) => stats.all.unreadable.push(unreadable),
+            }
+        }
+        stats
+    }
+
+    /// Files without the extension
+    #[must_use]
+    pub const fn other_count(&self) -> usize {
+        self.all.count - self.matching.count
+    }
 }
 
-/// Write a DWORD value to the Windows registry
+/// Get statistics about the files below a directory matching `options`
 ///
 /// # Errors
 ///
-/// Returns error if registry key cannot be opened/created or value cannot be written
-pub fn write_reg_dword(hkey: HKEY, path: &str, value_name: &str, value: u32) -> Result<()> {
-    let (key, _) = RegKey::predef(hkey)
-        .create_subkey(path)
-        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{}: {}", path, e)))?;
+/// Returns error if directory cannot be read
+pub fn get_directory_stats(path: &Path, options: &ScanOptions) -> Result<DirectoryStats> {
+    Ok(walk_directory(path, options)?.collect())
+}
 
-    key.set_value(value_name, &value)
-        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{}: {}", value_name, e)))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_windows_arg() {
+        assert_eq!(quote_windows_arg("--report"), "--report");
+        assert_eq!(quote_windows_arg(""), r#""""#);
+        assert_eq!(quote_windows_arg(r"C:\Users\A B"), r#""C:\Users\A B""#);
+        assert_eq!(quote_windows_arg(r"C:\A B\"), r#""C:\A B\\""#);
+        assert_eq!(quote_windows_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(powershell_quote("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.pf", "CMD.EXE-0BD30981.pf"));
+        assert!(glob_match("*.PF", "cmd.exe-0bd30981.pf"));
+        assert!(glob_match("layout.ini", "Layout.ini"));
+        assert!(glob_match("Ag*.db", "AgAppLaunch.db"));
+        assert!(glob_match("?.lnk", "a.lnk"));
+        assert!(!glob_match("*.pf", "Layout.ini"));
+        assert!(!glob_match("?.lnk", "ab.lnk"));
+        assert!(glob_match("*a*b", "xaxxb"));
+    }
+
+    #[test]
+    fn test_directory_stats() {
+        let root =
+            std::env::temp_dir().join(format!("recent-enabler-stats-{}", std::process::id()));
+        let nested = root.join("AutomaticDestinations").join("deeper");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("a.lnk"), b"12345").unwrap();
+        std::fs::write(root.join("notes.txt"), b"skip").unwrap();
+        std::fs::write(
+            root.join("AutomaticDestinations")
+                .join("f01b4d95cf55d32a.automaticDestinations-ms"),
+            b"123",
+        )
+        .unwrap();
+        std::fs::write(nested.join("too-deep.lnk"), b"1").unwrap();
+
+        let options = ScanOptions::new(&["*.lnk", "*.automaticDestinations-ms"], 1);
+        let stats = get_directory_stats(&root, &options).unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.total_size, 8);
+        assert!(stats.modified.newest.is_some());
+        assert!(stats.unreadable.is_empty());
+
+        let top_level = get_directory_stats(&root, &ScanOptions::new(&["*.lnk"], 0)).unwrap();
+        assert_eq!(top_level.count, 1);
+
+        let deep = get_directory_stats(&root, &ScanOptions::new(&["*.lnk"], 2)).unwrap();
+        assert_eq!(deep.count, 2);
+
+        let missing = get_directory_stats(&root.join("missing"), &options).unwrap();
+        assert_eq!(missing, DirectoryStats::default());
+
+        let split = ExtensionStats::collect(walk_directory(&root, &options).unwrap(), "LNK");
+        assert_eq!(split.matching.count, 1);
+        assert_eq!(split.other_count(), 1);
+        assert_eq!(split.all.total_size, 8);
+        assert_eq!(
+            split.matching.modified.newest,
+            std::fs::metadata(root.join("a.lnk"))
+                .unwrap()
+                .modified()
+                .ok()
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -0,0 +1,110 @@
+use super::*;
+
+use winreg::{RegKey, HKEY};
+
+/// Get the last write time of an open registry key
+#[must_use]
+pub fn reg_key_last_write(key: &RegKey) -> Option<SystemTime> {
+    let info = key.query_info().ok()?;
+    let filetime = (u64::from(info.last_write_time.dwHighDateTime) << 32)
+        | u64::from(info.last_write_time.dwLowDateTime);
+    filetime_to_system_time(filetime)
+}
+
+pub fn read_reg_dword(hkey: HKEY, path: &str, value: &str) -> Option<u32> {
+    RegKey::predef(hkey)
+        .open_subkey(path)
+        .ok()
+        .and_then(|k| k.get_value(value).ok())
+}
+
+/// Read a string value from the Windows registry
+#[must_use]
+pub fn read_reg_string(hkey: HKEY, path: &str, value: &str) -> Option<String> {
+    RegKey::predef(hkey)
+        .open_subkey(path)
+        .ok()
+        .and_then(|k| k.get_value(value).ok())
+}
+
+/// Write a DWORD value to the Windows registry
+///
+/// # Errors
+///
+/// Returns error if registry key cannot be opened/created or value cannot be written
+pub fn write_reg_dword(hkey: HKEY, path: &str, value_name: &str, value: u32) -> Result<()> {
+    let (key, _) = RegKey::predef(hkey)
+        .create_subkey(path)
+        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{}: {}", path, e)))?;
+
+    key.set_value(value_name, &value)
+        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{}: {}", value_name, e)))
+}
+
+/// Write a string value to the Windows registry
+///
+/// # Errors
+///
+/// Returns error if registry key cannot be opened/created or value cannot be written
+pub fn write_reg_string(hkey: HKEY, path: &str, value_name: &str, value: &str) -> Result<()> {
+    let (key, _) = RegKey::predef(hkey)
+        .create_subkey(path)
+        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{path}: {e}")))?;
+
+    key.set_value(value_name, &value)
+        .map_err(|e| RecentEnablerError::RegistryWriteFailed(format!("{value_name}: {e}")))
+}
+
+/// Send standard output and error to the console of the launching shell
+///
+/// Release builds use the GUI subsystem and start without a console, so
+/// command-line modes print nothing until they attach. Does nothing when
+/// started from Explorer or when a console is already attached.
+pub fn attach_parent_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Read `TokenElevation` of the current process token
+pub(super) fn token_is_elevated() -> bool {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = HANDLE::default();
+
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &raw mut token).is_err() {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut return_length = 0u32;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some((&raw mut elevation).cast()),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &raw mut return_length,
+        );
+
+        let _ = CloseHandle(token);
+
+        result.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Current time minus `GetTickCount64` uptime
+pub(super) fn query_boot_time() -> Option<SystemTime> {
+    use windows::Win32::System::SystemInformation::GetTickCount64;
+
+    let uptime = Duration::from_millis(unsafe { GetTickCount64() });
+    SystemTime::now().checked_sub(uptime)
+}
@@ -0,0 +1,199 @@
+use crate::{
+    error::{RecentEnablerError, Result},
+    prefetch, recent, sysmain, system_restore, utils,
+};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+const PREFETCH_FILE_PATTERNS: &[&str] = &["*.pf"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowCopy {
+    /// Shadow copy GUID, e.g. `{6C8A3A5E-...}`
+    pub id: String,
+    pub created: Option<SystemTime>,
+    /// `\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopyN`
+    pub device_object: String,
+    /// Originating volume, `\\?\Volume{GUID}\`
+    pub volume_name: String,
+    /// Drive letter of the originating volume, e.g. `C:`
+    pub drive_letter: Option<String>,
+}
+
+impl ShadowCopy {
+    /// Translate a path on the originating volume into the snapshot
+    ///
+    /// Returns `None` for paths on other volumes or when the volume has no
+    /// drive letter.
+    #[must_use]
+    pub fn map_path(&self, path: &Path) -> Option<PathBuf> {
+        let drive = self.drive_letter.as_deref()?;
+        let path = path.to_str()?;
+        let rest = path
+            .get(..drive.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(drive))
+            .map(|_| &path[drive.len()..])?;
+        if !rest.is_empty() && !rest.starts_with('\\') {
+            return None;
+        }
+        Some(PathBuf::from(format!(
+            "{}{rest}",
+            self.device_object.trim_end_matches('\\')
+        )))
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawShadowCopy {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(default)]
+    install_date: String,
+    device_object: String,
+    #[serde(default)]
+    volume_name: String,
+    #[serde(default)]
+    drive_letter: Option<String>,
+}
+
+/// Parse the shadow copy listing produced by `SHADOW_COPIES_COMMAND`
+///
+/// # Errors
+///
+/// Returns error if the output is not valid JSON
+pub fn parse_shadow_copies(json: &str) -> Result<Vec<ShadowCopy>> {
+    let raw: Vec<RawShadowCopy> = utils::parse_json_list(json)
+        .map_err(|e| RecentEnablerError::ShadowCopyReadFailed(e.to_string()))?;
+
+    let mut snapshots: Vec<_> = raw
+        .into_iter()
+        .map(|copy| ShadowCopy {
+            id: copy.id,
+            created: system_restore::parse_wmi_datetime(&copy.install_date),
+            device_object: copy.device_object,
+            volume_name: copy.volume_name,
+            drive_letter: copy.drive_letter.filter(|letter| !letter.is_empty()),
+        })
+        .collect();
+    snapshots.sort_by_key(|copy| copy.created);
+    Ok(snapshots)
+}
+
+/// Contents of a file its owner keeps open, and where they were read from
+pub struct LockedFile {
+    pub data: Vec<u8>,
+    /// Shadow copy the data came from, `None` if the live file could be read
+    pub snapshot: Option<ShadowCopy>,
+}
+
+/// Recent and Prefetch contents of one snapshot
+pub struct SnapshotArtifacts {
+    pub snapshot: ShadowCopy,
+    pub recent: Result<recent::RecentInfo>,
+    pub prefetch: Result<sysmain::PrefetchInfo>,
+    /// Prefetch files that parsed successfully
+    pub prefetch_files: Vec<prefetch::PrefetchFile>,
+}
+
+fn map_folder(snapshot: &ShadowCopy, folder: &Path) -> Result<PathBuf> {
+    snapshot.map_path(folder).ok_or_else(|| {
+        RecentEnablerError::ShadowCopyReadFailed(format!(
+            "{} is not on the snapshot volume {}",
+            folder.display(),
+            snapshot
+                .drive_letter
+                .as_deref()
+                .unwrap_or(&snapshot.volume_name)
+        ))
+    })
+}
+
+/// Run the Recent and Prefetch statistics and the Prefetch parser inside `snapshot`
+///
+/// `recent_folder` and `prefetch_folder` are live paths; they are mapped
+/// into the snapshot with [`ShadowCopy::map_path`].
+#[must_use]
+pub fn read_snapshot(
+    snapshot: ShadowCopy,
+    recent_folder: &Path,
+    prefetch_folder: &Path,
+) -> SnapshotArtifacts {
+    let recent =
+        map_folder(&snapshot, recent_folder).and_then(|folder| recent::get_recent_info_at(&folder));
+
+    let prefetch_path = map_folder(&snapshot, prefetch_folder);
+    let prefetch = prefetch_path
+        .clone()
+        .and_then(|folder| sysmain::get_prefetch_info_at(&folder));
+
+    let prefetch_files = prefetch_path
+        .ok()
+        .and_then(|folder| {
+            utils::walk_directory(&folder, &utils::ScanOptions::new(PREFETCH_FILE_PATTERNS, 0)).ok()
+        })
+        .into_iter()
+        .flatten()
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| prefetch::read_prefetch(&entry.path).ok())
+        .collect();
+
+    SnapshotArtifacts {
+        snapshot,
+        recent,
+        prefetch,
+        prefetch_files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> ShadowCopy {
+        ShadowCopy {
+            id: "{6C8A3A5E-1D2B-4C3D-9E4F-5A6B7C8D9E0F}".to_string(),
+            created: None,
+            device_object: r"\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy3".to_string(),
+            volume_name: r"\\?\Volume{0f5c2b1e-0000-0000-0000-100000000000}\".to_string(),
+            drive_letter: Some("C:".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_shadow_copies() {
+        let json = r#"[{"ID":"{B}","InstallDate":"20231005090000.000000+000","DeviceObject":"\\\\?\\GLOBALROOT\\Device\\HarddiskVolumeShadowCopy2","VolumeName":"\\\\?\\Volume{1}\\","DriveLetter":"C:"},
+                       {"ID":"{A}","InstallDate":"20230930133337.000000+000","DeviceObject":"\\\\?\\GLOBALROOT\\Device\\HarddiskVolumeShadowCopy1","VolumeName":"\\\\?\\Volume{2}\\","DriveLetter":null}]"#;
+        let snapshots = parse_shadow_copies(json).unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, "{A}");
+        assert_eq!(snapshots[0].drive_letter, None);
+        assert_eq!(
+            snapshots[1].device_object,
+            r"\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy2"
+        );
+        assert_eq!(snapshots[1].drive_letter.as_deref(), Some("C:"));
+        assert!(snapshots[0].created < snapshots[1].created);
+        assert!(parse_shadow_copies("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_map_path() {
+        let snapshot = snapshot();
+        assert_eq!(
+            snapshot.map_path(Path::new(r"c:\Windows\Prefetch")),
+            Some(PathBuf::from(
+                r"\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy3\Windows\Prefetch"
+            ))
+        );
+        assert_eq!(snapshot.map_path(Path::new(r"D:\Users")), None);
+        assert_eq!(snapshot.map_path(Path::new(r"C:x")), None);
+        assert_eq!(snapshot.map_path(Path::new(r"\\server\share\Recent")), None);
+    }
+}
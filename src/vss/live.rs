@@ -0,0 +1,93 @@
+use super::*;
+
+use std::io::Read;
+
+/// Lists shadow copies with the drive letter of the volume they were taken of
+const SHADOW_COPIES_COMMAND: &str = "$letters = @{}; \
+     Get-WmiObject Win32_Volume | ForEach-Object { $letters[$_.DeviceID] = $_.DriveLetter }; \
+     Get-WmiObject Win32_ShadowCopy | \
+     Select-Object ID,InstallDate,DeviceObject,VolumeName,@{n='DriveLetter';e={$letters[$_.VolumeName]}} | \
+     ConvertTo-Json -Compress";
+
+/// List shadow copies on all volumes, oldest first
+///
+/// # Errors
+///
+/// Returns error if `PowerShell` fails (listing requires administrator rights)
+pub fn list_shadow_copies() -> Result<Vec<ShadowCopy>> {
+    let output = utils::hidden_command("powershell")
+        .args(["-NoProfile", "-Command", SHADOW_COPIES_COMMAND])
+        .output()
+        .map_err(|e| {
+            RecentEnablerError::ShadowCopyReadFailed(format!(
+                "Failed to execute PowerShell command: {e}"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(RecentEnablerError::ShadowCopyReadFailed(
+            utils::command_error(&output),
+        ));
+    }
+    parse_shadow_copies(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn read_for_backup(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    crate::token::with_backup_semantics(|| utils::open_for_backup(path)?.read_to_end(&mut data))?;
+    Ok(data)
+}
+
+/// Read a file that may be held open exclusively, such as `SRUDB.dat` or `Amcache.hve`
+///
+/// The live file is tried first with backup semantics. If that fails, usually
+/// with a sharing violation, the copy in the newest shadow copy of its volume
+/// is read instead.
+///
+/// # Errors
+///
+/// Returns `on_error` describing both failures if neither copy can be read
+pub fn read_locked_file(
+    path: &Path,
+    on_error: fn(String) -> RecentEnablerError,
+) -> Result<LockedFile> {
+    let live_error = match read_for_backup(path) {
+        Ok(data) => {
+            return Ok(LockedFile {
+                data,
+                snapshot: None,
+            })
+        }
+        Err(e) => e,
+    };
+    let failed = |reason: String| on_error(format!("{}: {live_error}; {reason}", path.display()));
+
+    let (snapshot, snapshot_path) = list_shadow_copies()
+        .map_err(|e| failed(e.to_string()))?
+        .into_iter()
+        .rev()
+        .find_map(|snapshot| {
+            let mapped = snapshot.map_path(path)?;
+            Some((snapshot, mapped))
+        })
+        .ok_or_else(|| failed("no shadow copy of its volume".to_string()))?;
+    let data = read_for_backup(&snapshot_path)
+        .map_err(|e| failed(format!("{}: {e}", snapshot_path.display())))?;
+    Ok(LockedFile {
+        data,
+        snapshot: Some(snapshot),
+    })
+}
+
+/// Read Recent and Prefetch from every shadow copy, oldest first
+///
+/// # Errors
+///
+/// Returns error if shadow copies or the live folder locations cannot be determined
+pub fn read_snapshots() -> Result<Vec<SnapshotArtifacts>> {
+    let recent_folder = recent::get_recent_folder()?;
+    let prefetch_folder = sysmain::get_prefetch_folder()?;
+    Ok(list_shadow_copies()?
+        .into_iter()
+        .map(|snapshot| read_snapshot(snapshot, &recent_folder, &prefetch_folder))
+        .collect())
+}
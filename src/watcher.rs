@@ -0,0 +1,231 @@
+use crate::{
+    error::{RecentEnablerError, Result},
+    recent, utils,
+};
+use notify::event::{EventKind, ModifyKind, RenameMode};
+use notify::{RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "live")]
+pub use live::*;
+
+/// Prefetch trace files; `Layout.ini` and the databases change on their own schedule
+const PREFETCH_WATCH_PATTERNS: &[&str] = &["*.pf"];
+
+/// Folder whose changes are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WatchedFolder {
+    Recent,
+    Prefetch,
+}
+
+impl WatchedFolder {
+    /// File names reported for this folder
+    #[must_use]
+    pub fn patterns(self) -> &'static [&'static str] {
+        match self {
+            Self::Recent => recent::RECENT_PATTERNS,
+            Self::Prefetch => PREFETCH_WATCH_PATTERNS,
+        }
+    }
+
+    /// Jump lists live in subfolders of Recent, Prefetch is flat
+    fn recursive_mode(self) -> RecursiveMode {
+        match self {
+            Self::Recent => RecursiveMode::Recursive,
+            Self::Prefetch => RecursiveMode::NonRecursive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    #[must_use]
+    pub fn as_russian(self) -> &'static str {
+        match self {
+            Self::Created => "создан",
+            Self::Modified => "изменён",
+            Self::Removed => "удалён",
+        }
+    }
+}
+
+/// A tracked file created, modified or deleted in a watched folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderChange {
+    pub folder: WatchedFolder,
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+}
+
+impl FolderChange {
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+    }
+}
+
+/// Turn a raw notification into changes of files matching the folder's patterns
+///
+/// Renames are reported as removal of the old name and creation of the new one;
+/// access-only notifications are dropped.
+#[must_use]
+pub fn classify(folder: WatchedFolder, event: &notify::Event) -> Vec<FolderChange> {
+    let kinds: Vec<ChangeKind> = match event.kind {
+        EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+            vec![ChangeKind::Created]
+        }
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            vec![ChangeKind::Removed]
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            vec![ChangeKind::Removed, ChangeKind::Created]
+        }
+        EventKind::Modify(_) => vec![ChangeKind::Modified],
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => return Vec::new(),
+    };
+
+    let options = utils::ScanOptions::new(folder.patterns(), 0);
+    event
+        .paths
+        .iter()
+        .enumerate()
+        .filter_map(|(i, path)| {
+            let name = path.file_name()?.to_str()?;
+            options.matches(name).then(|| FolderChange {
+                folder,
+                kind: kinds[i.min(kinds.len() - 1)],
+                path: path.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Delivers [`FolderChange`]s to a handler until dropped
+pub struct FolderWatcher {
+    watcher: notify::RecommendedWatcher,
+    /// Watched roots, shared with the handler to tell which folder an event belongs to
+    folders: Arc<Mutex<Vec<(WatchedFolder, PathBuf)>>>,
+}
+
+impl FolderWatcher {
+    /// Create a watcher that calls `handler` from a background thread
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the platform notification API cannot be initialized
+    pub fn new(mut handler: impl FnMut(FolderChange) + Send + 'static) -> Result<Self> {
+        let folders: Arc<Mutex<Vec<(WatchedFolder, PathBuf)>>> = Arc::default();
+        let watched = Arc::clone(&folders);
+
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let Some(folder) = event.paths.first().and_then(|path| {
+                let watched = watched.lock().ok()?;
+                watched
+                    .iter()
+                    .filter(|(_, root)| path.starts_with(root))
+                    .max_by_key(|(_, root)| root.as_os_str().len())
+                    .map(|(folder, _)| *folder)
+            }) else {
+                return;
+            };
+            for change in classify(folder, &event) {
+                handler(change);
+            }
+        })
+        .map_err(|e| RecentEnablerError::WatchFailed(e.to_string()))?;
+
+        Ok(Self { watcher, folders })
+    }
+
+    /// Start reporting changes in `path` as changes of `folder`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the folder does not exist or cannot be watched
+    pub fn watch(&mut self, folder: WatchedFolder, path: &Path) -> Result {
+        self.watcher
+            .watch(path, folder.recursive_mode())
+            .map_err(|e| RecentEnablerError::WatchFailed(format!("{}: {e}", path.display())))?;
+        if let Ok(mut folders) = self.folders.lock() {
+            folders.push((folder, path.to_path_buf()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&str]) -> notify::Event {
+        paths.iter().fold(notify::Event::new(kind), |event, path| {
+            event.add_path(PathBuf::from(path))
+        })
+    }
+
+    #[test]
+    fn test_classify() {
+        let created = classify(
+            WatchedFolder::Recent,
+            &event(
+                EventKind::Create(CreateKind::File),
+                &["Recent/report.docx.lnk"],
+            ),
+        );
+        assert_eq!(
+            created,
+            vec![FolderChange {
+                folder: WatchedFolder::Recent,
+                kind: ChangeKind::Created,
+                path: PathBuf::from("Recent/report.docx.lnk"),
+            }]
+        );
+        assert_eq!(created[0].file_name(), "report.docx.lnk");
+
+        let modified = classify(
+            WatchedFolder::Prefetch,
+            &event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &["Prefetch/NOTEPAD.EXE-D8414F97.pf", "Prefetch/Layout.ini"],
+            ),
+        );
+        assert_eq!(modified.len(), 1);
+        assert_eq!(modified[0].kind, ChangeKind::Modified);
+
+        assert!(classify(
+            WatchedFolder::Recent,
+            &event(EventKind::Remove(RemoveKind::File), &["Recent/notes.txt"]),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_classify_rename() {
+        let changes = classify(
+            WatchedFolder::Recent,
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["Recent/old.lnk", "Recent/new.lnk"],
+            ),
+        );
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChangeKind::Removed, ChangeKind::Created]);
+        assert_eq!(changes[1].file_name(), "new.lnk");
+    }
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+use crate::sysmain;
+
+/// Watch the live Recent and Prefetch folders
+///
+/// Folders that cannot be watched (Prefetch needs administrator rights) are
+/// returned as errors next to the watcher instead of failing the whole call.
+///
+/// # Errors
+///
+/// Returns error if the watcher itself cannot be created
+pub fn watch_folders(
+    handler: impl FnMut(FolderChange) + Send + 'static,
+) -> Result<(FolderWatcher, Vec<(WatchedFolder, RecentEnablerError)>)> {
+    let mut watcher = FolderWatcher::new(handler)?;
+    let mut errors = Vec::new();
+
+    for (folder, path) in [
+        (WatchedFolder::Recent, recent::get_recent_folder()),
+        (WatchedFolder::Prefetch, sysmain::get_prefetch_folder()),
+    ] {
+        if let Err(e) = path.and_then(|path| watcher.watch(folder, &path)) {
+            errors.push((folder, e));
+        }
+    }
+
+    Ok((watcher, errors))
+}
@@ -0,0 +1,253 @@
+//! LZ77+Huffman ("Xpress Huffman") decompression as described in MS-XCA
+//!
+//! Windows 10 compresses Prefetch files with this format inside a `MAM`
+//! container. The decoder is bounds-checked: it returns `None` on corrupt
+//! input rather than panicking, and never produces more than the declared
+//! size, which is itself capped at [`MAX_UNCOMPRESSED_SIZE`].
+
+use crate::binary;
+
+/// Largest output accepted; real Prefetch files are a few hundred KiB
+pub const MAX_UNCOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Uncompressed bytes covered by one Huffman table
+const CHUNK_SIZE: usize = 65_536;
+/// Size of the table of 512 4-bit code lengths that starts every chunk
+const TABLE_SIZE: usize = 256;
+const SYMBOL_COUNT: usize = 512;
+const MAX_CODE_LENGTH: u32 = 15;
+
+/// Canonical Huffman decoding table indexed by the next 15 bits
+struct DecodingTable {
+    symbols: Vec<u16>,
+    lengths: [u8; SYMBOL_COUNT],
+}
+
+impl DecodingTable {
+    fn build(table: &[u8]) -> Option<Self> {
+        let mut lengths = [0u8; SYMBOL_COUNT];
+        for (i, &byte) in table.get(..TABLE_SIZE)?.iter().enumerate() {
+            lengths[i * 2] = byte & 0x0F;
+            lengths[i * 2 + 1] = byte >> 4;
+        }
+
+        let mut symbols = Vec::with_capacity(1 << MAX_CODE_LENGTH);
+        for length in 1..=MAX_CODE_LENGTH {
+            for (symbol, _) in lengths
+                .iter()
+                .enumerate()
+                .filter(|&(_, &l)| u32::from(l) == length)
+            {
+                let entries = 1usize << (MAX_CODE_LENGTH - length);
+                if symbols.len() + entries > 1 << MAX_CODE_LENGTH {
+                    return None;
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                symbols.extend(std::iter::repeat_n(symbol as u16, entries));
+            }
+        }
+
+        (symbols.len() == 1 << MAX_CODE_LENGTH).then_some(Self { symbols, lengths })
+    }
+}
+
+/// 32-bit window over the 16-bit little-endian words of a chunk
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bits: u32,
+    /// Buffered bits beyond the 16 that are always available
+    extra: i32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8], position: usize) -> Self {
+        let high = binary::read_u16(data, position).unwrap_or(0);
+        let low = binary::read_u16(data, position + 2).unwrap_or(0);
+        Self {
+            data,
+            position: position + 4,
+            bits: (u32::from(high) << 16) | u32::from(low),
+            extra: 16,
+        }
+    }
+
+    fn peek(&self, count: u32) -> u32 {
+        if count == 0 {
+            0
+        } else {
+            self.bits >> (32 - count)
+        }
+    }
+
+    fn skip(&mut self, count: u32) {
+        self.bits = self.bits.checked_shl(count).unwrap_or(0);
+        #[allow(clippy::cast_possible_wrap)]
+        {
+            self.extra -= count as i32;
+        }
+        if self.extra < 0 {
+            let word = binary::read_u16(self.data, self.position).unwrap_or(0);
+            #[allow(clippy::cast_sign_loss)]
+            {
+                self.bits |= u32::from(word) << (-self.extra) as u32;
+            }
+            self.extra += 16;
+            self.position += 2;
+        }
+    }
+
+    fn read_byte(&mut self) -> Option<u8> {
+        let value = binary::read_u8(self.data, self.position)?;
+        self.position += 1;
+        Some(value)
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let value = binary::read_u16(self.data, self.position)?;
+        self.position += 2;
+        Some(value)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let value = binary::read_u32(self.data, self.position)?;
+        self.position += 4;
+        Some(value)
+    }
+}
+
+/// Decompress an Xpress Huffman stream of known uncompressed size
+///
+/// Returns `None` if `uncompressed_size` exceeds [`MAX_UNCOMPRESSED_SIZE`] or a
+/// match would run past it.
+#[must_use]
+pub fn decompress(data: &[u8], uncompressed_size: usize) -> Option<Vec<u8>> {
+    if uncompressed_size > MAX_UNCOMPRESSED_SIZE {
+        return None;
+    }
+    let mut output = Vec::with_capacity(uncompressed_size);
+    let mut position = 0;
+
+    while output.len() < uncompressed_size {
+        let table = DecodingTable::build(data.get(position..)?)?;
+        let mut reader = BitReader::new(data, position + TABLE_SIZE);
+        let chunk_end = output.len() + CHUNK_SIZE;
+
+        while output.len() < chunk_end && output.len() < uncompressed_size {
+            let symbol = table.symbols[reader.peek(MAX_CODE_LENGTH) as usize];
+            reader.skip(u32::from(table.lengths[usize::from(symbol)]));
+
+            if symbol < 256 {
+                #[allow(clippy::cast_possible_truncation)]
+                output.push(symbol as u8);
+                continue;
+            }
+
+            let symbol = symbol - 256;
+            let mut length = usize::from(symbol & 0x0F);
+            let offset_bits = u32::from(symbol >> 4);
+
+            if length == 15 {
+                length = usize::from(reader.read_byte()?);
+                if length == 255 {
+                    length = usize::from(reader.read_u16()?);
+                    if length == 0 {
+                        length = reader.read_u32()? as usize;
+                    }
+                    length = length.checked_sub(15)?;
+                }
+                length += 15;
+            }
+            length += 3;
+
+            let offset = (reader.peek(offset_bits) | (1 << offset_bits)) as usize;
+            reader.skip(offset_bits);
+
+            if length > uncompressed_size - output.len() {
+                return None;
+            }
+            let start = output.len().checked_sub(offset)?;
+            for i in 0..length {
+                output.push(output[start + i]);
+            }
+        }
+
+        position = reader.position;
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode symbols with a flat table where every code is 9 bits long
+    ///
+    /// With all 512 lengths equal, each symbol's canonical code is its own value.
+    fn encode_flat(symbols: &[(u16, u32, u32)]) -> Vec<u8> {
+        let mut bits: Vec<bool> = Vec::new();
+        for &(symbol, extra, extra_bits) in symbols {
+            bits.extend((0..9).rev().map(|i| symbol >> i & 1 == 1));
+            bits.extend((0..extra_bits).rev().map(|i| extra >> i & 1 == 1));
+        }
+        while !bits.len().is_multiple_of(16) || bits.len() < 32 {
+            bits.push(false);
+        }
+        // Padding word so the decoder can refill past the last symbol
+        bits.extend([false; 16]);
+
+        let mut data = vec![0x99u8; TABLE_SIZE];
+        for word in bits.chunks(16) {
+            let value = word.iter().fold(0u16, |acc, &b| (acc << 1) | u16::from(b));
+            data.extend(value.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_decompress_literals_and_match() {
+        // "abc" then a match of length 6 at offset 3 (symbol 256 + (1 << 4) + 3)
+        // Offset 3 = (1 << 1) | 1, so one extra offset bit with value 1
+        let data = encode_flat(&[
+            (u16::from(b'a'), 0, 0),
+            (u16::from(b'b'), 0, 0),
+            (u16::from(b'c'), 0, 0),
+            (256 + (1 << 4) + 3, 1, 1),
+        ]);
+        assert_eq!(decompress(&data, 9).unwrap(), b"abcabcabc");
+    }
+
+    #[test]
+    fn test_decompress_rejects_invalid() {
+        // All-zero table lengths can't form a complete code
+        assert_eq!(decompress(&[0u8; 300], 10), None);
+
+        // Match pointing before the start of the output
+        let data = encode_flat(&[(256 + (2 << 4), 0, 2)]);
+        assert_eq!(decompress(&data, 3), None);
+
+        // Match running past the declared size
+        let data = encode_flat(&[
+            (u16::from(b'a'), 0, 0),
+            (u16::from(b'b'), 0, 0),
+            (u16::from(b'c'), 0, 0),
+            (256 + (1 << 4) + 3, 1, 1),
+        ]);
+        assert_eq!(decompress(&data, 5), None);
+
+        // Declared size above the cap is refused before allocating
+        assert_eq!(decompress(&data, MAX_UNCOMPRESSED_SIZE + 1), None);
+    }
+
+    #[test]
+    fn test_decompress_rejects_oversized_match_length() {
+        // "a" then a length-15 match at offset 1 whose length is extended through
+        // the 0xFF byte and zero u16 escapes to a 32-bit length of ~4 GiB
+        let mut data = encode_flat(&[(u16::from(b'a'), 0, 0), (256 + 15, 0, 0)]);
+        data.extend([0xFF, 0x00, 0x00]);
+        data.extend(u32::MAX.to_le_bytes());
+        data.extend([0u8; 4]);
+        assert_eq!(decompress(&data, 16), None);
+    }
+}
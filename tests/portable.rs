@@ -0,0 +1,166 @@
+//! Checks for the half of the library that runs on any host: command output
+//! parsers and report/timeline output. Run on Linux with
+//! `cargo test --no-default-features`.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use recent_enabler::error::RecentEnablerError;
+use recent_enabler::report::{HostInfo, ReadinessReport};
+use recent_enabler::timeline::{self, EventSource, TimelineEvent, TimestampKind};
+use recent_enabler::{audit_policy, srum, status, usn_journal, vss};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn test_command_output_parsers() {
+    let csv = std::fs::read_to_string(fixture("auditpol/get_all_en.csv")).unwrap();
+    let policies = audit_policy::parse_auditpol_csv(&csv).unwrap();
+    assert!(policies.iter().any(|p| p.name == "Logon"));
+
+    let output = std::fs::read_to_string(fixture("fsutil/queryjournal_en.txt")).unwrap();
+    let journal = usn_journal::parse_queryjournal(&output).unwrap();
+    assert_eq!(journal.journal_id, 0x01d9_f3a2_b7c4_e5d6);
+
+    let snapshots = vss::parse_shadow_copies(
+        r#"{"ID":"{A}","InstallDate":"20230930133337.000000+000","DeviceObject":"\\\\?\\GLOBALROOT\\Device\\HarddiskVolumeShadowCopy1","VolumeName":"\\\\?\\Volume{1}\\","DriveLetter":"C:"}"#,
+    )
+    .unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].drive_letter.as_deref(), Some("C:"));
+}
+
+/// `tests/fixtures/srum/SRUDB.dat` is a synthetic database with the SRUM
+/// tables, written with 32 KiB pages like the real one (see `srum::tests`)
+#[test]
+fn test_srudb_fixture() {
+    let data = srum::read_srum(&fixture("srum/SRUDB.dat")).unwrap();
+
+    assert_eq!(data.app_usage.len(), 1);
+    let app = &data.app_usage[0];
+    // Stored 7-bit compressed
+    assert_eq!(app.app, r"\device\harddiskvolume3\tools\nc.exe");
+    assert_eq!(app.user.as_deref(), Some("S-1-5-21-1-2-3-1001"));
+    assert_eq!(app.foreground_cycle_time, 5000);
+    assert_eq!(app.face_time, 60);
+
+    assert_eq!(data.network_usage.len(), 3);
+    assert_eq!(data.network_usage[0].bytes_sent, 1_048_576);
+    assert_eq!(data.network_usage[1].app, "#99");
+    assert_eq!(data.unreadable_names, 0);
+    assert_eq!(
+        data.newest_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(1_609_502_400))
+    );
+
+    assert!(srum::read_srum(&fixture("srum/missing.dat")).is_err());
+}
+
+/// Decode a real `SRUDB.dat` copied off a Windows machine (e.g. with `--collect`
+/// or from a shadow copy). Databases carry user activity, so none is checked in:
+/// `RECENT_ENABLER_SRUDB=path/to/SRUDB.dat cargo test -- --ignored`
+#[test]
+#[ignore = "needs a real SRUDB.dat in RECENT_ENABLER_SRUDB"]
+fn test_real_srudb() {
+    let path = std::env::var_os("RECENT_ENABLER_SRUDB").expect("RECENT_ENABLER_SRUDB is not set");
+    let data = srum::read_srum(Path::new(&path)).unwrap();
+
+    assert!(!data.app_usage.is_empty());
+    assert!(data.app_usage.iter().all(|e| e.timestamp.is_some()));
+    assert!(data.newest_time().is_some());
+    // Names that can't be decoded are counted rather than guessed
+    let unresolved = data
+        .app_usage
+        .iter()
+        .filter(|e| e.app.starts_with('#'))
+        .count();
+    assert!(
+        unresolved < data.app_usage.len(),
+        "{unresolved} unresolved apps, {} unreadable names",
+        data.unreadable_names
+    );
+}
+
+#[test]
+fn test_report_from_offline_results() {
+    let host = HostInfo {
+        computer_name: "ANALYST-<01>".to_string(),
+        user_name: "analyst".to_string(),
+        os_version: "Windows 11".to_string(),
+        is_admin: false,
+    };
+    let mut report = ReadinessReport::new(host, SystemTime::now());
+    report
+        .add(
+            "Точки восстановления",
+            &Ok(status::SystemRestoreStatus { is_enabled: true }),
+        )
+        .add(
+            "Время последнего доступа",
+            &Ok(status::LastAccessStatus {
+                is_disabled: true,
+                mode: "User Managed, Disabled".to_string(),
+                system_managed: false,
+                value: Some(1),
+                reboot_required: false,
+            }),
+        )
+        .add::<status::UsnJournalStatus>(
+            "Журнал USN",
+            &Err(RecentEnablerError::UsnJournalReadFailed("C:".to_string())),
+        );
+    assert_eq!(report.passed_count(), 1);
+
+    let path = std::env::temp_dir().join(format!("portable-report-{}.html", std::process::id()));
+    report.write_html(&path).unwrap();
+    let html = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(html.contains("ANALYST-&lt;01&gt;"));
+    assert!(html.contains("Время последнего доступа"));
+}
+
+#[test]
+fn test_timeline_writers() {
+    let events = [TimelineEvent::new(
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        TimestampKind::LastRun,
+        EventSource::Prefetch,
+        "NOTEPAD.EXE",
+    )
+    .with_path(r"C:\Windows\Prefetch\NOTEPAD.EXE-D8414F97.pf")];
+
+    let mut body = Vec::new();
+    timeline::write_bodyfile(&events, &mut body).unwrap();
+    assert!(String::from_utf8(body).unwrap().contains("1700000000"));
+
+    let mut csv = Vec::new();
+    timeline::write_l2t_csv(&events, "HOST", &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 2);
+
+    let mut jsonl = Vec::new();
+    timeline::write_timesketch_jsonl(&events, &mut jsonl).unwrap();
+    let record: serde_json::Value = serde_json::from_slice(&jsonl).unwrap();
+    assert_eq!(record["timestamp_desc"], "Last Time Executed");
+}
+
+#[test]
+fn test_error_round_trip() {
+    let error = RecentEnablerError::ShimCacheParseFailed("truncated".to_string());
+    let json = serde_json::to_string(&error).unwrap();
+    let decoded: RecentEnablerError = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.to_string(), error.to_string());
+    assert!(!decoded.to_russian().is_empty());
+}
+
+#[cfg(not(feature = "live"))]
+#[test]
+fn test_live_queries_without_live_feature() {
+    assert!(!recent_enabler::utils::is_admin());
+    assert!(recent_enabler::utils::boot_time().is_none());
+    assert!(recent_enabler::token::TokenInfo::current().is_err());
+}